@@ -1,12 +1,13 @@
-use crate::{spec::BinarySubtype, Document, RawBinaryRef};
+use crate::{spec::BinarySubtype, Document, RawBinaryRef, RawDocument};
 use std::{
     convert::TryFrom,
     error,
     fmt::{self, Display},
+    io,
 };
 
 /// Represents a BSON binary value.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Binary {
     /// The subtype of the bytes.
     pub subtype: BinarySubtype,
@@ -90,6 +91,136 @@ impl Binary {
             subtype: self.subtype,
         }
     }
+
+    /// Returns the bytes as a fixed-size array if there are exactly `N` of them, or [`None`]
+    /// otherwise. This is convenient for reading fixed-width values such as UUIDs, hashes, or keys.
+    ///
+    /// ```
+    /// use bson::{spec::BinarySubtype, Binary};
+    ///
+    /// let binary = Binary { subtype: BinarySubtype::Uuid, bytes: vec![7; 16] };
+    /// assert_eq!(binary.as_fixed::<16>(), Some([7; 16]));
+    /// assert_eq!(binary.as_fixed::<12>(), None);
+    /// ```
+    pub fn as_fixed<const N: usize>(&self) -> Option<[u8; N]> {
+        <[u8; N]>::try_from(self.bytes.as_slice()).ok()
+    }
+
+    /// Borrow the contents as a [`RawDocument`], for binaries whose bytes are themselves an
+    /// encoded BSON document.
+    ///
+    /// The bytes are fully validated (see [`RawDocument::from_bytes_validated`]) before the
+    /// reference is returned, so element access on the result will not encounter malformed data.
+    ///
+    /// ```
+    /// use bson::{doc, spec::BinarySubtype, Binary};
+    ///
+    /// let binary = Binary {
+    ///     subtype: BinarySubtype::Generic,
+    ///     bytes: bson::to_vec(&doc! { "x": 1 })?,
+    /// };
+    /// assert_eq!(binary.as_raw_document()?.get_i32("x")?, 1);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn as_raw_document(&self) -> crate::raw::Result<&RawDocument> {
+        RawDocument::from_bytes_validated(&self.bytes)
+    }
+
+    /// Creates a [`BinarySubtype::Encrypted`] binary holding a client-side field level encryption
+    /// payload: the `blob_subtype` byte followed by the `ciphertext`.
+    ///
+    /// ```
+    /// use bson::{spec::BinarySubtype, Binary};
+    ///
+    /// let binary = Binary::from_encrypted(6, b"ciphertext");
+    /// assert_eq!(binary.subtype, BinarySubtype::Encrypted);
+    /// assert_eq!(binary.as_encrypted(), Some((6, &b"ciphertext"[..])));
+    /// ```
+    pub fn from_encrypted(blob_subtype: u8, ciphertext: &[u8]) -> Self {
+        let mut bytes = Vec::with_capacity(1 + ciphertext.len());
+        bytes.push(blob_subtype);
+        bytes.extend_from_slice(ciphertext);
+        Self {
+            subtype: BinarySubtype::Encrypted,
+            bytes,
+        }
+    }
+
+    /// Splits a [`BinarySubtype::Encrypted`] binary into its leading blob subtype byte and the
+    /// remaining ciphertext. Returns [`None`] if the binary has a different subtype or is empty.
+    pub fn as_encrypted(&self) -> Option<(u8, &[u8])> {
+        if self.subtype != BinarySubtype::Encrypted {
+            return None;
+        }
+        let (blob_subtype, ciphertext) = self.bytes.split_first()?;
+        Some((*blob_subtype, ciphertext))
+    }
+}
+
+/// A [`Write`](std::io::Write) implementation that accumulates bytes into a [`Binary`].
+///
+/// This allows data to be written directly into a BSON binary value by any API that accepts a
+/// writer.
+///
+/// ```rust
+/// use std::io::Write;
+/// use bson::{binary::BinaryWriter, spec::BinarySubtype};
+///
+/// let mut writer = BinaryWriter::new();
+/// writer.write_all(b"hello ")?;
+/// write!(writer, "{}", "world")?;
+///
+/// let binary = writer.into_binary(BinarySubtype::Generic);
+/// assert_eq!(binary.bytes, b"hello world");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BinaryWriter {
+    bytes: Vec<u8>,
+}
+
+impl BinaryWriter {
+    /// Creates a new, empty [`BinaryWriter`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty [`BinaryWriter`] with at least the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bytes: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// The bytes written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the writer, returning a [`Binary`] of the given subtype containing the bytes
+    /// written.
+    pub fn into_binary(self, subtype: BinarySubtype) -> Binary {
+        Binary {
+            subtype,
+            bytes: self.bytes,
+        }
+    }
+}
+
+impl io::Write for BinaryWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.bytes.extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 /// Possible errors that can arise during [`Binary`] construction.
@@ -98,6 +229,11 @@ impl Binary {
 pub enum Error {
     /// While trying to decode from base64, an error was returned.
     DecodingError { message: String },
+
+    /// A user-defined binary subtype was requested with a value outside of the user-defined range
+    /// (`0x80` through `0xFF`).
+    #[non_exhaustive]
+    InvalidUserDefinedSubtype { subtype: u8 },
 }
 
 impl error::Error for Error {}
@@ -106,6 +242,11 @@ impl std::fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Error::DecodingError { message: m } => fmt.write_str(m),
+            Error::InvalidUserDefinedSubtype { subtype } => write!(
+                fmt,
+                "binary subtype {:#x} is not in the user-defined range",
+                subtype
+            ),
         }
     }
 }
@@ -22,21 +22,29 @@
 //! BSON definition
 
 use std::{
+    cmp::Ordering,
     convert::{TryFrom, TryInto},
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
 };
 
 use serde_json::{json, Value};
 
 pub use crate::document::Document;
 use crate::{
+    decimal128::DecimalValue,
     oid::{self, ObjectId},
     spec::{BinarySubtype, ElementType},
     Binary,
     Decimal128,
+    RawDocument,
 };
 
 /// Possible BSON value types.
+///
+/// [`Bson`] implements [`Eq`] and [`Hash`] so that values can be used as map keys. Because
+/// `f64::NAN` is not equal to itself, values containing a NaN [`Bson::Double`] do not satisfy the
+/// reflexivity required by [`Eq`] and should not be used as keys.
 #[derive(Clone, Default, PartialEq)]
 pub enum Bson {
     /// 64-bit binary floating point
@@ -87,6 +95,185 @@ pub enum Bson {
 /// Alias for `Vec<Bson>`.
 pub type Array = Vec<Bson>;
 
+impl Eq for Bson {}
+
+impl Hash for Bson {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Bson::Double(d) => {
+                // 0.0 and -0.0 compare equal, so they must hash the same; NaN is never equal to
+                // anything, but is hashed to a single canonical value for consistency.
+                let bits = if *d == 0.0 {
+                    0.0f64.to_bits()
+                } else if d.is_nan() {
+                    f64::NAN.to_bits()
+                } else {
+                    d.to_bits()
+                };
+                bits.hash(state);
+            }
+            Bson::String(s) | Bson::JavaScriptCode(s) | Bson::Symbol(s) => s.hash(state),
+            Bson::Array(array) => array.hash(state),
+            Bson::Document(doc) => doc.hash(state),
+            Bson::Boolean(b) => b.hash(state),
+            Bson::RegularExpression(regex) => regex.hash(state),
+            Bson::JavaScriptCodeWithScope(code_w_scope) => code_w_scope.hash(state),
+            Bson::Int32(i) => i.hash(state),
+            Bson::Int64(i) => i.hash(state),
+            Bson::Timestamp(ts) => ts.hash(state),
+            Bson::Binary(binary) => binary.hash(state),
+            Bson::ObjectId(oid) => oid.hash(state),
+            Bson::DateTime(dt) => dt.hash(state),
+            Bson::Decimal128(d) => d.hash(state),
+            Bson::DbPointer(dbp) => dbp.hash(state),
+            Bson::Null | Bson::Undefined | Bson::MaxKey | Bson::MinKey => {}
+        }
+    }
+}
+
+impl Bson {
+    /// Feeds the canonical form of this value into `state`; see [`Document::stable_hash`] for the
+    /// normalization applied.
+    pub(crate) fn stable_hash<H: Hasher>(&self, state: &mut H) {
+        fn write_bytes<H: Hasher>(bytes: &[u8], state: &mut H) {
+            state.write(&(bytes.len() as u64).to_le_bytes());
+            state.write(bytes);
+        }
+
+        if let Some(number) = StableNumber::new(self) {
+            number.hash(state);
+            return;
+        }
+        state.write_u8(self.element_type() as u8);
+        match self {
+            Bson::String(s) | Bson::JavaScriptCode(s) | Bson::Symbol(s) => {
+                write_bytes(s.as_bytes(), state)
+            }
+            Bson::Array(array) => {
+                state.write(&(array.len() as u64).to_le_bytes());
+                for value in array {
+                    value.stable_hash(state);
+                }
+            }
+            Bson::Document(doc) => doc.stable_hash(state),
+            Bson::Boolean(b) => state.write_u8(*b as u8),
+            Bson::RegularExpression(Regex { pattern, options }) => {
+                write_bytes(pattern.as_bytes(), state);
+                write_bytes(options.as_bytes(), state);
+            }
+            Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope { code, scope }) => {
+                write_bytes(code.as_bytes(), state);
+                scope.stable_hash(state);
+            }
+            Bson::Timestamp(Timestamp { time, increment }) => {
+                state.write(&time.to_le_bytes());
+                state.write(&increment.to_le_bytes());
+            }
+            Bson::Binary(Binary { subtype, bytes }) => {
+                state.write_u8(u8::from(*subtype));
+                write_bytes(bytes, state);
+            }
+            Bson::ObjectId(oid) => state.write(&oid.bytes()),
+            Bson::DateTime(dt) => state.write(&dt.timestamp_millis().to_le_bytes()),
+            Bson::DbPointer(DbPointer { namespace, id }) => {
+                write_bytes(namespace.as_bytes(), state);
+                state.write(&id.bytes());
+            }
+            Bson::Null | Bson::Undefined | Bson::MaxKey | Bson::MinKey => {}
+            Bson::Int32(_) | Bson::Int64(_) | Bson::Double(_) | Bson::Decimal128(_) => {
+                unreachable!("numbers are hashed by StableNumber")
+            }
+        }
+    }
+}
+
+/// The normalized form of a numeric value used by [`Bson::stable_hash`].
+enum StableNumber {
+    Integer(i64),
+    Double(u64),
+    Decimal {
+        negative: bool,
+        coefficient: u128,
+        exponent: i16,
+    },
+    Infinity {
+        negative: bool,
+    },
+    NaN,
+}
+
+impl StableNumber {
+    fn new(value: &Bson) -> Option<Self> {
+        Some(match *value {
+            Bson::Int32(i) => Self::Integer(i.into()),
+            Bson::Int64(i) => Self::Integer(i),
+            Bson::Double(d) if d.is_nan() => Self::NaN,
+            Bson::Double(d) if d.is_infinite() => Self::Infinity { negative: d < 0.0 },
+            // the bounds are exactly representable, and -0.0 becomes 0
+            Bson::Double(d) if d.fract() == 0.0 && d >= -(2f64.powi(63)) && d < 2f64.powi(63) => {
+                Self::Integer(d as i64)
+            }
+            Bson::Double(d) => Self::Double(d.to_bits()),
+            Bson::Decimal128(d) => {
+                if let Some(i) = d.to_i128().and_then(|i| i64::try_from(i).ok()) {
+                    return Some(Self::Integer(i));
+                }
+                match d.numeric_value() {
+                    DecimalValue::NaN => Self::NaN,
+                    DecimalValue::Infinity { negative } => Self::Infinity { negative },
+                    DecimalValue::Finite {
+                        negative,
+                        mut coefficient,
+                        mut exponent,
+                    } => {
+                        // non-zero, since zero is always integral
+                        while coefficient % 10 == 0 {
+                            coefficient /= 10;
+                            exponent += 1;
+                        }
+                        Self::Decimal {
+                            negative,
+                            coefficient,
+                            exponent,
+                        }
+                    }
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Self::Integer(i) => {
+                state.write_u8(ElementType::Int64 as u8);
+                state.write(&i.to_le_bytes());
+            }
+            Self::Double(bits) => {
+                state.write_u8(ElementType::Double as u8);
+                state.write(&bits.to_le_bytes());
+            }
+            Self::Decimal {
+                negative,
+                coefficient,
+                exponent,
+            } => {
+                state.write_u8(ElementType::Decimal128 as u8);
+                state.write_u8(negative as u8);
+                state.write(&coefficient.to_le_bytes());
+                state.write(&exponent.to_le_bytes());
+            }
+            // shared by doubles and decimals; 0xFF and 0xFE are not valid element types
+            Self::Infinity { negative } => {
+                state.write_u8(0xFF);
+                state.write_u8(negative as u8);
+            }
+            Self::NaN => state.write_u8(0xFE),
+        }
+    }
+}
+
 impl Display for Bson {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -274,6 +461,10 @@ impl<T: Into<Bson>> ::std::iter::FromIterator<T> for Bson {
     /// let x: Bson = Bson::from_iter(vec!["lorem", "ipsum", "dolor"]);
     /// // or
     /// let x: Bson = vec!["lorem", "ipsum", "dolor"].into_iter().collect();
+    ///
+    /// // values that are already `Bson` collect directly into an array
+    /// let numbers: Bson = (1..=3).map(Bson::from).collect();
+    /// assert_eq!(numbers, Bson::Array(vec![Bson::Int32(1), Bson::Int32(2), Bson::Int32(3)]));
     /// ```
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         Bson::Array(iter.into_iter().map(Into::into).collect())
@@ -384,9 +575,44 @@ impl From<Bson> for Value {
 }
 
 impl Bson {
-    /// Converts the Bson value into its [relaxed extended JSON representation](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/).
+    /// Constructs a [`Bson::Array`] from an iterator of values that can be converted into
+    /// [`Bson`].
+    ///
+    /// ```
+    /// use bson::Bson;
+    ///
+    /// let ids = vec![1, 2, 3];
+    /// assert_eq!(
+    ///     Bson::array(ids),
+    ///     Bson::Array(vec![Bson::Int32(1), Bson::Int32(2), Bson::Int32(3)])
+    /// );
+    /// ```
+    pub fn array<I, T>(iter: I) -> Bson
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Bson>,
+    {
+        Bson::Array(iter.into_iter().map(Into::into).collect())
+    }
+
+    /// Constructs an empty [`Bson::Array`] with space for at least `capacity` values, which can be
+    /// filled via [`Bson::as_array_mut`] without reallocating.
     ///
-    /// Note: If this method is called on a case which contains a `Decimal128` value, it will panic.
+    /// ```
+    /// use bson::Bson;
+    ///
+    /// let mut array = Bson::array_with_capacity(3);
+    /// if let Some(values) = array.as_array_mut() {
+    ///     assert!(values.capacity() >= 3);
+    ///     values.extend((1..=3).map(Bson::Int32));
+    /// }
+    /// assert_eq!(array, Bson::array(vec![1, 2, 3]));
+    /// ```
+    pub fn array_with_capacity(capacity: usize) -> Bson {
+        Bson::Array(Vec::with_capacity(capacity))
+    }
+
+    /// Converts the Bson value into its [relaxed extended JSON representation](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/).
     pub fn into_relaxed_extjson(self) -> Value {
         match self {
             Bson::Double(v) if v.is_nan() => {
@@ -439,15 +665,7 @@ impl Bson {
                     "i": increment,
                 }
             }),
-            Bson::Binary(Binary { subtype, ref bytes }) => {
-                let tval: u8 = From::from(subtype);
-                json!({
-                    "$binary": {
-                        "base64": base64::encode(bytes),
-                        "subType": hex::encode([tval]),
-                    }
-                })
-            }
+            Bson::Binary(ref binary) => binary_to_extjson(binary),
             Bson::ObjectId(v) => json!({"$oid": v.to_hex()}),
             Bson::DateTime(v) if v.timestamp_millis() >= 0 && v.to_time_0_3().year() <= 9999 => {
                 json!({
@@ -477,6 +695,92 @@ impl Bson {
         }
     }
 
+    /// Converts the Bson value into its [relaxed extended JSON representation](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/)
+    /// without consuming it.
+    ///
+    /// ```
+    /// use bson::bson;
+    /// use serde_json::json;
+    ///
+    /// let value = bson!({ "x": 1 });
+    /// assert_eq!(value.to_relaxed_extjson(), json!({ "x": 1 }));
+    /// assert_eq!(value.to_canonical_extjson(), json!({ "x": { "$numberInt": "1" } }));
+    /// ```
+    pub fn to_relaxed_extjson(&self) -> Value {
+        match self {
+            Bson::Array(v) => Value::Array(v.iter().map(Bson::to_relaxed_extjson).collect()),
+            Bson::Document(v) => Value::Object(
+                v.iter()
+                    .map(|(k, v)| (k.clone(), v.to_relaxed_extjson()))
+                    .collect(),
+            ),
+            Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope { code, scope }) => json!({
+                "$code": code,
+                "$scope": scope,
+            }),
+            Bson::Binary(binary) => binary_to_extjson(binary),
+            other => other.clone().into_relaxed_extjson(),
+        }
+    }
+
+    /// Converts the Bson value into its [relaxed extended JSON representation](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/),
+    /// adjusted by `options`.
+    ///
+    /// With the default options this is the same as [`Bson::into_relaxed_extjson`]. The other
+    /// options give up round-trip fidelity to produce JSON that is easier for plain JSON consumers
+    /// to handle; see [`RelaxedExtJsonOptions`].
+    ///
+    /// ```
+    /// use bson::{doc, Bson, NonFinitePolicy, RelaxedExtJsonOptions};
+    /// use serde_json::json;
+    ///
+    /// let value = Bson::from(doc! { "x": f64::NAN, "y": 1.5 });
+    /// let options = RelaxedExtJsonOptions::builder()
+    ///     .non_finite(NonFinitePolicy::Null)
+    ///     .build();
+    /// assert_eq!(
+    ///     value.clone().try_into_relaxed_extjson(options)?,
+    ///     json!({ "x": null, "y": 1.5 })
+    /// );
+    ///
+    /// let options = RelaxedExtJsonOptions::builder()
+    ///     .non_finite(NonFinitePolicy::Error)
+    ///     .build();
+    /// assert!(value.try_into_relaxed_extjson(options).is_err());
+    /// # Ok::<(), bson::NonFiniteDoubleError>(())
+    /// ```
+    pub fn try_into_relaxed_extjson(
+        self,
+        options: RelaxedExtJsonOptions,
+    ) -> Result<Value, NonFiniteDoubleError> {
+        if options == RelaxedExtJsonOptions::default() {
+            return Ok(self.into_relaxed_extjson());
+        }
+        Ok(match self {
+            Bson::Double(v) if !v.is_finite() => match options.non_finite {
+                NonFinitePolicy::NumberDouble => Bson::Double(v).into_relaxed_extjson(),
+                NonFinitePolicy::Null => Value::Null,
+                NonFinitePolicy::Error => return Err(NonFiniteDoubleError { value: v }),
+            },
+            Bson::ObjectId(oid) if options.object_id_as_hex => Value::String(oid.to_hex()),
+            Bson::Array(v) => Value::Array(
+                v.into_iter()
+                    .map(|v| v.try_into_relaxed_extjson(options))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Bson::Document(v) => Value::Object(
+                v.into_iter()
+                    .map(|(k, v)| Ok((k, v.try_into_relaxed_extjson(options)?)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope { code, scope }) => json!({
+                "$code": code,
+                "$scope": Bson::Document(scope).try_into_relaxed_extjson(options)?,
+            }),
+            other => other.into_relaxed_extjson(),
+        })
+    }
+
     /// Converts the Bson value into its [canonical extended JSON representation](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/).
     pub fn into_canonical_extjson(self) -> Value {
         match self {
@@ -515,6 +819,30 @@ impl Bson {
         }
     }
 
+    /// Converts the Bson value into its [canonical extended JSON representation](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/)
+    /// without consuming it.
+    pub fn to_canonical_extjson(&self) -> Value {
+        match self {
+            Bson::Array(arr) => Value::Array(arr.iter().map(Bson::to_canonical_extjson).collect()),
+            Bson::Document(arr) => Value::Object(
+                arr.iter()
+                    .map(|(k, v)| (k.clone(), v.to_canonical_extjson()))
+                    .collect(),
+            ),
+            Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope { code, scope }) => json!({
+                "$code": code,
+                "$scope": Value::Object(
+                    scope
+                        .iter()
+                        .map(|(k, v)| (k.clone(), v.to_canonical_extjson()))
+                        .collect()
+                ),
+            }),
+            Bson::Binary(binary) => binary_to_extjson(binary),
+            other => other.clone().into_canonical_extjson(),
+        }
+    }
+
     /// Get the [`ElementType`] of this value.
     pub fn element_type(&self) -> ElementType {
         match *self {
@@ -542,6 +870,56 @@ impl Bson {
         }
     }
 
+    /// Returns whether this value has no plain JSON equivalent and so is always represented as a
+    /// `$`-prefixed wrapper document in extended JSON, e.g. `{ "$oid": "..." }` for an
+    /// [`ObjectId`](oid::ObjectId).
+    ///
+    /// Numbers are not considered extended types, even though canonical extended JSON wraps them,
+    /// since relaxed extended JSON writes finite numbers as plain JSON numbers.
+    ///
+    /// ```
+    /// use bson::{doc, oid::ObjectId, Bson, DateTime};
+    ///
+    /// assert!(Bson::ObjectId(ObjectId::new()).is_extended_type());
+    /// assert!(Bson::DateTime(DateTime::now()).is_extended_type());
+    /// assert!(!Bson::Int32(1).is_extended_type());
+    /// assert!(!Bson::Document(doc! {}).is_extended_type());
+    /// ```
+    pub fn is_extended_type(&self) -> bool {
+        match self {
+            Bson::RegularExpression(..)
+            | Bson::JavaScriptCode(..)
+            | Bson::JavaScriptCodeWithScope(..)
+            | Bson::Timestamp(..)
+            | Bson::Binary(..)
+            | Bson::ObjectId(..)
+            | Bson::DateTime(..)
+            | Bson::Symbol(..)
+            | Bson::Decimal128(..)
+            | Bson::Undefined
+            | Bson::MaxKey
+            | Bson::MinKey
+            | Bson::DbPointer(..) => true,
+            Bson::Double(..)
+            | Bson::String(..)
+            | Bson::Array(..)
+            | Bson::Document(..)
+            | Bson::Boolean(..)
+            | Bson::Null
+            | Bson::Int32(..)
+            | Bson::Int64(..) => false,
+        }
+    }
+
+    /// Returns whether this value is a [`Bson::Document`] or a [`Bson::Array`], i.e. whether it
+    /// contains other values.
+    ///
+    /// Note that [`Bson::JavaScriptCodeWithScope`] is not considered a container, even though its
+    /// scope is a document.
+    pub fn is_container(&self) -> bool {
+        matches!(self, Bson::Document(..) | Bson::Array(..))
+    }
+
     /// Converts to extended format.
     /// This function mainly used for [extended JSON format](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/).
     // TODO RUST-426: Investigate either removing this from the serde implementation or unifying
@@ -777,7 +1155,7 @@ impl Bson {
                 if let Ok(regex) = doc.get_document("$regularExpression") {
                     if let Ok(pattern) = regex.get_str("pattern") {
                         if let Ok(options) = regex.get_str("options") {
-                            return Bson::RegularExpression(Regex::new(pattern, options));
+                            return Bson::RegularExpression(Regex::from_strings(pattern, options));
                         }
                     }
                 }
@@ -935,6 +1313,43 @@ impl Bson {
         }
     }
 
+    /// If `self` is [`Int32`](Bson::Int32) or [`Int64`](Bson::Int64), return its value widened to
+    /// an `i64`. Returns [`None`] otherwise. Use [`Bson::as_i64`] to accept only `Int64`.
+    ///
+    /// ```
+    /// use bson::Bson;
+    ///
+    /// assert_eq!(Bson::Int32(5).to_i64(), Some(5));
+    /// assert_eq!(Bson::Int64(5).to_i64(), Some(5));
+    /// assert_eq!(Bson::Double(5.0).to_i64(), None);
+    /// ```
+    pub fn to_i64(&self) -> Option<i64> {
+        match *self {
+            Bson::Int32(v) => Some(v.into()),
+            Bson::Int64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// If `self` is [`Int32`](Bson::Int32), or [`Int64`](Bson::Int64) with a value that fits in
+    /// an `i32`, return that value. Returns [`None`] otherwise. Use [`Bson::as_i32`] to accept
+    /// only `Int32`.
+    ///
+    /// ```
+    /// use bson::Bson;
+    ///
+    /// assert_eq!(Bson::Int32(5).to_i32_checked(), Some(5));
+    /// assert_eq!(Bson::Int64(5).to_i32_checked(), Some(5));
+    /// assert_eq!(Bson::Int64(i64::MAX).to_i32_checked(), None);
+    /// ```
+    pub fn to_i32_checked(&self) -> Option<i32> {
+        match *self {
+            Bson::Int32(v) => Some(v),
+            Bson::Int64(v) => i32::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
     /// If `self` is [`ObjectId`](Bson::ObjectId), return its value. Returns [`None`] otherwise.
     pub fn as_object_id(&self) -> Option<oid::ObjectId> {
         match *self {
@@ -1009,6 +1424,331 @@ impl Bson {
             _ => None,
         }
     }
+
+    /// If `self` is [`Binary`](Bson::Binary) and its bytes are a valid BSON document, return them
+    /// as a [`RawDocument`]. Returns [`None`] otherwise. See [`Binary::as_raw_document`].
+    pub fn as_raw_document(&self) -> Option<&RawDocument> {
+        match self {
+            Bson::Binary(binary) => binary.as_raw_document().ok(),
+            _ => None,
+        }
+    }
+
+    /// Replaces `self` with [`Null`](Bson::Null), returning the previous value.
+    ///
+    /// This is convenient when transforming values in place:
+    ///
+    /// ```
+    /// use bson::{bson, Bson};
+    ///
+    /// let mut value = bson!({ "a": [1, 2] });
+    /// if let Some(a) = value.as_document_mut().and_then(|doc| doc.get_mut("a")) {
+    ///     let old = a.take();
+    ///     *a = Bson::Array(vec![old]);
+    /// }
+    /// assert_eq!(value, bson!({ "a": [[1, 2]] }));
+    /// ```
+    pub fn take(&mut self) -> Bson {
+        std::mem::take(self)
+    }
+
+    /// Compares two numeric values ([`Int32`](Bson::Int32), [`Int64`](Bson::Int64),
+    /// [`Double`](Bson::Double) and [`Decimal128`](Bson::Decimal128)) by their mathematical value,
+    /// regardless of variant.
+    ///
+    /// The comparison is exact: no value is rounded by converting it to another type, so for
+    /// example `Int64(i64::MAX)` is not equal to `Double(i64::MAX as f64)`. Zeroes of either sign
+    /// compare equal, and infinities compare beyond all finite values.
+    ///
+    /// Returns [`None`] if either value is not numeric or is NaN.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use bson::Bson;
+    ///
+    /// assert_eq!(Bson::Int32(2).numeric_cmp(&Bson::Double(1.5)), Some(Ordering::Greater));
+    /// assert_eq!(Bson::Int64(1).numeric_cmp(&Bson::String("1".into())), None);
+    /// ```
+    pub fn numeric_cmp(&self, other: &Bson) -> Option<Ordering> {
+        Numeric::new(self)?.cmp(&Numeric::new(other)?)
+    }
+
+    /// Returns whether two numeric values are mathematically equal, regardless of variant. See
+    /// [`Bson::numeric_cmp`] for details of the comparison.
+    ///
+    /// Unlike the [`PartialEq`] implementation, which also requires the variants to match, this
+    /// considers values such as `Int32(1)`, `Int64(1)`, `Double(1.0)` and a `Decimal128` of `1.0`
+    /// to be equal. Non-numeric values and NaN are never equal to anything.
+    ///
+    /// ```
+    /// use bson::Bson;
+    ///
+    /// let one: bson::Decimal128 = "1.0".parse()?;
+    /// assert!(Bson::Int32(1).numeric_eq(&Bson::Int64(1)));
+    /// assert!(Bson::Double(1.0).numeric_eq(&Bson::Decimal128(one)));
+    /// assert!(!Bson::Double(0.1).numeric_eq(&Bson::Decimal128("0.1".parse()?)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn numeric_eq(&self, other: &Bson) -> bool {
+        self.numeric_cmp(other) == Some(Ordering::Equal)
+    }
+
+    /// Compares two values using the [BSON comparison order](https://www.mongodb.com/docs/manual/reference/bson-type-comparison-order/)
+    /// used by MongoDB for sorting and range queries.
+    ///
+    /// Values of different types are ordered by type first:
+    /// [`MinKey`](Bson::MinKey), [`Undefined`](Bson::Undefined), [`Null`](Bson::Null), numbers,
+    /// strings and symbols, documents, arrays, binaries, ObjectIds, booleans, datetimes,
+    /// timestamps, regular expressions, DBPointers, JavaScript code, JavaScript code with scope,
+    /// and finally [`MaxKey`](Bson::MaxKey). [`MinKey`](Bson::MinKey) and
+    /// [`MaxKey`](Bson::MaxKey) therefore sort below and above every other value, which makes
+    /// them suitable as open bounds for ranges.
+    ///
+    /// Numbers of any variant are compared by their mathematical value as in
+    /// [`Bson::numeric_cmp`], with NaN sorting below all other numbers. Strings are compared by
+    /// their bytes, documents and arrays element by element, and the remaining types field by
+    /// field.
+    ///
+    /// Because numbers of different variants can compare equal, this ordering is not consistent
+    /// with the [`PartialEq`] implementation, so [`Bson`] does not implement [`Ord`].
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use bson::Bson;
+    ///
+    /// assert_eq!(Bson::MinKey.canonical_cmp(&Bson::Int32(i32::MIN)), Ordering::Less);
+    /// assert_eq!(Bson::MaxKey.canonical_cmp(&Bson::String("z".into())), Ordering::Greater);
+    /// assert_eq!(Bson::Int32(1).canonical_cmp(&Bson::Double(1.0)), Ordering::Equal);
+    /// assert_eq!(Bson::Int64(5).canonical_cmp(&Bson::String("1".into())), Ordering::Less);
+    /// ```
+    pub fn canonical_cmp(&self, other: &Bson) -> Ordering {
+        let type_order = self
+            .element_type()
+            .canonical_order_rank()
+            .cmp(&other.element_type().canonical_order_rank());
+        if type_order != Ordering::Equal {
+            return type_order;
+        }
+        match (self, other) {
+            (Bson::String(a) | Bson::Symbol(a), Bson::String(b) | Bson::Symbol(b)) => a.cmp(b),
+            (Bson::Document(a), Bson::Document(b)) => canonical_cmp_elements(a.iter(), b.iter()),
+            (Bson::Array(a), Bson::Array(b)) => {
+                canonical_cmp_elements(a.iter().map(|v| ((), v)), b.iter().map(|v| ((), v)))
+            }
+            (Bson::Binary(a), Bson::Binary(b)) => a
+                .bytes
+                .len()
+                .cmp(&b.bytes.len())
+                .then_with(|| u8::from(a.subtype).cmp(&u8::from(b.subtype)))
+                .then_with(|| a.bytes.cmp(&b.bytes)),
+            (Bson::ObjectId(a), Bson::ObjectId(b)) => a.cmp(b),
+            (Bson::Boolean(a), Bson::Boolean(b)) => a.cmp(b),
+            (Bson::DateTime(a), Bson::DateTime(b)) => a.cmp(b),
+            (Bson::Timestamp(a), Bson::Timestamp(b)) => {
+                (a.time, a.increment).cmp(&(b.time, b.increment))
+            }
+            (Bson::RegularExpression(a), Bson::RegularExpression(b)) => {
+                (&a.pattern, &a.options).cmp(&(&b.pattern, &b.options))
+            }
+            (Bson::DbPointer(a), Bson::DbPointer(b)) => {
+                (&a.namespace, a.id).cmp(&(&b.namespace, b.id))
+            }
+            (Bson::JavaScriptCode(a), Bson::JavaScriptCode(b)) => a.cmp(b),
+            (Bson::JavaScriptCodeWithScope(a), Bson::JavaScriptCodeWithScope(b)) => a
+                .code
+                .cmp(&b.code)
+                .then_with(|| canonical_cmp_elements(a.scope.iter(), b.scope.iter())),
+            // Numbers, with NaN (for which there is no `Numeric`) sorting below all others, or
+            // types that only have a single value.
+            _ => match (Numeric::new(self), Numeric::new(other)) {
+                (Some(a), Some(b)) => a.cmp(&b).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Greater,
+                (None, Some(_)) => Ordering::Less,
+                (None, None) => Ordering::Equal,
+            },
+        }
+    }
+}
+
+/// The extended JSON representation of a [`Binary`], which is the same in canonical and relaxed
+/// modes.
+fn binary_to_extjson(binary: &Binary) -> Value {
+    let tval: u8 = From::from(binary.subtype);
+    json!({
+        "$binary": {
+            "base64": base64::encode(&binary.bytes),
+            "subType": hex::encode([tval]),
+        }
+    })
+}
+
+/// Compares the elements of two documents or arrays in order by type, then key, then value,
+/// with a shorter sequence sorting first if it is a prefix of the other.
+fn canonical_cmp_elements<'a, K: Ord>(
+    mut a: impl Iterator<Item = (K, &'a Bson)>,
+    mut b: impl Iterator<Item = (K, &'a Bson)>,
+) -> Ordering {
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some((key_a, value_a)), Some((key_b, value_b))) => value_a
+                .element_type()
+                .canonical_order_rank()
+                .cmp(&value_b.element_type().canonical_order_rank())
+                .then_with(|| key_a.cmp(&key_b))
+                .then_with(|| value_a.canonical_cmp(value_b)),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// A numeric [`Bson`] value, decomposed for exact comparison across types.
+enum Numeric {
+    Infinity {
+        negative: bool,
+    },
+    /// `coefficient * 10^exp10 * 2^exp2`
+    Finite {
+        negative: bool,
+        coefficient: u128,
+        exp10: i32,
+        exp2: i32,
+    },
+}
+
+impl Numeric {
+    /// Returns [`None`] for non-numeric values and NaN.
+    fn new(bson: &Bson) -> Option<Self> {
+        let finite = |negative, coefficient, exp10, exp2| Numeric::Finite {
+            negative,
+            coefficient,
+            exp10,
+            exp2,
+        };
+        match *bson {
+            Bson::Int32(i) => Some(finite(i < 0, i.unsigned_abs() as u128, 0, 0)),
+            Bson::Int64(i) => Some(finite(i < 0, i.unsigned_abs() as u128, 0, 0)),
+            Bson::Double(d) => {
+                if d.is_nan() {
+                    return None;
+                }
+                let negative = d.is_sign_negative();
+                if d.is_infinite() {
+                    return Some(Numeric::Infinity { negative });
+                }
+                let bits = d.to_bits();
+                let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+                let fraction = bits & ((1 << 52) - 1);
+                // Subnormals have an implicit leading 0 rather than 1.
+                let (mantissa, exp2) = if biased_exponent == 0 {
+                    (fraction, -1074)
+                } else {
+                    (fraction | (1 << 52), biased_exponent - 1075)
+                };
+                Some(finite(negative, mantissa as u128, 0, exp2))
+            }
+            Bson::Decimal128(d) => match d.numeric_value() {
+                DecimalValue::NaN => None,
+                DecimalValue::Infinity { negative } => Some(Numeric::Infinity { negative }),
+                DecimalValue::Finite {
+                    negative,
+                    coefficient,
+                    exponent,
+                } => Some(finite(negative, coefficient, exponent as i32, 0)),
+            },
+            _ => None,
+        }
+    }
+
+    /// Where this value sits relative to zero, with infinities beyond all finite values.
+    fn rank(&self) -> i8 {
+        match *self {
+            Numeric::Infinity { negative: true } => -2,
+            Numeric::Infinity { negative: false } => 2,
+            Numeric::Finite { coefficient: 0, .. } => 0,
+            Numeric::Finite { negative: true, .. } => -1,
+            Numeric::Finite {
+                negative: false, ..
+            } => 1,
+        }
+    }
+
+    fn cmp(&self, other: &Numeric) -> Option<Ordering> {
+        let (rank, other_rank) = (self.rank(), other.rank());
+        if rank != other_rank || rank.abs() != 1 {
+            return Some(rank.cmp(&other_rank));
+        }
+        let ((c1, e1, b1), (c2, e2, b2)) = match (self, other) {
+            (
+                Numeric::Finite {
+                    coefficient: c1,
+                    exp10: e1,
+                    exp2: b1,
+                    ..
+                },
+                Numeric::Finite {
+                    coefficient: c2,
+                    exp10: e2,
+                    exp2: b2,
+                    ..
+                },
+            ) => ((*c1, *e1, *b1), (*c2, *e2, *b2)),
+            _ => return None,
+        };
+        // Scale both magnitudes by the same power of ten and two so that all exponents are
+        // non-negative, then compare the resulting integers exactly.
+        let (min10, min2) = (e1.min(e2), b1.min(b2));
+        let lhs = big_uint(c1, (e1 - min10) as u32, (b1 - min2) as u32);
+        let rhs = big_uint(c2, (e2 - min10) as u32, (b2 - min2) as u32);
+        let magnitude = lhs
+            .len()
+            .cmp(&rhs.len())
+            .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()));
+        Some(
+            if rank < 0 {
+                magnitude.reverse()
+            } else {
+                magnitude
+            },
+        )
+    }
+}
+
+/// Computes `value * 10^pow10 * 2^pow2` as little-endian `u32` limbs with no trailing zero limbs.
+fn big_uint(value: u128, pow10: u32, pow2: u32) -> Vec<u32> {
+    fn mul_small(limbs: &mut Vec<u32>, factor: u32) {
+        let mut carry = 0u64;
+        for limb in limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry != 0 {
+            limbs.push(carry as u32);
+        }
+    }
+
+    let mut limbs: Vec<u32> = (0..4).map(|i| (value >> (32 * i)) as u32).collect();
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    if limbs.is_empty() {
+        return limbs;
+    }
+    for _ in 0..pow10 / 9 {
+        mul_small(&mut limbs, 1_000_000_000);
+    }
+    mul_small(&mut limbs, 10u32.pow(pow10 % 9));
+    let (whole, bits) = ((pow2 / 32) as usize, pow2 % 32);
+    if bits != 0 {
+        mul_small(&mut limbs, 1 << bits);
+    }
+    limbs.splice(0..0, std::iter::repeat(0).take(whole));
+    limbs
 }
 
 /// Represents a BSON timestamp value.
@@ -1028,7 +1768,45 @@ impl Display for Timestamp {
     }
 }
 
+impl From<Timestamp> for (u32, u32) {
+    /// Converts a timestamp into a `(time, increment)` pair.
+    fn from(timestamp: Timestamp) -> Self {
+        (timestamp.time, timestamp.increment)
+    }
+}
+
+impl From<(u32, u32)> for Timestamp {
+    /// Creates a timestamp from a `(time, increment)` pair.
+    fn from((time, increment): (u32, u32)) -> Self {
+        Timestamp { time, increment }
+    }
+}
+
 impl Timestamp {
+    /// Packs this timestamp into a single `u64` as `(time << 32) | increment`.
+    ///
+    /// This is the representation MongoDB uses when comparing timestamps, so the ordering of the
+    /// packed values matches the ordering of the timestamps.
+    ///
+    /// ```
+    /// use bson::Timestamp;
+    ///
+    /// let ts = Timestamp { time: 1, increment: 2 };
+    /// assert_eq!(ts.to_u64(), (1 << 32) | 2);
+    /// assert_eq!(Timestamp::from_u64(ts.to_u64()), ts);
+    /// ```
+    pub fn to_u64(self) -> u64 {
+        ((self.time as u64) << 32) | self.increment as u64
+    }
+
+    /// Unpacks a timestamp from a `u64` produced by [`Timestamp::to_u64`].
+    pub fn from_u64(value: u64) -> Self {
+        Self {
+            time: (value >> 32) as u32,
+            increment: value as u32,
+        }
+    }
+
     pub(crate) fn to_le_bytes(self) -> [u8; 8] {
         let mut out = [0; 8];
         out[0..4].copy_from_slice(&self.increment.to_le_bytes());
@@ -1049,9 +1827,9 @@ impl Timestamp {
 }
 
 /// Represents a BSON regular expression value.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Regex {
-    /// The regex pattern to match.
+    /// The regex pattern to match. This must not contain null bytes.
     pub pattern: String,
 
     /// The options for the regex.
@@ -1060,12 +1838,53 @@ pub struct Regex {
     /// alphabetical order. Valid options are 'i' for case insensitive matching, 'm' for
     /// multiline matching, 'x' for verbose mode, 'l' to make \w, \W, etc. locale dependent,
     /// 's' for dotall mode ('.' matches everything), and 'u' to make \w, \W, etc. match
-    /// unicode.
+    /// unicode. Each option may appear at most once.
+    ///
+    /// These invariants are checked by [`Regex::new`], but not when constructing a [`Regex`]
+    /// directly.
     pub options: String,
 }
 
 impl Regex {
-    pub(crate) fn new(pattern: impl AsRef<str>, options: impl AsRef<str>) -> Self {
+    /// Creates a new [`Regex`], validating that `pattern` contains no null bytes and that
+    /// `options` consists of unique, alphabetically sorted characters from the allowed set
+    /// (`i`, `l`, `m`, `s`, `u`, `x`).
+    ///
+    /// ```
+    /// use bson::{Regex, RegexError};
+    ///
+    /// let regex = Regex::new("^abc", "im")?;
+    /// assert_eq!(regex.options, "im");
+    ///
+    /// assert!(matches!(
+    ///     Regex::new("^abc", "mi"),
+    ///     Err(RegexError::UnsortedOptions { .. })
+    /// ));
+    /// # Ok::<(), RegexError>(())
+    /// ```
+    pub fn new(pattern: impl Into<String>, options: impl Into<String>) -> Result<Self, RegexError> {
+        let pattern = pattern.into();
+        let options = options.into();
+        if pattern.contains('\0') || options.contains('\0') {
+            return Err(RegexError::InteriorNull);
+        }
+        let mut previous: Option<char> = None;
+        for option in options.chars() {
+            if !"ilmsux".contains(option) {
+                return Err(RegexError::InvalidOption { option });
+            }
+            match previous {
+                Some(p) if p == option => return Err(RegexError::DuplicateOption { option }),
+                Some(p) if p > option => return Err(RegexError::UnsortedOptions { options }),
+                _ => {}
+            }
+            previous = Some(option);
+        }
+        Ok(Self { pattern, options })
+    }
+
+    /// Creates a new [`Regex`] without validation, sorting the characters of `options`.
+    pub(crate) fn from_strings(pattern: impl AsRef<str>, options: impl AsRef<str>) -> Self {
         let mut chars: Vec<_> = options.as_ref().chars().collect();
         chars.sort_unstable();
         let options: String = chars.into_iter().collect();
@@ -1082,8 +1901,144 @@ impl Display for Regex {
     }
 }
 
+/// Errors that can occur when constructing a [`Regex`] with [`Regex::new`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegexError {
+    /// The pattern or options contained an interior null byte, which cannot be encoded in BSON.
+    InteriorNull,
+
+    /// An option character outside of the allowed set was provided.
+    #[non_exhaustive]
+    InvalidOption {
+        /// The unrecognized option.
+        option: char,
+    },
+
+    /// An option character was repeated.
+    #[non_exhaustive]
+    DuplicateOption {
+        /// The repeated option.
+        option: char,
+    },
+
+    /// The options were not in alphabetical order.
+    #[non_exhaustive]
+    UnsortedOptions {
+        /// The provided options.
+        options: String,
+    },
+}
+
+impl Display for RegexError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegexError::InteriorNull => fmt.write_str("regex contains an interior null byte"),
+            RegexError::InvalidOption { option } => {
+                write!(fmt, "invalid regex option {:?}", option)
+            }
+            RegexError::DuplicateOption { option } => {
+                write!(fmt, "duplicate regex option {:?}", option)
+            }
+            RegexError::UnsortedOptions { options } => {
+                write!(
+                    fmt,
+                    "regex options {:?} are not in alphabetical order",
+                    options
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+/// Options for [`Bson::try_into_relaxed_extjson`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RelaxedExtJsonOptions {
+    /// How non-finite doubles are represented. The default is [`NonFinitePolicy::NumberDouble`].
+    pub non_finite: NonFinitePolicy,
+
+    /// Whether to render [`ObjectId`](oid::ObjectId)s as bare hex strings rather than
+    /// `{ "$oid": "..." }`. Output produced this way can't be parsed back into an `ObjectId`,
+    /// since it is indistinguishable from a string. The default is false.
+    pub object_id_as_hex: bool,
+}
+
+impl RelaxedExtJsonOptions {
+    /// Create a builder struct used to construct a [`RelaxedExtJsonOptions`].
+    pub fn builder() -> RelaxedExtJsonOptionsBuilder {
+        RelaxedExtJsonOptionsBuilder {
+            options: Default::default(),
+        }
+    }
+}
+
+/// Builder used to construct a [`RelaxedExtJsonOptions`].
+pub struct RelaxedExtJsonOptionsBuilder {
+    options: RelaxedExtJsonOptions,
+}
+
+impl RelaxedExtJsonOptionsBuilder {
+    /// Set the value for [`RelaxedExtJsonOptions::non_finite`].
+    pub fn non_finite(mut self, val: NonFinitePolicy) -> Self {
+        self.options.non_finite = val;
+        self
+    }
+
+    /// Set the value for [`RelaxedExtJsonOptions::object_id_as_hex`].
+    pub fn object_id_as_hex(mut self, val: bool) -> Self {
+        self.options.object_id_as_hex = val;
+        self
+    }
+
+    /// Consume this builder and produce a [`RelaxedExtJsonOptions`].
+    pub fn build(self) -> RelaxedExtJsonOptions {
+        self.options
+    }
+}
+
+/// How [`Bson::try_into_relaxed_extjson`] represents non-finite doubles (`NaN` and `±Infinity`),
+/// which plain JSON has no way to express.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NonFinitePolicy {
+    /// Use the extended JSON `{ "$numberDouble": "NaN" }` form, as required by the extended JSON
+    /// specification.
+    #[default]
+    NumberDouble,
+
+    /// Replace the value with `null`.
+    Null,
+
+    /// Fail with a [`NonFiniteDoubleError`].
+    Error,
+}
+
+/// The error returned by [`Bson::try_into_relaxed_extjson`] when a non-finite double is
+/// encountered under [`NonFinitePolicy::Error`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct NonFiniteDoubleError {
+    /// The non-finite value.
+    pub value: f64,
+}
+
+impl Display for NonFiniteDoubleError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "non-finite double {} cannot be represented in JSON",
+            self.value
+        )
+    }
+}
+
+impl std::error::Error for NonFiniteDoubleError {}
+
 /// Represents a BSON code with scope value.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct JavaScriptCodeWithScope {
     /// The JavaScript code.
     pub code: String,
@@ -1092,6 +2047,34 @@ pub struct JavaScriptCodeWithScope {
     pub scope: Document,
 }
 
+impl JavaScriptCodeWithScope {
+    /// Creates a new code with scope value from the given code and scope document.
+    ///
+    /// Variables can be added to the scope afterwards with
+    /// [`JavaScriptCodeWithScope::with_scope_var`]:
+    ///
+    /// ```
+    /// use bson::{doc, JavaScriptCodeWithScope};
+    ///
+    /// let code = JavaScriptCodeWithScope::new("x + y", doc! {})
+    ///     .with_scope_var("x", 1)
+    ///     .with_scope_var("y", 2);
+    /// assert_eq!(code.scope, doc! { "x": 1, "y": 2 });
+    /// ```
+    pub fn new(code: impl Into<String>, scope: Document) -> Self {
+        Self {
+            code: code.into(),
+            scope,
+        }
+    }
+
+    /// Binds `key` to `value` in the scope, replacing any existing binding, and returns `self`.
+    pub fn with_scope_var(mut self, key: impl Into<String>, value: impl Into<Bson>) -> Self {
+        self.scope.insert(key, value);
+        self
+    }
+}
+
 impl Display for JavaScriptCodeWithScope {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.write_str(&self.code)
@@ -1099,7 +2082,7 @@ impl Display for JavaScriptCodeWithScope {
 }
 
 /// Represents a DBPointer. (Deprecated)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DbPointer {
     pub(crate) namespace: String,
     pub(crate) id: oid::ObjectId,
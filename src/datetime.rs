@@ -195,6 +195,35 @@ impl crate::DateTime {
         Self(date)
     }
 
+    /// Makes a new [`DateTime`] from the number of non-leap milliseconds since the Unix epoch, or
+    /// returns [`None`] if the result could not be represented in every output format this crate
+    /// supports.
+    ///
+    /// The accepted range is `0000-01-01T00:00:00.000Z` (`-62_167_219_200_000`) through
+    /// `9999-12-31T23:59:59.999Z` (`253_402_300_799_999`), inclusive. Values in this range can
+    /// always be formatted as RFC 3339 (including in relaxed extended JSON) and converted to
+    /// [`time::OffsetDateTime`] or `chrono::DateTime` without clamping. Values outside of it are
+    /// still valid BSON and can be created with [`DateTime::from_millis`].
+    ///
+    /// ```
+    /// use bson::DateTime;
+    ///
+    /// assert!(DateTime::checked_from_millis(1_591_700_287_095).is_some());
+    /// assert!(DateTime::checked_from_millis(253_402_300_799_999).is_some());
+    /// assert!(DateTime::checked_from_millis(253_402_300_800_000).is_none());
+    /// assert!(DateTime::checked_from_millis(i64::MIN).is_none());
+    /// ```
+    pub const fn checked_from_millis(millis: i64) -> Option<Self> {
+        const MIN_MILLIS: i64 = -62_167_219_200_000;
+        const MAX_MILLIS: i64 = 253_402_300_799_999;
+
+        if millis >= MIN_MILLIS && millis <= MAX_MILLIS {
+            Some(Self(millis))
+        } else {
+            None
+        }
+    }
+
     /// Returns a [`DateTime`] which corresponds to the current date and time.
     pub fn now() -> DateTime {
         Self::from_system_time(SystemTime::now())
@@ -371,6 +400,20 @@ impl crate::DateTime {
     }
 
     /// Convert this [`DateTime`] to an RFC 3339 formatted string.
+    ///
+    /// The string is always in UTC with a `Z` suffix, and includes a fractional seconds component
+    /// only when the datetime has a non-zero millisecond part. This does not require the
+    /// `chrono-0_4` or `time-0_3` feature flags. An error is returned if the datetime falls
+    /// outside the range representable in RFC 3339 (years 0000 through 9999).
+    ///
+    /// ```
+    /// let dt = bson::DateTime::from_millis(1_591_700_287_095);
+    /// assert_eq!(dt.try_to_rfc3339_string()?, "2020-06-09T10:58:07.095Z");
+    ///
+    /// let dt = bson::DateTime::from_millis(0);
+    /// assert_eq!(dt.try_to_rfc3339_string()?, "1970-01-01T00:00:00Z");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
     pub fn try_to_rfc3339_string(self) -> Result<String> {
         self.to_time_0_3()
             .format(&Rfc3339)
@@ -381,6 +424,15 @@ impl crate::DateTime {
 
     /// Convert the given RFC 3339 formatted string to a [`DateTime`], truncating it to millisecond
     /// precision.
+    ///
+    /// Both the `Z` suffix and explicit UTC offsets (e.g. `+02:00`) are accepted; the result is
+    /// normalized to UTC. This does not require the `chrono-0_4` or `time-0_3` feature flags.
+    ///
+    /// ```
+    /// let dt = bson::DateTime::parse_rfc3339_str("2020-06-09T12:58:07.095123+02:00")?;
+    /// assert_eq!(dt, bson::DateTime::parse_rfc3339_str("2020-06-09T10:58:07.095Z")?);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
     pub fn parse_rfc3339_str(s: impl AsRef<str>) -> Result<Self> {
         let odt = time::OffsetDateTime::parse(s.as_ref(), &Rfc3339).map_err(|e| {
             Error::InvalidTimestamp {
@@ -427,6 +479,34 @@ impl Display for crate::DateTime {
     }
 }
 
+/// Interprets the `i64` as a number of milliseconds since the Unix epoch, as with
+/// [`DateTime::from_millis`](crate::DateTime::from_millis). This only applies to converting to a
+/// [`DateTime`](crate::DateTime) directly; an `i64` still converts to
+/// [`Bson::Int64`](crate::Bson::Int64) rather than [`Bson::DateTime`](crate::Bson::DateTime).
+///
+/// ```
+/// use bson::DateTime;
+///
+/// let millis = vec![0, 1_591_700_287_095];
+/// let dates: Vec<DateTime> = millis.into_iter().map(DateTime::from).collect();
+/// assert_eq!(dates[1].try_to_rfc3339_string()?, "2020-06-09T10:58:07.095Z");
+/// assert_eq!(i64::from(dates[1]), 1_591_700_287_095);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+impl From<i64> for crate::DateTime {
+    fn from(millis: i64) -> Self {
+        Self::from_millis(millis)
+    }
+}
+
+/// Returns the number of milliseconds since the Unix epoch, as with
+/// [`DateTime::timestamp_millis`](crate::DateTime::timestamp_millis).
+impl From<crate::DateTime> for i64 {
+    fn from(dt: crate::DateTime) -> Self {
+        dt.timestamp_millis()
+    }
+}
+
 impl From<SystemTime> for crate::DateTime {
     fn from(st: SystemTime) -> Self {
         Self::from_system_time(st)
@@ -455,6 +535,24 @@ impl<T: chrono::TimeZone> From<chrono::DateTime<T>> for crate::DateTime {
     }
 }
 
+/// Converts to a [`chrono::NaiveDateTime`] in UTC.
+#[cfg(feature = "chrono-0_4")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono-0_4")))]
+impl From<crate::DateTime> for chrono::NaiveDateTime {
+    fn from(bson_dt: DateTime) -> Self {
+        bson_dt.to_chrono().naive_utc()
+    }
+}
+
+/// Interprets the [`chrono::NaiveDateTime`] as UTC.
+#[cfg(feature = "chrono-0_4")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono-0_4")))]
+impl From<chrono::NaiveDateTime> for crate::DateTime {
+    fn from(x: chrono::NaiveDateTime) -> Self {
+        Self::from_chrono(Utc.from_utc_datetime(&x))
+    }
+}
+
 #[cfg(all(feature = "chrono-0_4", feature = "serde_with"))]
 #[cfg_attr(docsrs, doc(cfg(all(feature = "chrono-0_4", feature = "serde_with"))))]
 impl<'de> DeserializeAs<'de, chrono::DateTime<Utc>> for crate::DateTime {
@@ -528,6 +626,26 @@ impl From<time::OffsetDateTime> for crate::DateTime {
     }
 }
 
+/// Converts to a [`time::PrimitiveDateTime`] in UTC, clamping out-of-range values in the same way
+/// as [`DateTime::to_time_0_3`].
+#[cfg(feature = "time-0_3")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time-0_3")))]
+impl From<crate::DateTime> for time::PrimitiveDateTime {
+    fn from(bson_dt: DateTime) -> Self {
+        let odt = bson_dt.to_time_0_3();
+        time::PrimitiveDateTime::new(odt.date(), odt.time())
+    }
+}
+
+/// Interprets the [`time::PrimitiveDateTime`] as UTC.
+#[cfg(feature = "time-0_3")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time-0_3")))]
+impl From<time::PrimitiveDateTime> for crate::DateTime {
+    fn from(x: time::PrimitiveDateTime) -> Self {
+        Self::from_time_0_3(x.assume_utc())
+    }
+}
+
 #[cfg(all(feature = "time-0_3", feature = "serde_with"))]
 #[cfg_attr(docsrs, doc(cfg(all(feature = "time-0_3", feature = "serde_with"))))]
 impl<'de> DeserializeAs<'de, time::OffsetDateTime> for crate::DateTime {
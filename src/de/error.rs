@@ -35,9 +35,39 @@ pub enum Error {
     DeserializationError {
         /// A message describing the error.
         message: String,
+
+        /// The key of the document field that was being deserialized when the error occurred, if
+        /// any. Only the innermost key is recorded; see [`Error::key`].
+        key: Option<String>,
     },
 }
 
+impl Error {
+    /// The key of the innermost document field that was being deserialized when this error
+    /// occurred, if known.
+    ///
+    /// This is a lightweight, single-level hint; for the full path to the failing field, wrap the
+    /// deserializer with a crate such as `serde_path_to_error`.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Error::DeserializationError { key, .. } => key.as_deref(),
+            Error::UnrecognizedDocumentElementType { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Records the key of the field being deserialized if no (more deeply nested) key has been
+    /// recorded yet.
+    pub(crate) fn with_key(mut self, get_key: impl FnOnce() -> String) -> Self {
+        if let Error::DeserializationError { ref mut key, .. } = self {
+            if key.is_none() {
+                *key = Some(get_key());
+            }
+        }
+        self
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Error {
         Error::Io(Arc::new(err))
@@ -64,7 +94,11 @@ impl fmt::Display for Error {
                 key, element_type
             ),
             Error::EndOfStream => fmt.write_str("end of stream"),
-            Error::DeserializationError { ref message } => message.fmt(fmt),
+            Error::DeserializationError {
+                ref message,
+                key: Some(ref key),
+            } => write!(fmt, "{} (while deserializing field `{}`)", message, key),
+            Error::DeserializationError { ref message, .. } => message.fmt(fmt),
         }
     }
 }
@@ -83,6 +117,7 @@ impl de::Error for Error {
     fn custom<T: Display>(msg: T) -> Error {
         Error::DeserializationError {
             message: msg.to_string(),
+            key: None,
         }
     }
 }
@@ -0,0 +1,163 @@
+use std::{convert::TryFrom, sync::Arc};
+
+use serde::de::Error as _;
+
+use crate::{
+    raw::{RawBsonRef, RawDocument, RawIter},
+    Bson,
+    Document,
+};
+
+use super::{Error, Result};
+
+/// A BSON value whose strings and document keys are shared [`Arc<str>`]s produced by the interner
+/// passed to [`from_slice_interned`].
+///
+/// Values of any other type are held as the equivalent [`Bson`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum InternedBson {
+    /// A UTF-8 string.
+    String(Arc<str>),
+
+    /// An embedded document, as its key-value pairs in order.
+    Document(Vec<(Arc<str>, InternedBson)>),
+
+    /// An array.
+    Array(Vec<InternedBson>),
+
+    /// A value of any other type.
+    Other(Bson),
+}
+
+impl From<InternedBson> for Bson {
+    fn from(value: InternedBson) -> Self {
+        match value {
+            InternedBson::String(s) => Bson::String(s.as_ref().to_owned()),
+            InternedBson::Document(entries) => Bson::Document(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.as_ref().to_owned(), Bson::from(v)))
+                    .collect::<Document>(),
+            ),
+            InternedBson::Array(values) => {
+                Bson::Array(values.into_iter().map(Into::into).collect())
+            }
+            InternedBson::Other(bson) => bson,
+        }
+    }
+}
+
+enum Entries {
+    Document(Vec<(Arc<str>, InternedBson)>),
+    Array(Vec<InternedBson>),
+}
+
+/// A document or array that is being built, along with its key in the enclosing document.
+struct Frame<'a> {
+    iter: RawIter<'a>,
+    key: Option<Arc<str>>,
+    entries: Entries,
+}
+
+impl<'a> Frame<'a> {
+    fn push(&mut self, key: Option<Arc<str>>, value: InternedBson) {
+        match (&mut self.entries, key) {
+            (Entries::Document(entries), Some(key)) => entries.push((key, value)),
+            (Entries::Array(values), _) => values.push(value),
+            (Entries::Document(_), None) => unreachable!("document entries always have a key"),
+        }
+    }
+}
+
+/// Deserialize a document from a slice of BSON bytes into an [`InternedBson`] tree, passing every
+/// string value and document key through `interner` so that repeated strings can share storage.
+///
+/// The interner is called once per string, so a typical interner looks each string up in a set
+/// of previously seen values, as below. Array indexes are not passed to the interner.
+///
+/// ```
+/// use std::{collections::HashSet, sync::Arc};
+///
+/// use bson::de::InternedBson;
+///
+/// let mut seen: HashSet<Arc<str>> = HashSet::new();
+/// let interner = |s: &str| match seen.get(s) {
+///     Some(shared) => shared.clone(),
+///     None => {
+///         let shared: Arc<str> = s.into();
+///         seen.insert(shared.clone());
+///         shared
+///     }
+/// };
+///
+/// let bytes = bson::to_vec(&bson::doc! { "a": "books", "b": ["books"] })?;
+/// let doc = bson::from_slice_interned(&bytes, interner)?;
+/// match (&doc[0].1, &doc[1].1) {
+///     (InternedBson::String(a), InternedBson::Array(b)) => {
+///         assert_eq!(b[0], InternedBson::String(a.clone()));
+///         assert!(matches!(&b[0], InternedBson::String(b) if Arc::ptr_eq(a, b)));
+///     }
+///     _ => unreachable!(),
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn from_slice_interned(
+    bytes: &[u8],
+    mut interner: impl FnMut(&str) -> Arc<str>,
+) -> Result<Vec<(Arc<str>, InternedBson)>> {
+    let raw_error = |error: crate::raw::Error| {
+        let key = error.key().map(ToOwned::to_owned);
+        let error = Error::custom(error);
+        match key {
+            Some(key) => error.with_key(|| key),
+            None => error,
+        }
+    };
+
+    let doc = RawDocument::from_bytes(bytes).map_err(raw_error)?;
+    // An explicit stack rather than recursion, so that deeply nested input can't overflow the
+    // call stack.
+    let mut stack = vec![Frame {
+        iter: doc.iter_elements(),
+        key: None,
+        entries: Entries::Document(Vec::new()),
+    }];
+    while let Some(frame) = stack.last_mut() {
+        let elem = match frame.iter.next() {
+            Some(elem) => elem.map_err(raw_error)?,
+            None => {
+                let frame = stack.pop().unwrap();
+                let value = match frame.entries {
+                    Entries::Document(entries) => InternedBson::Document(entries),
+                    Entries::Array(values) => InternedBson::Array(values),
+                };
+                match (stack.last_mut(), value) {
+                    (Some(parent), value) => parent.push(frame.key, value),
+                    (None, InternedBson::Document(entries)) => return Ok(entries),
+                    (None, _) => unreachable!("the top level is a document"),
+                }
+                continue;
+            }
+        };
+
+        let key = match frame.entries {
+            Entries::Document(_) => Some(interner(elem.key())),
+            Entries::Array(_) => None,
+        };
+        let (iter, entries) = match elem.value().map_err(raw_error)? {
+            RawBsonRef::Document(doc) => (doc.iter_elements(), Entries::Document(Vec::new())),
+            RawBsonRef::Array(array) => (array.doc.iter_elements(), Entries::Array(Vec::new())),
+            RawBsonRef::String(s) => {
+                frame.push(key, InternedBson::String(interner(s)));
+                continue;
+            }
+            other => {
+                let value = Bson::try_from(other).map_err(raw_error)?;
+                frame.push(key, InternedBson::Other(value));
+                continue;
+            }
+        };
+        stack.push(Frame { iter, key, entries });
+    }
+    unreachable!("the top-level frame returns when it is finished")
+}
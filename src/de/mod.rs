@@ -20,17 +20,56 @@
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 //! Deserializer
+//!
+//! ## `Option` fields
+//!
+//! Fields of type `Option<T>` deserialize to `None` both when the key is absent from the document
+//! and when it is present with a BSON null value. This holds for every entry point, including
+//! [`from_document`], [`from_bson`], [`from_slice`], and deserializing from a
+//! [`RawDocument`](crate::RawDocument).
+//!
+//! ## Keys starting with `$`
+//!
+//! When deserializing into a plain map type such as `HashMap<String, T>` or `BTreeMap<String, T>`,
+//! keys are passed through literally, even if they look like extended JSON (e.g. `$oid` or
+//! `$numberLong`). Only [`Bson`](crate::Bson) and [`Document`](crate::Document) interpret such
+//! keys, since that is how they represent the BSON types that serde has no equivalent for; a
+//! document whose keys match that format, such as `{ "$oid": "abc" }`, will therefore fail to
+//! deserialize or be converted into the corresponding type when the target is [`Bson`](crate::Bson)
+//! or [`Document`](crate::Document).
+//!
+//! ## Borrowing keys
+//!
+//! When deserializing from bytes with [`from_slice`] or from a [`RawDocument`](crate::RawDocument),
+//! document keys are handed to the visitor as `&'de str` borrowed from the input, so map types
+//! with borrowed keys such as `HashMap<&str, T>` can be deserialized without copying the keys.
+//! The exception is [`from_slice_utf8_lossy`], which must allocate for any key containing invalid
+//! UTF-8. Deserializing from a [`Document`](crate::Document) or [`Bson`](crate::Bson) value always
+//! produces owned keys, so types with borrowed keys can't be deserialized that way.
+//!
+//! ## Repeated string values
+//!
+//! [`Bson::String`](crate::Bson::String) always owns its contents, so deserializing into
+//! [`Bson`](crate::Bson) or [`Document`](crate::Document) allocates every string value separately,
+//! even when the same value appears many times. [`from_slice_interned`] instead produces an
+//! [`InternedBson`] tree whose strings and keys are [`Arc<str>`](std::sync::Arc)s obtained from a
+//! caller-supplied interner, so repeated values can share storage.
 
 mod error;
+mod interned;
 mod raw;
 mod serde;
 
 pub use self::{
     error::{Error, Result},
+    interned::{from_slice_interned, InternedBson},
     serde::{Deserializer, DeserializerOptions},
 };
 
-use std::io::Read;
+use std::{
+    convert::{TryFrom, TryInto},
+    io::Read,
+};
 
 use crate::{
     bson::{Bson, Document, Timestamp},
@@ -42,7 +81,7 @@ use crate::{
 };
 
 use ::serde::{
-    de::{DeserializeOwned, Error as _, Unexpected},
+    de::{DeserializeOwned, DeserializeSeed, Error as _, Unexpected},
     Deserialize,
 };
 
@@ -71,36 +110,42 @@ enum DeserializerHint {
     RawBson,
 }
 
-pub(crate) fn read_string<R: Read + ?Sized>(reader: &mut R, utf8_lossy: bool) -> Result<String> {
-    let len = read_i32(reader)?;
-
-    // UTF-8 String must have at least 1 byte (the last 0x00).
-    if len < 1 {
-        return Err(Error::invalid_length(
-            len as usize,
-            &"UTF-8 string must have at least 1 byte",
-        ));
-    }
+/// The error for a string whose length prefix runs past the end of the input.
+pub(crate) fn string_length_out_of_bounds(len: i32, remaining: usize) -> Error {
+    Error::custom(format!(
+        "string length exceeds document bounds: {} bytes declared, but only {} remain",
+        len, remaining
+    ))
+}
 
-    let s = if utf8_lossy {
-        let mut buf = Vec::with_capacity(len as usize - 1);
-        reader.take(len as u64 - 1).read_to_end(&mut buf)?;
-        String::from_utf8_lossy(&buf).to_string()
-    } else {
-        let mut s = String::with_capacity(len as usize - 1);
-        reader.take(len as u64 - 1).read_to_string(&mut s)?;
-        s
+/// Computes the total length of a code with scope value implied by its contents, i.e. its own
+/// length prefix plus the encoded code string and scope document. `contents` are the bytes that
+/// follow the total length prefix. Returns `None` if they are too short to contain the string and
+/// scope length prefixes.
+pub(crate) fn code_with_scope_contents_length(contents: &[u8]) -> Option<i64> {
+    let read_len = |at: usize| -> Option<i64> {
+        let bytes = contents.get(at..at.checked_add(4)?)?;
+        Some(i32::from_le_bytes(bytes.try_into().ok()?) as i64)
     };
+    let string_len = read_len(0)?;
+    let scope_start = usize::try_from(4 + string_len).ok()?;
+    let scope_len = read_len(scope_start)?;
+    Some(4 + 4 + string_len + scope_len)
+}
 
-    // read the null terminator
-    if read_u8(reader)? != 0 {
-        return Err(Error::invalid_length(
-            len as usize,
-            &"contents of string longer than provided length",
-        ));
+/// The error message for a code with scope value whose total length prefix doesn't match the
+/// length of its contents.
+pub(crate) fn code_with_scope_length_mismatch(len: i32, contents_length: Option<i64>) -> String {
+    match contents_length {
+        Some(contents_length) => format!(
+            "code with scope length {} does not match the length of its contents ({} bytes)",
+            len, contents_length
+        ),
+        None => format!(
+            "code with scope length {} is too short to contain its code and scope",
+            len
+        ),
     }
-
-    Ok(s)
 }
 
 pub(crate) fn read_bool<R: Read>(mut reader: R) -> Result<bool> {
@@ -228,6 +273,18 @@ where
     Deserialize::deserialize(de)
 }
 
+/// Deserialize a value from the provided [`Bson`] value using a [`DeserializeSeed`], allowing
+/// context to be threaded through deserialization.
+///
+/// As with [`from_bson`], the deserializer presents itself as human readable.
+pub fn from_bson_seed<'de, S>(bson: Bson, seed: S) -> Result<S::Value>
+where
+    S: DeserializeSeed<'de>,
+{
+    let de = Deserializer::new(bson);
+    seed.deserialize(de)
+}
+
 /// Deserialize a `T` from the provided [`Bson`] value, configuring the underlying
 /// deserializer with the provided options.
 /// ```
@@ -291,12 +348,138 @@ where
     Deserialize::deserialize(de)
 }
 
-fn reader_to_vec<R: Read>(mut reader: R) -> Result<Vec<u8>> {
-    let length = read_i32(&mut reader)?;
+/// Describes an enum and its variants for use in error messages, e.g.
+/// ``enum `Color` with variants `Red`, `Green` ``.
+pub(crate) fn describe_enum(name: &str, variants: &[&str]) -> String {
+    if variants.is_empty() {
+        return format!("enum `{}` with no variants", name);
+    }
+    let variants: Vec<_> = variants.iter().map(|v| format!("`{}`", v)).collect();
+    format!("enum `{}` with variants {}", name, variants.join(", "))
+}
 
+/// Converts a [`Decimal128`] to an `i128` for `deserialize_i128`, erroring if it is not an integer
+/// in range.
+pub(crate) fn decimal128_to_i128(d128: Decimal128) -> Result<i128> {
+    d128.to_i128().ok_or_else(|| {
+        Error::invalid_value(
+            Unexpected::Other(&format!("Decimal128 {}", d128)),
+            &"an integer in the range of i128",
+        )
+    })
+}
+
+fn check_document_length(length: i32) -> Result<()> {
     if length < MIN_BSON_DOCUMENT_SIZE {
         return Err(Error::custom("document size too small"));
     }
+    Ok(())
+}
+
+/// Reads the declared length of the BSON document at the start of `bytes` without parsing the rest
+/// of the document.
+///
+/// This is useful when splitting a buffer of concatenated documents. An error is returned if fewer
+/// than 4 bytes are available or if the declared length is smaller than the minimum size of a BSON
+/// document. The returned length is not checked against the length of `bytes`.
+///
+/// ```
+/// use bson::{doc, Document};
+///
+/// let mut buffer = bson::to_vec(&doc! { "x": 1 })?;
+/// buffer.extend(bson::to_vec(&doc! { "y": "two" })?);
+///
+/// let mut remaining = buffer.as_slice();
+/// let mut docs = Vec::new();
+/// while !remaining.is_empty() {
+///     let length = bson::peek_document_length(remaining)?;
+///     docs.push(bson::from_slice::<Document>(&remaining[..length])?);
+///     remaining = &remaining[length..];
+/// }
+/// assert_eq!(docs, vec![doc! { "x": 1 }, doc! { "y": "two" }]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn peek_document_length(mut bytes: &[u8]) -> Result<usize> {
+    if bytes.len() < 4 {
+        return Err(Error::EndOfStream);
+    }
+    let length = read_i32(&mut bytes)?;
+    check_document_length(length)?;
+    Ok(length as usize)
+}
+
+/// The result of checking whether a buffer begins with a complete BSON document, as returned by
+/// [`document_frame_len`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameStatus {
+    /// The buffer does not yet contain a complete document. At least `needed` more bytes must be
+    /// read before checking again; this is exact once the 4-byte length prefix is available.
+    Incomplete {
+        /// The number of additional bytes needed.
+        needed: usize,
+    },
+
+    /// The buffer begins with a complete document of `len` bytes.
+    Complete {
+        /// The length of the document, including its length prefix and null terminator.
+        len: usize,
+    },
+
+    /// The buffer does not begin with a valid document, either because its declared length is
+    /// smaller than the minimum size of a BSON document or because the byte at the declared end
+    /// of the document is not a null terminator.
+    Invalid,
+}
+
+/// Checks whether `buf` begins with a complete BSON document by reading only its length prefix
+/// and null terminator, without parsing its contents.
+///
+/// This is the framing primitive needed to split documents out of a stream that is read in
+/// arbitrary chunks, e.g. when implementing an asynchronous codec. A document reported as
+/// [`FrameStatus::Complete`] may still fail to parse. The declared length is not checked against
+/// any maximum, so callers reading from untrusted sources should enforce their own limit before
+/// buffering the number of bytes reported as needed.
+///
+/// ```
+/// use bson::{doc, FrameStatus};
+///
+/// let bytes = bson::to_vec(&doc! { "x": 1 })?;
+///
+/// assert_eq!(bson::document_frame_len(&bytes[..2]), FrameStatus::Incomplete { needed: 2 });
+/// assert_eq!(
+///     bson::document_frame_len(&bytes[..6]),
+///     FrameStatus::Incomplete { needed: bytes.len() - 6 }
+/// );
+/// assert_eq!(bson::document_frame_len(&bytes), FrameStatus::Complete { len: bytes.len() });
+/// assert_eq!(bson::document_frame_len(&[4, 0, 0, 0]), FrameStatus::Invalid);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn document_frame_len(buf: &[u8]) -> FrameStatus {
+    let prefix: [u8; 4] = match buf.get(..4) {
+        Some(prefix) => prefix.try_into().unwrap(),
+        None => {
+            return FrameStatus::Incomplete {
+                needed: 4 - buf.len(),
+            }
+        }
+    };
+    let length = i32::from_le_bytes(prefix);
+    if length < MIN_BSON_DOCUMENT_SIZE {
+        return FrameStatus::Invalid;
+    }
+    let length = length as usize;
+    match buf.get(length - 1) {
+        None => FrameStatus::Incomplete {
+            needed: length - buf.len(),
+        },
+        Some(0) => FrameStatus::Complete { len: length },
+        Some(_) => FrameStatus::Invalid,
+    }
+}
+
+fn reader_to_vec<R: Read>(mut reader: R) -> Result<Vec<u8>> {
+    let length = read_i32(&mut reader)?;
+    check_document_length(length)?;
 
     let mut bytes = Vec::with_capacity(length as usize);
     write_i32(&mut bytes, length).map_err(Error::custom)?;
@@ -339,6 +522,176 @@ where
     T::deserialize(&mut deserializer)
 }
 
+/// Deserialize a value from a slice of BSON bytes using a [`DeserializeSeed`], allowing context
+/// to be threaded through deserialization.
+///
+/// ```
+/// use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+///
+/// /// Deserializes an `i32` and adds an offset supplied at runtime.
+/// struct Offset(i32);
+///
+/// impl<'de> DeserializeSeed<'de> for Offset {
+///     type Value = i32;
+///
+///     fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<i32, D::Error> {
+///         #[derive(serde::Deserialize)]
+///         struct Data {
+///             x: i32,
+///         }
+///         Ok(Data::deserialize(deserializer)?.x + self.0)
+///     }
+/// }
+///
+/// let bytes = bson::to_vec(&bson::doc! { "x": 1 })?;
+/// assert_eq!(bson::from_slice_seed(&bytes, Offset(10))?, 11);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn from_slice_seed<'de, S>(bytes: &'de [u8], seed: S) -> Result<S::Value>
+where
+    S: DeserializeSeed<'de>,
+{
+    let mut deserializer = raw::Deserializer::new(bytes, false);
+    seed.deserialize(&mut deserializer)
+}
+
+/// How string values are checked for valid UTF-8 when deserializing from BSON bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Utf8Validation {
+    /// Invalid UTF-8 results in an error. This is the default.
+    #[default]
+    Strict,
+
+    /// Invalid UTF-8 sequences are replaced with the Unicode replacement character.
+    Lossy,
+}
+
+/// How repeated keys within a single BSON document are handled when deserializing from BSON bytes.
+///
+/// The BSON format does not forbid duplicate keys, and MongoDB keeps the last value when it
+/// encounters them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DuplicateKeyPolicy {
+    /// Every occurrence is passed through to the type being deserialized. When deserializing a
+    /// [`Document`] or a map this means the last value wins. This is the default.
+    #[default]
+    KeepLast,
+
+    /// Only the first occurrence of a key is deserialized; later occurrences are skipped.
+    KeepFirst,
+
+    /// A repeated key results in an error.
+    Error,
+}
+
+/// Options used to configure deserialization from BSON bytes via [`from_slice_with_options`].
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct RawDeserializerOptions {
+    /// How string values are checked for valid UTF-8. The default is
+    /// [`Utf8Validation::Strict`].
+    pub utf8: Utf8Validation,
+
+    /// How repeated keys within a document are handled. The default is
+    /// [`DuplicateKeyPolicy::KeepLast`].
+    pub duplicate_keys: DuplicateKeyPolicy,
+
+    /// Whether the deprecated `Undefined`, `DbPointer`, and `Symbol` types result in an error
+    /// wherever they occur in the input, including within values that are otherwise skipped and
+    /// within values borrowed or copied as raw BSON, such as a
+    /// [`RawDocumentBuf`](crate::RawDocumentBuf) field. The default is `false`.
+    pub reject_deprecated_types: bool,
+
+    utf8_unchecked: bool,
+}
+
+impl RawDeserializerOptions {
+    /// Create a builder used to construct a [`RawDeserializerOptions`].
+    pub fn builder() -> RawDeserializerOptionsBuilder {
+        RawDeserializerOptionsBuilder {
+            options: Default::default(),
+        }
+    }
+}
+
+/// Builder used to construct a [`RawDeserializerOptions`].
+pub struct RawDeserializerOptionsBuilder {
+    options: RawDeserializerOptions,
+}
+
+impl RawDeserializerOptionsBuilder {
+    /// Set the value for [`RawDeserializerOptions::utf8`].
+    pub fn utf8(mut self, val: Utf8Validation) -> Self {
+        self.options.utf8 = val;
+        self
+    }
+
+    /// Set the value for [`RawDeserializerOptions::duplicate_keys`].
+    pub fn duplicate_keys(mut self, val: DuplicateKeyPolicy) -> Self {
+        self.options.duplicate_keys = val;
+        self
+    }
+
+    /// Set the value for [`RawDeserializerOptions::reject_deprecated_types`].
+    pub fn reject_deprecated_types(mut self, val: bool) -> Self {
+        self.options.reject_deprecated_types = val;
+        self
+    }
+
+    /// Skip UTF-8 validation of string values entirely, borrowing them from the input as-is.
+    ///
+    /// This is intended for high-throughput paths reading data from a trusted producer. It takes
+    /// precedence over [`Utf8Validation::Strict`] but not over [`Utf8Validation::Lossy`].
+    ///
+    /// # Safety
+    ///
+    /// Every string value in the bytes later passed to [`from_slice_with_options`] with these
+    /// options must be valid UTF-8; otherwise, the resulting `&str` values are undefined
+    /// behavior.
+    pub unsafe fn utf8_unchecked(mut self) -> Self {
+        self.options.utf8_unchecked = true;
+        self
+    }
+
+    /// Consume this builder and produce a [`RawDeserializerOptions`].
+    pub fn build(self) -> RawDeserializerOptions {
+        self.options
+    }
+}
+
+/// Deserialize an instance of type `T` from a slice of BSON bytes using the provided options.
+///
+/// ```
+/// # use serde::Deserialize;
+/// use bson::de::{RawDeserializerOptions, Utf8Validation};
+///
+/// #[derive(Deserialize)]
+/// struct Data {
+///     s: String,
+/// }
+///
+/// // "s": "a\xFFb"
+/// let bytes = b"\x10\x00\x00\x00\x02s\x00\x04\x00\x00\x00a\xFFb\x00\x00";
+/// assert!(bson::from_slice::<Data>(bytes).is_err());
+///
+/// let options = RawDeserializerOptions::builder().utf8(Utf8Validation::Lossy).build();
+/// let data: Data = bson::from_slice_with_options(bytes, options)?;
+/// assert_eq!(data.s, "a\u{FFFD}b");
+/// # Ok::<(), bson::de::Error>(())
+/// ```
+pub fn from_slice_with_options<'de, T>(
+    bytes: &'de [u8],
+    options: RawDeserializerOptions,
+) -> Result<T>
+where
+    T: Deserialize<'de>,
+{
+    let mut deserializer = raw::Deserializer::new_with_options(bytes, options);
+    T::deserialize(&mut deserializer)
+}
+
 /// Deserialize an instance of type `T` from a slice of BSON bytes, replacing any invalid UTF-8
 /// sequences with the Unicode replacement character.
 ///
@@ -1,19 +1,20 @@
 use std::{
     borrow::Cow,
+    collections::HashSet,
     convert::TryInto,
     io::{ErrorKind, Read},
     sync::Arc,
 };
 
 use serde::{
-    de::{EnumAccess, Error as SerdeError, IntoDeserializer, MapAccess, VariantAccess},
+    de::{EnumAccess, Error as SerdeError, IgnoredAny, IntoDeserializer, MapAccess, VariantAccess},
     forward_to_deserialize_any,
     Deserializer as SerdeDeserializer,
 };
 
 use crate::{
     oid::ObjectId,
-    raw::{RawBinaryRef, RAW_ARRAY_NEWTYPE, RAW_BSON_NEWTYPE, RAW_DOCUMENT_NEWTYPE},
+    raw::{RawBinaryRef, RawBsonRef, RAW_ARRAY_NEWTYPE, RAW_BSON_NEWTYPE, RAW_DOCUMENT_NEWTYPE},
     serde_helpers::HUMAN_READABLE_NEWTYPE,
     spec::{BinarySubtype, ElementType},
     uuid::UUID_NEWTYPE_NAME,
@@ -26,16 +27,23 @@ use crate::{
 };
 
 use super::{
+    code_with_scope_contents_length,
+    code_with_scope_length_mismatch,
+    decimal128_to_i128,
+    describe_enum,
     read_bool,
     read_f128,
     read_f64,
     read_i32,
     read_i64,
-    read_string,
     read_u8,
+    string_length_out_of_bounds,
     DeserializerHint,
+    DuplicateKeyPolicy,
     Error,
+    RawDeserializerOptions,
     Result,
+    Utf8Validation,
     MAX_BSON_SIZE,
     MIN_CODE_WITH_SCOPE_SIZE,
 };
@@ -54,6 +62,10 @@ pub(crate) struct Deserializer<'de> {
     current_type: ElementType,
 
     human_readable: bool,
+
+    duplicate_keys: DuplicateKeyPolicy,
+
+    reject_deprecated_types: bool,
 }
 
 /// Enum used to determine what the type of document being deserialized is in
@@ -69,9 +81,19 @@ impl<'de> Deserializer<'de> {
             bytes: BsonBuf::new(buf, utf8_lossy),
             current_type: ElementType::EmbeddedDocument,
             human_readable: false,
+            duplicate_keys: DuplicateKeyPolicy::KeepLast,
+            reject_deprecated_types: false,
         }
     }
 
+    pub(crate) fn new_with_options(buf: &'de [u8], options: RawDeserializerOptions) -> Self {
+        let mut de = Self::new(buf, options.utf8 == Utf8Validation::Lossy);
+        de.bytes.utf8_unchecked = options.utf8_unchecked;
+        de.duplicate_keys = options.duplicate_keys;
+        de.reject_deprecated_types = options.reject_deprecated_types;
+        de
+    }
+
     /// Ensure the entire document was visited, returning an error if not.
     /// Will read the trailing null byte if necessary (i.e. the visitor stopped after visiting
     /// exactly the number of elements in the document).
@@ -155,6 +177,10 @@ impl<'de> Deserializer<'de> {
 
                 let doc = RawDocument::from_bytes(self.bytes.read_slice(len as usize)?)
                     .map_err(Error::custom)?;
+                // the contents are handed out without being read element by element here
+                if self.reject_deprecated_types {
+                    check_deprecated_types(doc)?;
+                }
 
                 let access = if is_array {
                     RawDocumentAccess::for_array(doc)
@@ -175,15 +201,25 @@ impl<'de> Deserializer<'de> {
     where
         F: FnOnce(DocumentAccess<'_, 'de>) -> Result<O>,
     {
+        let start = self.bytes.bytes_read();
         let mut length_remaining = read_i32(&mut self.bytes)?;
         if length_remaining < 4 {
             return Err(Error::custom("invalid length, less than min document size"));
         }
+        let parent_end = self.bytes.document_end;
+        self.bytes.document_end = parent_end.min(start + length_remaining as usize);
         length_remaining -= 4;
+        let seen_keys = match self.duplicate_keys {
+            DuplicateKeyPolicy::KeepLast => None,
+            _ => Some(HashSet::new()),
+        };
         let out = f(DocumentAccess {
             root_deserializer: self,
             length_remaining: &mut length_remaining,
+            key_start: None,
+            seen_keys,
         });
+        self.bytes.document_end = parent_end;
 
         if out.is_ok() {
             self.end_document(length_remaining)?;
@@ -202,6 +238,15 @@ impl<'de> Deserializer<'de> {
         let element_type = ElementType::from(tag)
             .ok_or_else(|| Error::custom(format!("invalid element type: {}", tag)))?;
 
+        if self.reject_deprecated_types
+            && matches!(
+                element_type,
+                ElementType::Undefined | ElementType::DbPointer | ElementType::Symbol
+            )
+        {
+            return Err(deprecated_type_error(element_type));
+        }
+
         self.current_type = element_type;
         Ok(Some(element_type))
     }
@@ -282,25 +327,21 @@ impl<'de> Deserializer<'de> {
                 let mut de = DbPointerDeserializer::new(&mut *self, hint);
                 visitor.visit_map(DbPointerAccess::new(&mut de))
             }
-            ElementType::JavaScriptCode => {
-                let utf8_lossy = self.bytes.utf8_lossy;
-
-                match hint {
-                    DeserializerHint::RawBson => visitor.visit_map(RawBsonAccess::new(
-                        "$code",
-                        BsonContent::Str(self.bytes.read_borrowed_str()?),
-                    )),
-                    _ => {
-                        let code = read_string(&mut self.bytes, utf8_lossy)?;
-                        let doc = Bson::JavaScriptCode(code).into_extended_document(false);
-                        visitor.visit_map(MapDeserializer::new(
-                            doc,
-                            #[allow(deprecated)]
-                            DeserializerOptions::builder().human_readable(false).build(),
-                        ))
-                    }
+            ElementType::JavaScriptCode => match hint {
+                DeserializerHint::RawBson => visitor.visit_map(RawBsonAccess::new(
+                    "$code",
+                    BsonContent::Str(self.bytes.read_borrowed_str()?),
+                )),
+                _ => {
+                    let code = self.bytes.read_str()?.into_owned();
+                    let doc = Bson::JavaScriptCode(code).into_extended_document(false);
+                    visitor.visit_map(MapDeserializer::new(
+                        doc,
+                        #[allow(deprecated)]
+                        DeserializerOptions::builder().human_readable(false).build(),
+                    ))
                 }
-            }
+            },
             ElementType::JavaScriptCodeWithScope => {
                 let len = read_i32(&mut self.bytes)?;
 
@@ -324,6 +365,15 @@ impl<'de> Deserializer<'de> {
                     ));
                 }
 
+                let contents_length =
+                    code_with_scope_contents_length(self.bytes.slice((len - 4) as usize)?);
+                if contents_length != Some(len as i64) {
+                    return Err(Error::custom(code_with_scope_length_mismatch(
+                        len,
+                        contents_length,
+                    )));
+                }
+
                 let mut de = CodeWithScopeDeserializer::new(&mut *self, hint, len - 4);
                 let out = visitor.visit_map(CodeWithScopeAccess::new(&mut de));
 
@@ -340,25 +390,21 @@ impl<'de> Deserializer<'de> {
 
                 out
             }
-            ElementType::Symbol => {
-                let utf8_lossy = self.bytes.utf8_lossy;
-
-                match hint {
-                    DeserializerHint::RawBson => visitor.visit_map(RawBsonAccess::new(
-                        "$symbol",
-                        BsonContent::Str(self.bytes.read_borrowed_str()?),
-                    )),
-                    _ => {
-                        let symbol = read_string(&mut self.bytes, utf8_lossy)?;
-                        let doc = Bson::Symbol(symbol).into_extended_document(false);
-                        visitor.visit_map(MapDeserializer::new(
-                            doc,
-                            #[allow(deprecated)]
-                            DeserializerOptions::builder().human_readable(false).build(),
-                        ))
-                    }
+            ElementType::Symbol => match hint {
+                DeserializerHint::RawBson => visitor.visit_map(RawBsonAccess::new(
+                    "$symbol",
+                    BsonContent::Str(self.bytes.read_borrowed_str()?),
+                )),
+                _ => {
+                    let symbol = self.bytes.read_str()?.into_owned();
+                    let doc = Bson::Symbol(symbol).into_extended_document(false);
+                    visitor.visit_map(MapDeserializer::new(
+                        doc,
+                        #[allow(deprecated)]
+                        DeserializerOptions::builder().human_readable(false).build(),
+                    ))
                 }
-            }
+            },
             ElementType::Timestamp => {
                 let ts = Timestamp::from_reader(&mut self.bytes)?;
                 let mut d = TimestampDeserializer::new(ts);
@@ -404,8 +450,8 @@ impl<'de, 'a> serde::de::Deserializer<'de> for &'a mut Deserializer<'de> {
 
     fn deserialize_enum<V>(
         self,
-        _name: &str,
-        _variants: &'static [&'static str],
+        name: &str,
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
@@ -416,7 +462,23 @@ impl<'de, 'a> serde::de::Deserializer<'de> for &'a mut Deserializer<'de> {
             ElementType::EmbeddedDocument => {
                 self.access_document(|access| visitor.visit_enum(access))
             }
-            t => Err(Error::custom(format!("expected enum, instead got {:?}", t))),
+            t => Err(Error::custom(format!(
+                "expected {}, instead got {:?}",
+                describe_enum(name, variants),
+                t
+            ))),
+        }
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self.current_type {
+            ElementType::Decimal128 => {
+                visitor.visit_i128(decimal128_to_i128(read_f128(&mut self.bytes)?)?)
+            }
+            _ => self.deserialize_any(visitor),
         }
     }
 
@@ -482,11 +544,57 @@ impl<'de, 'a> serde::de::Deserializer<'de> for &'a mut Deserializer<'de> {
     }
 }
 
+fn deprecated_type_error(element_type: ElementType) -> Error {
+    Error::custom(format!(
+        "encountered deprecated BSON type {:?}",
+        element_type
+    ))
+}
+
+/// Returns an error if a deprecated element type occurs anywhere within `doc`, including in
+/// nested documents, arrays, and code-with-scope scopes.
+fn check_deprecated_types(doc: &RawDocument) -> Result<()> {
+    // An explicit stack rather than recursion, so that deeply nested input can't overflow the
+    // call stack.
+    let mut stack = vec![doc.iter_elements()];
+    while let Some(iter) = stack.last_mut() {
+        let elem = match iter.next() {
+            Some(elem) => elem.map_err(Error::custom)?,
+            None => {
+                stack.pop();
+                continue;
+            }
+        };
+        match elem.element_type() {
+            element_type @ (ElementType::Undefined
+            | ElementType::DbPointer
+            | ElementType::Symbol) => return Err(deprecated_type_error(element_type)),
+            ElementType::EmbeddedDocument
+            | ElementType::Array
+            | ElementType::JavaScriptCodeWithScope => match elem.value().map_err(Error::custom)? {
+                RawBsonRef::Document(doc) => stack.push(doc.iter_elements()),
+                RawBsonRef::Array(array) => stack.push(array.doc.iter_elements()),
+                RawBsonRef::JavaScriptCodeWithScope(cws) => stack.push(cws.scope.iter_elements()),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 /// Struct for accessing documents for deserialization purposes.
 /// This is used to deserialize maps, structs, sequences, and enums.
 struct DocumentAccess<'d, 'de> {
     root_deserializer: &'d mut Deserializer<'de>,
     length_remaining: &'d mut i32,
+
+    /// Offset of the most recently read key, used to attach it to errors lazily.
+    key_start: Option<usize>,
+
+    /// The keys seen so far in this document, tracked only when the duplicate key policy requires
+    /// it.
+    seen_keys: Option<HashSet<String>>,
 }
 
 impl<'d, 'de> DocumentAccess<'d, 'de> {
@@ -527,6 +635,29 @@ impl<'d, 'de> DocumentAccess<'d, 'de> {
         Ok(out)
     }
 
+    /// Applies the deserializer's [`DuplicateKeyPolicy`] to the key starting at `key_start`,
+    /// returning whether the element was skipped.
+    fn skip_duplicate_key(&mut self, key_start: usize) -> Result<bool> {
+        let seen_keys = match self.seen_keys {
+            Some(ref mut seen_keys) => seen_keys,
+            None => return Ok(false),
+        };
+        let key = self.root_deserializer.bytes.cstr_lossy_at(key_start);
+        if seen_keys.contains(&key) {
+            if self.root_deserializer.duplicate_keys == DuplicateKeyPolicy::Error {
+                return Err(Error::custom(format!("duplicate key `{}`", key)));
+            }
+            // keep the first occurrence by skipping over this element entirely
+            self.read(|s| {
+                s.root_deserializer.deserialize_cstr()?;
+                s.root_deserializer.deserialize_ignored_any(IgnoredAny)
+            })?;
+            return Ok(true);
+        }
+        seen_keys.insert(key);
+        Ok(false)
+    }
+
     /// Read the next value from the document.
     fn read_next_value<V>(&mut self, seed: V) -> Result<V::Value>
     where
@@ -543,8 +674,15 @@ impl<'d, 'de> serde::de::MapAccess<'de> for DocumentAccess<'d, 'de> {
     where
         K: serde::de::DeserializeSeed<'de>,
     {
-        if self.read_next_type()?.is_none() {
-            return Ok(None);
+        loop {
+            if self.read_next_type()?.is_none() {
+                return Ok(None);
+            }
+            let key_start = self.root_deserializer.bytes.bytes_read();
+            self.key_start = Some(key_start);
+            if !self.skip_duplicate_key(key_start)? {
+                break;
+            }
         }
 
         self.read(|s| {
@@ -559,7 +697,14 @@ impl<'d, 'de> serde::de::MapAccess<'de> for DocumentAccess<'d, 'de> {
     where
         V: serde::de::DeserializeSeed<'de>,
     {
-        self.read_next_value(seed)
+        let key_start = self.key_start.take();
+        self.read_next_value(seed).map_err(|e| {
+            e.with_key(|| {
+                key_start
+                    .map(|start| self.root_deserializer.bytes.cstr_lossy_at(start))
+                    .unwrap_or_default()
+            })
+        })
     }
 }
 
@@ -1719,6 +1864,14 @@ struct BsonBuf<'a> {
     /// Whether or not to insert replacement characters in place of invalid UTF-8 sequences when
     /// deserializing strings.
     utf8_lossy: bool,
+
+    /// Whether strings are trusted to be valid UTF-8 and returned without validation. This is only
+    /// set via the unsafe [`RawDeserializerOptionsBuilder::utf8_unchecked`].
+    utf8_unchecked: bool,
+
+    /// The offset just past the end of the document currently being read, which length-prefixed
+    /// values within it must not run past.
+    document_end: usize,
 }
 
 impl<'a> Read for BsonBuf<'a> {
@@ -1736,6 +1889,8 @@ impl<'a> BsonBuf<'a> {
             bytes,
             index: 0,
             utf8_lossy,
+            utf8_unchecked: false,
+            document_end: bytes.len(),
         }
     }
 
@@ -1743,10 +1898,22 @@ impl<'a> BsonBuf<'a> {
         self.index
     }
 
+    /// Reads the cstring starting at `start` without advancing, for use in error messages.
+    fn cstr_lossy_at(&self, start: usize) -> String {
+        let bytes = self.bytes.get(start..).unwrap_or_default();
+        let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        String::from_utf8_lossy(&bytes[..end]).into_owned()
+    }
+
     fn bytes_remaining(&self) -> usize {
         self.bytes.len() - self.bytes_read()
     }
 
+    /// The number of bytes left in the current document before its null terminator.
+    fn document_bytes_remaining(&self) -> usize {
+        self.document_end.saturating_sub(self.index + 1)
+    }
+
     /// Verify the index has not run out of bounds.
     fn index_check(&self) -> std::io::Result<()> {
         if self.index >= self.bytes.len() {
@@ -1762,6 +1929,10 @@ impl<'a> BsonBuf<'a> {
         let bytes = &self.bytes[start..self.index];
         let s = if utf8_lossy_override.unwrap_or(self.utf8_lossy) {
             String::from_utf8_lossy(bytes)
+        } else if self.utf8_unchecked {
+            // SAFETY: the caller of `RawDeserializerOptionsBuilder::utf8_unchecked` guaranteed
+            // that all strings in the input are valid UTF-8.
+            Cow::Borrowed(unsafe { std::str::from_utf8_unchecked(bytes) })
         } else {
             Cow::Borrowed(std::str::from_utf8(bytes).map_err(Error::custom)?)
         };
@@ -1804,6 +1975,12 @@ impl<'a> BsonBuf<'a> {
             ));
         }
 
+        // the contents and null terminator must fit in the rest of the enclosing document
+        let remaining = self.document_bytes_remaining();
+        if len as usize > remaining {
+            return Err(string_length_out_of_bounds(len, remaining));
+        }
+
         self.index += (len - 1) as usize;
         self.index_check()?;
 
@@ -33,7 +33,7 @@ use crate::{
     Decimal128,
 };
 
-use super::{raw::Decimal128Access, DeserializerHint};
+use super::{decimal128_to_i128, describe_enum, raw::Decimal128Access, DeserializerHint};
 
 pub(crate) struct BsonVisitor;
 
@@ -433,7 +433,9 @@ impl<'de> Visitor<'de> for BsonVisitor {
 
                 "$regularExpression" => {
                     let re = visitor.next_value::<extjson::models::RegexBody>()?;
-                    return Ok(Bson::RegularExpression(Regex::new(re.pattern, re.options)));
+                    return Ok(Bson::RegularExpression(Regex::from_strings(
+                        re.pattern, re.options,
+                    )));
                 }
 
                 "$dbPointer" => {
@@ -589,6 +591,11 @@ pub struct DeserializerOptions {
     /// The default is true.
     #[deprecated = "use bson::serde_helpers::HumanReadable"]
     pub human_readable: Option<bool>,
+
+    /// Whether the deprecated `Undefined`, `DbPointer`, and `Symbol` types result in an error
+    /// wherever they occur in the input, including within values that are otherwise skipped. The
+    /// default is `false`.
+    pub reject_deprecated_types: bool,
 }
 
 impl DeserializerOptions {
@@ -614,6 +621,12 @@ impl DeserializerOptionsBuilder {
         self
     }
 
+    /// Set the value for [`DeserializerOptions::reject_deprecated_types`].
+    pub fn reject_deprecated_types(mut self, val: bool) -> Self {
+        self.options.reject_deprecated_types = val;
+        self
+    }
+
     /// Consume this builder and produce a [`DeserializerOptions`].
     pub fn build(self) -> DeserializerOptions {
         self.options
@@ -647,6 +660,18 @@ impl Deserializer {
             None => return Err(crate::de::Error::EndOfStream),
         };
 
+        if self.options.reject_deprecated_types
+            && matches!(
+                value,
+                Bson::Undefined | Bson::DbPointer(_) | Bson::Symbol(_)
+            )
+        {
+            return Err(Error::custom(format!(
+                "encountered deprecated BSON type {:?}",
+                value.element_type()
+            )));
+        }
+
         let is_rawbson = matches!(hint, DeserializerHint::RawBson);
 
         if let DeserializerHint::BinarySubtype(expected_subtype) = hint {
@@ -754,6 +779,49 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         }
     }
 
+    #[inline]
+    fn deserialize_tuple<V>(mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value.take() {
+            Some(Bson::Array(values)) => SeqDeserializer {
+                len: values.len(),
+                iter: values.into_iter(),
+                options: self.options,
+            }
+            .deserialize_tuple(len, visitor),
+            value => {
+                self.value = value;
+                self.deserialize_any(visitor)
+            }
+        }
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    #[inline]
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(Bson::Decimal128(d128)) => visitor.visit_i128(decimal128_to_i128(d128)?),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
     #[inline]
     fn deserialize_option<V>(self, visitor: V) -> crate::de::Result<V::Value>
     where
@@ -769,8 +837,8 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     #[inline]
     fn deserialize_enum<V>(
         mut self,
-        _name: &str,
-        _variants: &'static [&'static str],
+        name: &str,
+        variants: &'static [&'static str],
         visitor: V,
     ) -> crate::de::Result<V::Value>
     where
@@ -790,7 +858,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
             Some(v) => {
                 return Err(crate::de::Error::invalid_type(
                     v.as_unexpected(),
-                    &"expected an enum",
+                    &describe_enum(name, variants).as_str(),
                 ));
             }
             None => {
@@ -805,7 +873,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
             None => {
                 return Err(crate::de::Error::invalid_value(
                     Unexpected::Other("empty document"),
-                    &"variant name",
+                    &format!("a variant of {}", describe_enum(name, variants)).as_str(),
                 ))
             }
         };
@@ -889,9 +957,7 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         deserialize_seq();
         deserialize_map();
         deserialize_unit_struct(name: &'static str);
-        deserialize_tuple_struct(name: &'static str, len: usize);
         deserialize_struct(name: &'static str, fields: &'static [&'static str]);
-        deserialize_tuple(len: usize);
         deserialize_identifier();
         deserialize_ignored_any();
         deserialize_byte_buf();
@@ -944,7 +1010,7 @@ impl<'de> VariantAccess<'de> for VariantDeserializer {
         seed.deserialize(dec)
     }
 
-    fn tuple_variant<V>(mut self, _len: usize, visitor: V) -> crate::de::Result<V::Value>
+    fn tuple_variant<V>(mut self, len: usize, visitor: V) -> crate::de::Result<V::Value>
     where
         V: Visitor<'de>,
     {
@@ -955,7 +1021,7 @@ impl<'de> VariantAccess<'de> for VariantDeserializer {
                     iter: fields.into_iter(),
                     options: self.options,
                 };
-                de.deserialize_any(visitor)
+                de.deserialize_tuple(len, visitor)
             }
             other => Err(crate::de::Error::invalid_type(
                 other.as_unexpected(),
@@ -977,6 +1043,7 @@ impl<'de> VariantAccess<'de> for VariantDeserializer {
                 let de = MapDeserializer {
                     len: fields.len(),
                     iter: fields.into_iter(),
+                    key: None,
                     value: None,
                     options: self.options,
                 };
@@ -1011,6 +1078,33 @@ impl<'de> de::Deserializer<'de> for SeqDeserializer {
         }
     }
 
+    #[inline]
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> crate::de::Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.len != len {
+            return Err(crate::de::Error::custom(format!(
+                "expected array of length {}, found {}",
+                len, self.len
+            )));
+        }
+        visitor.visit_seq(self)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> crate::de::Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
     forward_to_deserialize! {
         deserialize_bool();
         deserialize_u8();
@@ -1033,9 +1127,7 @@ impl<'de> de::Deserializer<'de> for SeqDeserializer {
         deserialize_map();
         deserialize_unit_struct(name: &'static str);
         deserialize_newtype_struct(name: &'static str);
-        deserialize_tuple_struct(name: &'static str, len: usize);
         deserialize_struct(name: &'static str, fields: &'static [&'static str]);
-        deserialize_tuple(len: usize);
         deserialize_enum(name: &'static str, variants: &'static [&'static str]);
         deserialize_identifier();
         deserialize_ignored_any();
@@ -1070,6 +1162,7 @@ impl<'de> SeqAccess<'de> for SeqDeserializer {
 
 pub(crate) struct MapDeserializer {
     pub(crate) iter: IntoIter,
+    pub(crate) key: Option<String>,
     pub(crate) value: Option<Bson>,
     pub(crate) len: usize,
     pub(crate) options: DeserializerOptions,
@@ -1081,6 +1174,7 @@ impl MapDeserializer {
         MapDeserializer {
             iter: doc.into_iter(),
             len,
+            key: None,
             value: None,
             options: options.into().unwrap_or_default(),
         }
@@ -1098,8 +1192,12 @@ impl<'de> MapAccess<'de> for MapDeserializer {
             Some((key, value)) => {
                 self.len -= 1;
                 self.value = Some(value);
+                let key = self.key.insert(key);
 
-                let de = Deserializer::new_with_options(Bson::String(key), self.options.clone());
+                let de = KeyDeserializer {
+                    key,
+                    options: &self.options,
+                };
                 match seed.deserialize(de) {
                     Ok(val) => Ok(Some(val)),
                     Err(e) => Err(e),
@@ -1115,7 +1213,9 @@ impl<'de> MapAccess<'de> for MapDeserializer {
     {
         let value = self.value.take().ok_or(crate::de::Error::EndOfStream)?;
         let de = Deserializer::new_with_options(value, self.options.clone());
+        let key = self.key.take();
         seed.deserialize(de)
+            .map_err(|e| e.with_key(|| key.unwrap_or_default()))
     }
 
     fn size_hint(&self) -> Option<usize> {
@@ -1123,6 +1223,73 @@ impl<'de> MapAccess<'de> for MapDeserializer {
     }
 }
 
+/// Deserializes a document key borrowed from a [`MapDeserializer`], which keeps the key so that it
+/// can be attached to errors from the corresponding value.
+struct KeyDeserializer<'a> {
+    key: &'a str,
+    options: &'a DeserializerOptions,
+}
+
+impl<'a> KeyDeserializer<'a> {
+    fn into_owned(self) -> Deserializer {
+        Deserializer::new_with_options(Bson::String(self.key.to_owned()), self.options.clone())
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for KeyDeserializer<'a> {
+    type Error = crate::de::Error;
+
+    #[allow(deprecated)]
+    fn is_human_readable(&self) -> bool {
+        self.options.human_readable.unwrap_or(true)
+    }
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> crate::de::Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.key)
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> crate::de::Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> crate::de::Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.into_owned().deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> crate::de::Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.into_owned().deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
 impl<'de> de::Deserializer<'de> for MapDeserializer {
     type Error = crate::de::Error;
 
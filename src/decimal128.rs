@@ -1,6 +1,9 @@
 //! [BSON Decimal128](https://github.com/mongodb/specifications/blob/master/source/bson-decimal128/decimal128.rst) data type representation
 
-use std::{convert::TryInto, fmt};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt,
+};
 
 use bitvec::prelude::*;
 
@@ -21,7 +24,7 @@ use bitvec::prelude::*;
 /// # }
 /// # example().unwrap()
 /// ```
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Decimal128 {
     /// BSON bytes containing the decimal128. Stored for round tripping.
     pub(crate) bytes: [u8; 16],
@@ -38,6 +41,109 @@ impl Decimal128 {
         self.bytes
     }
 
+    /// Decodes the numeric value, for comparison against other numeric types.
+    pub(crate) fn numeric_value(&self) -> DecimalValue {
+        let parsed = ParsedDecimal128::new(self);
+        match parsed.kind {
+            Decimal128Kind::NaN { .. } => DecimalValue::NaN,
+            Decimal128Kind::Infinity => DecimalValue::Infinity {
+                negative: parsed.sign,
+            },
+            Decimal128Kind::Finite {
+                exponent,
+                coefficient,
+            } => DecimalValue::Finite {
+                negative: parsed.sign,
+                coefficient: coefficient.value(),
+                exponent: exponent.value(),
+            },
+        }
+    }
+
+    /// Converts to an `i128` if the value is an integer within range, with any fractional digits
+    /// all zero. Returns `None` for NaN, infinities, non-integral values and out-of-range values.
+    pub(crate) fn to_i128(self) -> Option<i128> {
+        let (negative, coefficient, exponent) = match self.numeric_value() {
+            DecimalValue::Finite {
+                negative,
+                coefficient,
+                exponent,
+            } => (negative, coefficient, exponent),
+            _ => return None,
+        };
+        let magnitude = if coefficient == 0 {
+            0
+        } else if exponent >= 0 {
+            10u128
+                .checked_pow(exponent as u32)
+                .and_then(|scale| coefficient.checked_mul(scale))?
+        } else {
+            // coefficients have at most 34 digits, so any larger scale leaves a fraction
+            let scale = 10u128.checked_pow(exponent.unsigned_abs() as u32)?;
+            if coefficient % scale != 0 {
+                return None;
+            }
+            coefficient / scale
+        };
+        if negative && magnitude == i128::MIN.unsigned_abs() {
+            Some(i128::MIN)
+        } else if negative {
+            i128::try_from(magnitude).ok().map(|m| -m)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    /// Returns the canonical member of this value's cohort: trailing zeros are removed from the
+    /// coefficient (raising the exponent as far as the format allows), zeros are given an
+    /// exponent of `0`, and NaNs lose their sign and payload. Values that are numerically equal
+    /// normalize to the same bytes, except that the sign of zero is kept.
+    ///
+    /// ```
+    /// use bson::Decimal128;
+    ///
+    /// let a: Decimal128 = "1.500".parse()?;
+    /// let b: Decimal128 = "15E-1".parse()?;
+    /// assert_ne!(a.bytes(), b.bytes());
+    /// assert_eq!(a.normalized(), b.normalized());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn normalized(&self) -> Decimal128 {
+        ParsedDecimal128::new(self).normalize().pack()
+    }
+
+    /// Formats this value so that every member of a cohort has the same string form, following
+    /// the same rules as the [`Display`](fmt::Display) implementation, which renders the exact
+    /// cohort member instead.
+    ///
+    /// Trailing zeros are dropped from the fractional part, but integers that can be written in
+    /// plain notation keep their zeros rather than switching to exponential notation. Values that
+    /// need exponential notation anyway are written from the
+    /// [normalized](Decimal128::normalized) value.
+    ///
+    /// This is not the canonical string of the decimal128 BSON corpus, which
+    /// [`Display`](fmt::Display) already matches. The corpus keeps each cohort member's own
+    /// exponent and trailing zeros, so for example `"1.05E+3"`, `"1.050E+4"` and `"0E+3"` are
+    /// canonical there, whereas this writes `"1050"`, `"10500"` and `"0"`, and `"-1.00E-8"`
+    /// becomes `"-1E-8"`.
+    ///
+    /// ```
+    /// use bson::Decimal128;
+    ///
+    /// let value: Decimal128 = "2.50".parse()?;
+    /// assert_eq!(value.to_string(), "2.50");
+    /// assert_eq!(value.to_canonical_string(), "2.5");
+    /// assert_eq!("1200".parse::<Decimal128>()?.to_canonical_string(), "1200");
+    /// assert_eq!("1.2E+3".parse::<Decimal128>()?.to_canonical_string(), "1200");
+    /// assert_eq!("1.50E-10".parse::<Decimal128>()?.to_canonical_string(), "1.5E-10");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        ParsedDecimal128::new(self)
+            .normalize_for_display()
+            .to_string()
+    }
+
     pub(crate) fn deserialize_from_slice<E: serde::de::Error>(
         bytes: &[u8],
     ) -> std::result::Result<Self, E> {
@@ -46,6 +152,20 @@ impl Decimal128 {
     }
 }
 
+/// The decoded value of a [`Decimal128`]; finite values are `coefficient * 10^exponent`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DecimalValue {
+    NaN,
+    Infinity {
+        negative: bool,
+    },
+    Finite {
+        negative: bool,
+        coefficient: u128,
+        exponent: i16,
+    },
+}
+
 impl fmt::Debug for Decimal128 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Decimal128(...)")
@@ -220,6 +340,66 @@ impl ParsedDecimal128 {
         ParsedDecimal128 { sign, kind }
     }
 
+    fn normalize(&self) -> Self {
+        match &self.kind {
+            Decimal128Kind::NaN { .. } => Self {
+                sign: false,
+                kind: Decimal128Kind::NaN { signalling: false },
+            },
+            Decimal128Kind::Infinity => self.clone(),
+            Decimal128Kind::Finite {
+                exponent,
+                coefficient,
+            } => {
+                let mut coefficient = coefficient.value();
+                let mut exponent = exponent.value();
+                if coefficient == 0 {
+                    exponent = 0;
+                } else {
+                    while coefficient % 10 == 0 && exponent < Exponent::MAX {
+                        coefficient /= 10;
+                        exponent += 1;
+                    }
+                }
+                Self {
+                    sign: self.sign,
+                    kind: Decimal128Kind::Finite {
+                        exponent: Exponent::from_native(exponent),
+                        coefficient: Coefficient::from_native(coefficient),
+                    },
+                }
+            }
+        }
+    }
+
+    /// Like [`normalize`](Self::normalize), but lowers positive exponents back to zero when the
+    /// coefficient allows it, so that the value is still displayed in plain notation.
+    fn normalize_for_display(&self) -> Self {
+        let normalized = self.normalize();
+        if let Decimal128Kind::Finite {
+            exponent,
+            coefficient,
+        } = &normalized.kind
+        {
+            if exponent.value() > 0 {
+                let plain = 10u128
+                    .checked_pow(exponent.value() as u32)
+                    .and_then(|scale| coefficient.value().checked_mul(scale))
+                    .filter(|value| *value <= Coefficient::MAX_VALUE);
+                if let Some(value) = plain {
+                    return Self {
+                        sign: normalized.sign,
+                        kind: Decimal128Kind::Finite {
+                            exponent: Exponent::from_native(0),
+                            coefficient: Coefficient::from_native(value),
+                        },
+                    };
+                }
+            }
+        }
+        normalized
+    }
+
     fn pack(&self) -> Decimal128 {
         let mut tmp = [0u8; 16];
         let dest_bits = tmp.view_bits_mut::<Msb0>();
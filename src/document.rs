@@ -4,6 +4,7 @@ use std::{
     convert::TryInto,
     error,
     fmt::{self, Debug, Display, Formatter},
+    hash::{Hash, Hasher},
     io::{Read, Write},
     iter::{Extend, FromIterator, IntoIterator},
 };
@@ -57,12 +58,109 @@ impl Display for ValueAccessError {
 
 impl error::Error for ValueAccessError {}
 
+/// The rules applied to document keys by [`validate_key`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyPolicy {
+    /// Only reject keys that cannot be encoded in BSON at all, i.e. those containing a null byte.
+    #[default]
+    Bson,
+
+    /// Additionally apply MongoDB's traditional field name restrictions: keys may not start with
+    /// `$` or contain `.`.
+    Strict,
+}
+
+/// An error returned when a key does not satisfy a [`KeyPolicy`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyError {
+    /// The key contained an interior null byte, which cannot be encoded in BSON.
+    #[non_exhaustive]
+    InteriorNull {
+        /// The rejected key.
+        key: String,
+    },
+
+    /// The key started with `$`.
+    #[non_exhaustive]
+    LeadingDollar {
+        /// The rejected key.
+        key: String,
+    },
+
+    /// The key contained a `.`.
+    #[non_exhaustive]
+    ContainsDot {
+        /// The rejected key.
+        key: String,
+    },
+}
+
+impl Display for KeyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            KeyError::InteriorNull { key } => {
+                write!(f, "key {:?} contains an interior null byte", key)
+            }
+            KeyError::LeadingDollar { key } => write!(f, "key {:?} starts with '$'", key),
+            KeyError::ContainsDot { key } => write!(f, "key {:?} contains '.'", key),
+        }
+    }
+}
+
+impl error::Error for KeyError {}
+
+/// Checks that `key` is valid as a document key under the given [`KeyPolicy`].
+///
+/// ```
+/// use bson::{validate_key, KeyError, KeyPolicy};
+///
+/// assert!(validate_key("$set", KeyPolicy::Bson).is_ok());
+/// assert!(matches!(
+///     validate_key("$set", KeyPolicy::Strict),
+///     Err(KeyError::LeadingDollar { .. })
+/// ));
+/// assert!(validate_key("a\0b", KeyPolicy::Bson).is_err());
+/// ```
+pub fn validate_key(key: &str, policy: KeyPolicy) -> Result<(), KeyError> {
+    let error = if key.contains('\0') {
+        KeyError::InteriorNull { key: key.into() }
+    } else if policy == KeyPolicy::Bson {
+        return Ok(());
+    } else if key.starts_with('$') {
+        KeyError::LeadingDollar { key: key.into() }
+    } else if key.contains('.') {
+        KeyError::ContainsDot { key: key.into() }
+    } else {
+        return Ok(());
+    };
+    Err(error)
+}
+
 /// A BSON document represented as an associative HashMap with insertion ordering.
-#[derive(Clone, PartialEq)]
+///
+/// Equality between documents does not depend on the order of their keys, and neither does the
+/// [`Hash`] implementation. See [`Bson`] for a caveat about NaN values when using documents as map
+/// keys.
+#[derive(Clone, PartialEq, Eq)]
 pub struct Document {
     inner: IndexMap<String, Bson, RandomState>,
 }
 
+impl Hash for Document {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Equality is order-insensitive, so combine the entry hashes with a commutative operation.
+        let combined = self.inner.iter().fold(0u64, |acc, entry| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            entry.hash(&mut hasher);
+            acc.wrapping_add(hasher.finish())
+        });
+        self.inner.len().hash(state);
+        combined.hash(state);
+    }
+}
+
 impl Default for Document {
     fn default() -> Self {
         Document::new()
@@ -207,7 +305,19 @@ impl Document {
         self.into_iter()
     }
 
-    /// Gets an iterator over pairs of keys and mutable values.
+    /// Gets an iterator over pairs of keys and mutable values, in insertion order.
+    ///
+    /// ```
+    /// use bson::{doc, Bson};
+    ///
+    /// let mut doc = doc! { "a": "HELLO", "b": 1, "c": "World" };
+    /// for (_, value) in doc.iter_mut() {
+    ///     if let Bson::String(s) = value {
+    ///         *s = s.to_lowercase();
+    ///     }
+    /// }
+    /// assert_eq!(doc, doc! { "a": "hello", "b": 1, "c": "world" });
+    /// ```
     pub fn iter_mut(&mut self) -> IterMut {
         IterMut {
             inner: self.inner.iter_mut(),
@@ -481,6 +591,36 @@ impl Document {
         self.inner.contains_key(key.as_ref())
     }
 
+    /// Sorts the document's entries by key.
+    pub(crate) fn sort_keys(&mut self) {
+        self.inner.sort_keys();
+    }
+
+    /// Returns the first entry whose key matches `key` ignoring ASCII case, along with the key as
+    /// it is actually stored in the document.
+    ///
+    /// This is an O(n) scan over the document's entries.
+    ///
+    /// ```
+    /// use bson::{doc, Bson};
+    ///
+    /// let doc = doc! { "userId": 1 };
+    /// assert_eq!(doc.get_case_insensitive("userid"), Some(("userId", &Bson::Int32(1))));
+    /// ```
+    pub fn get_case_insensitive(&self, key: &str) -> Option<(&str, &Bson)> {
+        self.inner
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Returns true if the document contains a key matching `key` ignoring ASCII case.
+    ///
+    /// Like [`Document::get_case_insensitive`], this is an O(n) scan.
+    pub fn contains_key_case_insensitive(&self, key: &str) -> bool {
+        self.get_case_insensitive(key).is_some()
+    }
+
     /// Gets a collection of all keys in the document.
     pub fn keys(&self) -> Keys {
         Keys {
@@ -512,12 +652,240 @@ impl Document {
         self.inner.insert(key.into(), val.into())
     }
 
+    /// Like [`Document::insert`], but first checks the key with [`validate_key`], leaving the
+    /// document unchanged if it is rejected.
+    ///
+    /// Keys containing a null byte are accepted by [`Document::insert`] but cannot be serialized;
+    /// this reports them at insertion time instead.
+    ///
+    /// ```
+    /// use bson::{Document, KeyPolicy};
+    ///
+    /// let mut doc = Document::new();
+    /// doc.insert_checked("name", "Jane", KeyPolicy::Strict)?;
+    /// assert!(doc.insert_checked("a.b", 1, KeyPolicy::Strict).is_err());
+    /// assert!(doc.insert_checked("a.b", 1, KeyPolicy::Bson).is_ok());
+    /// assert_eq!(doc.len(), 2);
+    /// # Ok::<(), bson::document::KeyError>(())
+    /// ```
+    pub fn insert_checked<KT: Into<String>, BT: Into<Bson>>(
+        &mut self,
+        key: KT,
+        val: BT,
+        policy: KeyPolicy,
+    ) -> Result<Option<Bson>, KeyError> {
+        let key = key.into();
+        validate_key(&key, policy)?;
+        Ok(self.insert(key, val))
+    }
+
+    /// Inserts an array built from the values of `iter` under `key`, and returns the key's old
+    /// value if there was one. See [`Bson::array`].
+    ///
+    /// ```
+    /// use bson::{doc, Document};
+    ///
+    /// let mut filter = Document::new();
+    /// filter.array_field("$in", vec!["a", "b"]);
+    /// assert_eq!(filter, doc! { "$in": ["a", "b"] });
+    /// ```
+    pub fn array_field<KT, I, T>(&mut self, key: KT, iter: I) -> Option<Bson>
+    where
+        KT: Into<String>,
+        I: IntoIterator<Item = T>,
+        T: Into<Bson>,
+    {
+        self.insert(key, Bson::array(iter))
+    }
+
+    /// Creates a document from key-value pairs, converting the keys and values as
+    /// [`Document::insert`] does. This is a more flexible alternative to collecting an iterator of
+    /// `(String, Bson)` pairs.
+    ///
+    /// ```
+    /// use bson::{doc, Document};
+    ///
+    /// let doc = Document::from_pairs(vec![("a", 1), ("b", 2)]);
+    /// assert_eq!(doc, doc! { "a": 1, "b": 2 });
+    /// ```
+    pub fn from_pairs<KT, BT, I>(iter: I) -> Document
+    where
+        KT: Into<String>,
+        BT: Into<Bson>,
+        I: IntoIterator<Item = (KT, BT)>,
+    {
+        let mut doc = Document::new();
+        doc.extend_pairs(iter);
+        doc
+    }
+
+    /// Inserts each of the given key-value pairs, converting the keys and values as
+    /// [`Document::insert`] does. This is a more flexible alternative to [`Extend::extend`], which
+    /// only accepts `(String, Bson)` pairs.
+    ///
+    /// ```
+    /// use bson::{doc, Document};
+    ///
+    /// let mut doc = doc! { "a": 1 };
+    /// doc.extend_pairs(vec![("b", "two"), ("c", "three")]);
+    /// assert_eq!(doc, doc! { "a": 1, "b": "two", "c": "three" });
+    /// ```
+    pub fn extend_pairs<KT, BT, I>(&mut self, iter: I)
+    where
+        KT: Into<String>,
+        BT: Into<Bson>,
+        I: IntoIterator<Item = (KT, BT)>,
+    {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+
     /// Takes the value of the entry out of the document, and returns it.
     /// Computes in **O(n)** time (average).
     pub fn remove(&mut self, key: impl AsRef<str>) -> Option<Bson> {
         self.inner.shift_remove(key.as_ref())
     }
 
+    /// Removes the entry for `key` from the document and returns its value. This is equivalent to
+    /// [`Document::remove`].
+    pub fn take(&mut self, key: impl AsRef<str>) -> Option<Bson> {
+        self.remove(key)
+    }
+
+    /// Changes the key of the entry for `from` to `to`, keeping its value and its position in the
+    /// document. Returns whether the entry was renamed.
+    ///
+    /// Nothing is changed and `false` is returned if `from` isn't present, or if a different entry
+    /// already has the key `to`, since renaming would then overwrite or duplicate that entry.
+    /// Renaming a key to itself returns whether it is present.
+    /// Computes in **O(n)** time.
+    ///
+    /// ```
+    /// use bson::doc;
+    ///
+    /// let mut doc = doc! { "a": 1, "b": 2, "c": 3 };
+    /// assert!(doc.rename_key("b", "beta"));
+    /// assert_eq!(doc, doc! { "a": 1, "beta": 2, "c": 3 });
+    ///
+    /// assert!(!doc.rename_key("missing", "d"));
+    /// assert!(!doc.rename_key("a", "c"));
+    /// assert_eq!(doc, doc! { "a": 1, "beta": 2, "c": 3 });
+    /// ```
+    pub fn rename_key(&mut self, from: &str, to: impl Into<String>) -> bool {
+        let to = to.into();
+        if from == to {
+            return self.contains_key(from);
+        }
+        if self.inner.contains_key(&to) {
+            return false;
+        }
+        match self.inner.shift_remove_full(from) {
+            Some((index, _, value)) => {
+                let (new_index, _) = self.inner.insert_full(to, value);
+                self.inner.move_index(new_index, index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces every value equal to `target` with a copy of `replacement`, recursing into
+    /// embedded documents and arrays, and returns the number of values replaced.
+    ///
+    /// Values are compared with the strict [`PartialEq`] implementation of [`Bson`], so for
+    /// example `Bson::Int32(1)` does not match `Bson::Int64(1)`, and a NaN double never matches.
+    /// A replaced value is not searched further, so the replacement itself is never modified.
+    ///
+    /// ```
+    /// use bson::{doc, Bson};
+    ///
+    /// let redacted = Bson::String("REDACTED".to_string());
+    /// let mut doc = doc! { "ssn": "REDACTED", "contacts": [{ "ssn": "REDACTED" }, 1] };
+    /// let count = doc.replace_values(&redacted, &Bson::Null);
+    /// assert_eq!(count, 2);
+    /// assert_eq!(doc, doc! { "ssn": null, "contacts": [{ "ssn": null }, 1] });
+    /// ```
+    pub fn replace_values(&mut self, target: &Bson, replacement: &Bson) -> usize {
+        self.inner
+            .values_mut()
+            .map(|value| replace_value(value, target, replacement))
+            .sum()
+    }
+
+    /// Splits the document into two, preserving the order of entries: the first contains the
+    /// entries for which `pred` returns true and the second contains the rest.
+    ///
+    /// ```
+    /// use bson::doc;
+    ///
+    /// let doc = doc! { "_id": 1, "$meta": "x", "name": "Jane", "$score": 0.5 };
+    /// let (meta, content) = doc.partition(|key, _| key.starts_with('$'));
+    /// assert_eq!(meta, doc! { "$meta": "x", "$score": 0.5 });
+    /// assert_eq!(content, doc! { "_id": 1, "name": "Jane" });
+    /// ```
+    pub fn partition<F>(self, mut pred: F) -> (Document, Document)
+    where
+        F: FnMut(&str, &Bson) -> bool,
+    {
+        let mut matching = Document::new();
+        let mut rest = Document::new();
+        for (key, value) in self.inner {
+            if pred(&key, &value) {
+                matching.inner.insert(key, value);
+            } else {
+                rest.inner.insert(key, value);
+            }
+        }
+        (matching, rest)
+    }
+
+    /// Feeds a canonical encoding of the document into `state`, for computing content hashes that
+    /// are stable across key order, processes and platforms. Pair it with a hasher whose output is
+    /// itself stable (for example a cryptographic digest wrapped in a [`Hasher`]); the standard
+    /// library's `DefaultHasher` is not.
+    ///
+    /// The encoding is normalized so that documents which differ only in representation hash the
+    /// same:
+    /// * Fields are visited in sorted key order, at every level of nesting. Array elements keep
+    ///   their order.
+    /// * Integral numbers within the range of `i64` are hashed as that integer whatever their BSON
+    ///   type, so `1`, `1i64`, `1.0` and the `Decimal128` `1.00` all hash the same, as do `0.0` and
+    ///   `-0.0`.
+    /// * Other `Decimal128` values are hashed with trailing zeros removed from the coefficient, so
+    ///   `1.50` and `1.5` hash the same. Other doubles are hashed by their bit pattern, and so
+    ///   never collide with a `Decimal128`.
+    /// * NaN and each infinity hash the same whether stored as a double or a `Decimal128`.
+    ///
+    /// All other values, including strings, are hashed exactly as stored.
+    ///
+    /// ```
+    /// use std::hash::Hasher;
+    /// use bson::doc;
+    ///
+    /// fn digest(doc: &bson::Document) -> u64 {
+    ///     // stand-in for a stable hasher
+    ///     let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    ///     doc.stable_hash(&mut hasher);
+    ///     hasher.finish()
+    /// }
+    ///
+    /// let a = doc! { "x": 1, "y": { "b": 2.0, "a": [1, 2] } };
+    /// let b = doc! { "y": { "a": [1i64, 2i64], "b": 2 }, "x": 1.0 };
+    /// assert_eq!(digest(&a), digest(&b));
+    /// assert_ne!(digest(&a), digest(&doc! { "x": 1, "y": { "b": 2, "a": [2, 1] } }));
+    /// ```
+    pub fn stable_hash<H: Hasher>(&self, state: &mut H) {
+        let mut entries: Vec<_> = self.inner.iter().collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+        state.write(&(entries.len() as u64).to_le_bytes());
+        for (key, value) in entries {
+            state.write(&(key.len() as u64).to_le_bytes());
+            state.write(key.as_bytes());
+            value.stable_hash(state);
+        }
+    }
+
     pub fn entry(&mut self, k: String) -> Entry {
         match self.inner.entry(k) {
             indexmap::map::Entry::Occupied(o) => Entry::Occupied(OccupiedEntry { inner: o }),
@@ -525,6 +893,47 @@ impl Document {
         }
     }
 
+    /// Encodes the [`Document`] into an owned [`RawDocumentBuf`](crate::RawDocumentBuf).
+    ///
+    /// This writes the BSON bytes directly in a single pass rather than going through serde, and
+    /// fails only if a key or regular expression contains an interior null byte.
+    ///
+    /// ```
+    /// use bson::doc;
+    ///
+    /// let doc = doc! { "x": 1, "nested": { "y": "z" } };
+    /// let raw = doc.to_raw_document_buf()?;
+    /// assert_eq!(raw.get_document("nested")?.get_str("y")?, "z");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_raw_document_buf(&self) -> crate::raw::Result<crate::RawDocumentBuf> {
+        crate::RawDocumentBuf::from_document(self)
+    }
+
+    /// Deserializes the [`Document`] into a `T`. This is equivalent to
+    /// [`from_document`](crate::from_document).
+    ///
+    /// ```
+    /// use bson::doc;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize, PartialEq)]
+    /// struct User {
+    ///     name: String,
+    ///     age: i32,
+    /// }
+    ///
+    /// let user: User = doc! { "name": "Jane", "age": 42 }.try_into_typed()?;
+    /// assert_eq!(user, User { name: "Jane".into(), age: 42 });
+    /// # Ok::<(), bson::de::Error>(())
+    /// ```
+    pub fn try_into_typed<T>(self) -> crate::de::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        crate::from_document(self)
+    }
+
     /// Attempts to serialize the [`Document`] into a byte stream.
     ///
     /// While the method signature indicates an owned writer must be passed in, a mutable reference
@@ -560,6 +969,7 @@ impl Document {
                 .try_into()
                 .map_err(|e| crate::de::Error::DeserializationError {
                     message: format!("invalid document length: {}", e),
+                    key: None,
                 })?;
         let mut buf = vec![0u8; ulen];
         buf[0..4].copy_from_slice(&length.to_le_bytes());
@@ -611,6 +1021,21 @@ impl Document {
     }
 }
 
+fn replace_value(value: &mut Bson, target: &Bson, replacement: &Bson) -> usize {
+    if value == target {
+        *value = replacement.clone();
+        return 1;
+    }
+    match value {
+        Bson::Document(doc) => doc.replace_values(target, replacement),
+        Bson::Array(array) => array
+            .iter_mut()
+            .map(|value| replace_value(value, target, replacement))
+            .sum(),
+        _ => 0,
+    }
+}
+
 /// A view into a single entry in a map, which may either be vacant or occupied.
 ///
 /// This enum is constructed from the entry method on HashMap.
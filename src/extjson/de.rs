@@ -21,7 +21,7 @@
 //! Bson::try_from(invalid_ext_json).expect_err("5 should be a string");
 //! ```
 
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 
 use serde::de::{Error as _, Unexpected};
 
@@ -37,6 +37,13 @@ pub enum Error {
     /// A general error encountered during deserialization.
     /// See: <https://docs.serde.rs/serde/de/trait.Error.html>
     DeserializationError { message: String },
+
+    /// The input was nested more deeply than allowed.
+    #[non_exhaustive]
+    DepthLimitExceeded {
+        /// The maximum nesting depth that was allowed.
+        max_depth: usize,
+    },
 }
 
 impl std::fmt::Display for Error {
@@ -44,6 +51,11 @@ impl std::fmt::Display for Error {
         match *self {
             Self::InvalidObjectId(ref err) => err.fmt(fmt),
             Self::DeserializationError { ref message } => message.fmt(fmt),
+            Self::DepthLimitExceeded { max_depth } => write!(
+                fmt,
+                "input exceeds the maximum nesting depth of {}",
+                max_depth
+            ),
         }
     }
 }
@@ -77,100 +89,263 @@ impl From<oid::Error> for Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The maximum nesting depth of arrays and objects accepted by the `TryFrom<serde_json::Value>`
+/// conversions. This matches the recursion limit `serde_json` applies when parsing, so any value
+/// parsed from text by `serde_json` is accepted. Use [`Bson::try_from_value_limited`] to choose a
+/// different limit.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+impl Bson {
+    /// Converts the input JSON as if it were [MongoDB Extended JSON v2](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/),
+    /// returning [`Error::DepthLimitExceeded`] if arrays and objects are nested more than
+    /// `max_depth` levels deep.
+    ///
+    /// The conversion is recursive, so a limit protects against stack overflow when converting
+    /// untrusted values. The depth is checked before any conversion is done, without recursion.
+    ///
+    /// ```
+    /// # use bson::{doc, Bson};
+    /// # use serde_json::json;
+    /// let value = json!({ "a": [{ "b": 1 }] });
+    /// assert!(Bson::try_from_value_limited(value.clone(), 2).is_err());
+    /// assert_eq!(
+    ///     Bson::try_from_value_limited(value, 3)?,
+    ///     Bson::Document(doc! { "a": [{ "b": 1 }] })
+    /// );
+    /// # Ok::<(), bson::extjson::de::Error>(())
+    /// ```
+    pub fn try_from_value_limited(value: serde_json::Value, max_depth: usize) -> Result<Self> {
+        check_depth(value, max_depth).and_then(value_to_bson)
+    }
+
+    /// Parses a string of canonical [MongoDB Extended JSON v2](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/),
+    /// such as one produced by formatting [`Bson::into_canonical_extjson`].
+    ///
+    /// Unlike [`Bson::from_relaxed_extjson_str`], this rejects the relaxed-only forms: bare JSON
+    /// numbers (other than in `$timestamp`, `$minKey`, and `$maxKey`) and `$date` values given as
+    /// strings.
+    ///
+    /// ```
+    /// # use bson::{doc, Bson};
+    /// let bson = Bson::from_canonical_extjson_str(r#"{ "x": { "$numberInt": "5" } }"#)?;
+    /// assert_eq!(bson, Bson::Document(doc! { "x": 5 }));
+    /// assert_eq!(Bson::from_canonical_extjson_str(&bson.clone().into_canonical_extjson().to_string())?, bson);
+    ///
+    /// assert!(Bson::from_canonical_extjson_str(r#"{ "x": 5 }"#).is_err());
+    /// # Ok::<(), bson::extjson::de::Error>(())
+    /// ```
+    pub fn from_canonical_extjson_str(s: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(s)?;
+        check_canonical(&value)?;
+        Bson::try_from(value)
+    }
+
+    /// Parses a string of relaxed [MongoDB Extended JSON v2](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/),
+    /// such as one produced by formatting [`Bson::into_relaxed_extjson`]. Canonical extended JSON
+    /// is also accepted, and the two modes may be mixed.
+    ///
+    /// ```
+    /// # use bson::{doc, Bson};
+    /// let bson = Bson::from_relaxed_extjson_str(r#"{ "x": 5, "y": { "$numberLong": "6" } }"#)?;
+    /// assert_eq!(bson, Bson::Document(doc! { "x": 5, "y": 6i64 }));
+    ///
+    /// let err = Bson::from_relaxed_extjson_str(r#"{ "x": "#).unwrap_err();
+    /// assert!(err.to_string().contains("line 1"));
+    /// # Ok::<(), bson::extjson::de::Error>(())
+    /// ```
+    pub fn from_relaxed_extjson_str(s: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(s)?;
+        Bson::try_from(value)
+    }
+}
+
+/// Returns an error if the value uses any of the forms that are only valid in relaxed extended
+/// JSON.
+fn check_canonical(value: &serde_json::Value) -> Result<()> {
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        match value {
+            serde_json::Value::Number(n) => {
+                return Err(Error::custom(format!(
+                    "canonical extended JSON does not allow the bare number {}; use $numberInt, \
+                     $numberLong, or $numberDouble",
+                    n
+                )))
+            }
+            serde_json::Value::Array(values) => stack.extend(values),
+            serde_json::Value::Object(map) => {
+                if let Some(serde_json::Value::String(date)) = map.get("$date") {
+                    return Err(Error::custom(format!(
+                        "canonical extended JSON requires $date to be a $numberLong, but got the \
+                         string {:?}",
+                        date
+                    )));
+                }
+                // these hold bare numbers even in canonical mode
+                if !["$timestamp", "$minKey", "$maxKey"]
+                    .iter()
+                    .any(|key| map.contains_key(*key))
+                {
+                    stack.extend(map.values());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Returns the value if its arrays and objects are nested at most `max_depth` levels deep, and
+/// otherwise an error. Over-deep values are dropped without recursion, since dropping them
+/// normally could itself overflow the stack.
+fn check_depth(value: serde_json::Value, max_depth: usize) -> Result<serde_json::Value> {
+    let mut exceeded = false;
+    let mut stack = vec![(&value, 0)];
+    while let Some((value, depth)) = stack.pop() {
+        match value {
+            serde_json::Value::Array(values) if depth < max_depth => {
+                stack.extend(values.iter().map(|child| (child, depth + 1)))
+            }
+            serde_json::Value::Object(map) if depth < max_depth => {
+                stack.extend(map.values().map(|child| (child, depth + 1)))
+            }
+            serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                exceeded = true;
+                break;
+            }
+            _ => {}
+        }
+    }
+    drop(stack);
+    if !exceeded {
+        return Ok(value);
+    }
+
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        match value {
+            serde_json::Value::Array(values) => stack.extend(values),
+            serde_json::Value::Object(map) => stack.extend(map.into_iter().map(|(_, v)| v)),
+            _ => {}
+        }
+    }
+    Err(Error::DepthLimitExceeded { max_depth })
+}
+
 /// This converts from the input JSON object as if it were [MongoDB Extended JSON v2](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/).
 impl TryFrom<serde_json::Map<String, serde_json::Value>> for Bson {
     type Error = Error;
 
     fn try_from(obj: serde_json::Map<String, serde_json::Value>) -> Result<Self> {
-        if obj.contains_key("$oid") {
-            let oid: models::ObjectId = serde_json::from_value(obj.into())?;
-            return Ok(Bson::ObjectId(oid.parse()?));
+        match check_depth(obj.into(), DEFAULT_MAX_DEPTH)? {
+            serde_json::Value::Object(obj) => map_to_bson(obj),
+            _ => unreachable!("depth checking returns its input"),
         }
+    }
+}
 
-        if obj.contains_key("$symbol") {
-            let symbol: models::Symbol = serde_json::from_value(obj.into())?;
-            return Ok(Bson::Symbol(symbol.value));
-        }
+fn map_to_bson(obj: serde_json::Map<String, serde_json::Value>) -> Result<Bson> {
+    if obj.contains_key("$oid") {
+        let oid: models::ObjectId = serde_json::from_value(obj.into())?;
+        return Ok(Bson::ObjectId(oid.parse()?));
+    }
 
-        if obj.contains_key("$regularExpression") {
-            let regex: models::Regex = serde_json::from_value(obj.into())?;
-            return Ok(regex.parse().into());
-        }
+    if obj.contains_key("$symbol") {
+        let symbol: models::Symbol = serde_json::from_value(obj.into())?;
+        return Ok(Bson::Symbol(symbol.value));
+    }
 
-        if obj.contains_key("$numberInt") {
-            let int: models::Int32 = serde_json::from_value(obj.into())?;
-            return Ok(Bson::Int32(int.parse()?));
-        }
+    if obj.contains_key("$regularExpression") {
+        let regex: models::Regex = serde_json::from_value(obj.into())?;
+        return Ok(regex.parse().into());
+    }
 
-        if obj.contains_key("$numberLong") {
-            let int: models::Int64 = serde_json::from_value(obj.into())?;
-            return Ok(Bson::Int64(int.parse()?));
+    // The legacy `{ "$regex": <string>, "$options": <string> }` form. Documents with any other
+    // keys, or whose `$regex` value is not a string (e.g. a `$regex` query operator taking a
+    // `$regularExpression`), are left as plain documents.
+    if obj.len() == 2 {
+        if let (
+            Some(serde_json::Value::String(pattern)),
+            Some(serde_json::Value::String(options)),
+        ) = (obj.get("$regex"), obj.get("$options"))
+        {
+            return Ok(crate::Regex::from_strings(pattern, options).into());
         }
+    }
 
-        if obj.contains_key("$numberDouble") {
-            let double: models::Double = serde_json::from_value(obj.into())?;
-            return Ok(Bson::Double(double.parse()?));
-        }
+    if obj.contains_key("$numberInt") {
+        let int: models::Int32 = serde_json::from_value(obj.into())?;
+        return Ok(Bson::Int32(int.parse()?));
+    }
 
-        if obj.contains_key("$numberDecimal") {
-            let decimal: models::Decimal128 = serde_json::from_value(obj.into())?;
-            return Ok(Bson::Decimal128(decimal.parse()?));
-        }
+    if obj.contains_key("$numberLong") {
+        let int: models::Int64 = serde_json::from_value(obj.into())?;
+        return Ok(Bson::Int64(int.parse()?));
+    }
 
-        if obj.contains_key("$binary") {
-            let binary: models::Binary = serde_json::from_value(obj.into())?;
-            return Ok(Bson::Binary(binary.parse()?));
-        }
+    if obj.contains_key("$numberDouble") {
+        let double: models::Double = serde_json::from_value(obj.into())?;
+        return Ok(Bson::Double(double.parse()?));
+    }
 
-        if obj.contains_key("$uuid") {
-            let uuid: models::Uuid = serde_json::from_value(obj.into())?;
-            return Ok(Bson::Binary(uuid.parse()?));
-        }
+    if obj.contains_key("$numberDecimal") {
+        let decimal: models::Decimal128 = serde_json::from_value(obj.into())?;
+        return Ok(Bson::Decimal128(decimal.parse()?));
+    }
 
-        if obj.contains_key("$code") {
-            let code_w_scope: models::JavaScriptCodeWithScope = serde_json::from_value(obj.into())?;
-            return match code_w_scope.scope {
-                Some(scope) => Ok(crate::JavaScriptCodeWithScope {
-                    code: code_w_scope.code,
-                    scope: scope.try_into()?,
-                }
-                .into()),
-                None => Ok(Bson::JavaScriptCode(code_w_scope.code)),
-            };
-        }
+    if obj.contains_key("$binary") {
+        let binary: models::Binary = serde_json::from_value(obj.into())?;
+        return Ok(Bson::Binary(binary.parse()?));
+    }
 
-        if obj.contains_key("$timestamp") {
-            let ts: models::Timestamp = serde_json::from_value(obj.into())?;
-            return Ok(ts.parse().into());
-        }
+    if obj.contains_key("$uuid") {
+        let uuid: models::Uuid = serde_json::from_value(obj.into())?;
+        return Ok(Bson::Binary(uuid.parse()?));
+    }
 
-        if obj.contains_key("$date") {
-            let extjson_datetime: models::DateTime = serde_json::from_value(obj.into())?;
-            return Ok(Bson::DateTime(extjson_datetime.parse()?));
-        }
+    if obj.contains_key("$code") {
+        let code_w_scope: models::JavaScriptCodeWithScope = serde_json::from_value(obj.into())?;
+        return match code_w_scope.scope {
+            Some(scope) => Ok(crate::JavaScriptCodeWithScope {
+                code: code_w_scope.code,
+                scope: map_to_document(scope)?,
+            }
+            .into()),
+            None => Ok(Bson::JavaScriptCode(code_w_scope.code)),
+        };
+    }
 
-        if obj.contains_key("$minKey") {
-            let min_key: models::MinKey = serde_json::from_value(obj.into())?;
-            return min_key.parse();
-        }
+    if obj.contains_key("$timestamp") {
+        let ts: models::Timestamp = serde_json::from_value(obj.into())?;
+        return Ok(ts.parse().into());
+    }
 
-        if obj.contains_key("$maxKey") {
-            let max_key: models::MaxKey = serde_json::from_value(obj.into())?;
-            return max_key.parse();
-        }
+    if obj.contains_key("$date") {
+        let extjson_datetime: models::DateTime = serde_json::from_value(obj.into())?;
+        return Ok(Bson::DateTime(extjson_datetime.parse()?));
+    }
 
-        if obj.contains_key("$dbPointer") {
-            let db_ptr: models::DbPointer = serde_json::from_value(obj.into())?;
-            return Ok(db_ptr.parse()?.into());
-        }
+    if obj.contains_key("$minKey") {
+        let min_key: models::MinKey = serde_json::from_value(obj.into())?;
+        return min_key.parse();
+    }
 
-        if obj.contains_key("$undefined") {
-            let undefined: models::Undefined = serde_json::from_value(obj.into())?;
-            return undefined.parse();
-        }
+    if obj.contains_key("$maxKey") {
+        let max_key: models::MaxKey = serde_json::from_value(obj.into())?;
+        return max_key.parse();
+    }
+
+    if obj.contains_key("$dbPointer") {
+        let db_ptr: models::DbPointer = serde_json::from_value(obj.into())?;
+        return Ok(db_ptr.parse()?.into());
+    }
 
-        Ok(Bson::Document(obj.try_into()?))
+    if obj.contains_key("$undefined") {
+        let undefined: models::Undefined = serde_json::from_value(obj.into())?;
+        return undefined.parse();
     }
+
+    Ok(Bson::Document(map_to_document(obj)?))
 }
 
 /// This converts from the input JSON as if it were [MongoDB Extended JSON v2](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/).
@@ -178,33 +353,37 @@ impl TryFrom<serde_json::Value> for Bson {
     type Error = Error;
 
     fn try_from(value: serde_json::Value) -> Result<Self> {
-        match value {
-            serde_json::Value::Number(x) => x
-                .as_i64()
-                .map(|i| {
-                    if i >= std::i32::MIN as i64 && i <= std::i32::MAX as i64 {
-                        Bson::Int32(i as i32)
-                    } else {
-                        Bson::Int64(i)
-                    }
-                })
-                .or_else(|| x.as_f64().map(Bson::from))
-                .ok_or_else(|| {
-                    Error::invalid_value(
-                        Unexpected::Other(format!("{}", x).as_str()),
-                        &"a number that could fit in i32, i64, or f64",
-                    )
-                }),
-            serde_json::Value::String(x) => Ok(x.into()),
-            serde_json::Value::Bool(x) => Ok(x.into()),
-            serde_json::Value::Array(x) => Ok(Bson::Array(
-                x.into_iter()
-                    .map(Bson::try_from)
-                    .collect::<Result<Vec<Bson>>>()?,
-            )),
-            serde_json::Value::Null => Ok(Bson::Null),
-            serde_json::Value::Object(map) => map.try_into(),
-        }
+        Bson::try_from_value_limited(value, DEFAULT_MAX_DEPTH)
+    }
+}
+
+fn value_to_bson(value: serde_json::Value) -> Result<Bson> {
+    match value {
+        serde_json::Value::Number(x) => x
+            .as_i64()
+            .map(|i| {
+                if i >= std::i32::MIN as i64 && i <= std::i32::MAX as i64 {
+                    Bson::Int32(i as i32)
+                } else {
+                    Bson::Int64(i)
+                }
+            })
+            .or_else(|| x.as_f64().map(Bson::from))
+            .ok_or_else(|| {
+                Error::invalid_value(
+                    Unexpected::Other(format!("{}", x).as_str()),
+                    &"a number that could fit in i32, i64, or f64",
+                )
+            }),
+        serde_json::Value::String(x) => Ok(x.into()),
+        serde_json::Value::Bool(x) => Ok(x.into()),
+        serde_json::Value::Array(x) => Ok(Bson::Array(
+            x.into_iter()
+                .map(value_to_bson)
+                .collect::<Result<Vec<Bson>>>()?,
+        )),
+        serde_json::Value::Null => Ok(Bson::Null),
+        serde_json::Value::Object(map) => map_to_bson(map),
     }
 }
 
@@ -213,14 +392,21 @@ impl TryFrom<serde_json::Map<String, serde_json::Value>> for Document {
     type Error = Error;
 
     fn try_from(obj: serde_json::Map<String, serde_json::Value>) -> Result<Self> {
-        Ok(obj
-            .into_iter()
-            .map(|(k, v)| -> Result<(String, Bson)> {
-                let value: Bson = v.try_into()?;
-                Ok((k, value))
-            })
-            .collect::<Result<Vec<(String, Bson)>>>()?
-            .into_iter()
-            .collect())
+        match check_depth(obj.into(), DEFAULT_MAX_DEPTH)? {
+            serde_json::Value::Object(obj) => map_to_document(obj),
+            _ => unreachable!("depth checking returns its input"),
+        }
     }
 }
+
+fn map_to_document(obj: serde_json::Map<String, serde_json::Value>) -> Result<Document> {
+    Ok(obj
+        .into_iter()
+        .map(|(k, v)| -> Result<(String, Bson)> {
+            let value = value_to_bson(v)?;
+            Ok((k, value))
+        })
+        .collect::<Result<Vec<(String, Bson)>>>()?
+        .into_iter()
+        .collect())
+}
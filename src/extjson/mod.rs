@@ -88,6 +88,21 @@
 //! let canonical_extjson = doc.into_canonical_extjson();
 //! println!("{}", canonical_extjson); // { "x": { "$numberInt": "5" }, "_id": { "$oid": <hexstring> } }
 //! ```
+//!
+//! A [`RawDocument`](crate::RawDocument) can be converted directly via
+//! [`RawDocument::to_extjson`](crate::RawDocument::to_extjson), which avoids building an owned
+//! [`Document`](crate::Document) first.
 
 pub mod de;
 pub(crate) mod models;
+
+/// The mode in which to produce extended JSON. See the [module-level
+/// documentation](self#canonical-and-relaxed-modes) for the differences between the two.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExtJsonMode {
+    /// Canonical extended JSON, which preserves all type information.
+    Canonical,
+
+    /// Relaxed extended JSON, which represents numbers as plain JSON numbers where possible.
+    Relaxed,
+}
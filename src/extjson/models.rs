@@ -1,11 +1,11 @@
 //! A module defining serde models for the extended JSON representations of the various BSON types.
 
 use serde::{
-    de::{Error, Unexpected},
+    de::{value::MapAccessDeserializer, Error, MapAccess, Unexpected, Visitor},
     Deserialize,
     Serialize,
 };
-use std::borrow::Cow;
+use std::{borrow::Cow, convert::TryFrom};
 
 use crate::{extjson, oid, raw::serde::CowStr, spec::BinarySubtype, Bson};
 
@@ -134,7 +134,7 @@ pub(crate) struct RegexBody {
 
 impl Regex {
     pub(crate) fn parse(self) -> crate::Regex {
-        crate::Regex::new(self.body.pattern, self.body.options)
+        crate::Regex::from_strings(self.body.pattern, self.body.options)
     }
 }
 
@@ -251,11 +251,59 @@ pub(crate) struct DateTime {
     pub(crate) body: DateTimeBody,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Serialize)]
 #[serde(untagged)]
 pub(crate) enum DateTimeBody {
     Canonical(Int64),
     Relaxed(String),
+    Legacy(i64),
+}
+
+impl<'de> Deserialize<'de> for DateTimeBody {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct BodyVisitor;
+
+        impl<'de> Visitor<'de> for BodyVisitor {
+            type Value = DateTimeBody;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str(
+                    "a $numberLong document, an RFC 3339 formatted string, or an integer number \
+                     of milliseconds",
+                )
+            }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(DateTimeBody::Relaxed(v.to_string()))
+            }
+
+            fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+                Ok(DateTimeBody::Relaxed(v))
+            }
+
+            fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(DateTimeBody::Legacy(v))
+            }
+
+            fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+                let v = i64::try_from(v)
+                    .map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &"an i64"))?;
+                Ok(DateTimeBody::Legacy(v))
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                Int64::deserialize(MapAccessDeserializer::new(map)).map(DateTimeBody::Canonical)
+            }
+        }
+
+        deserializer.deserialize_any(BodyVisitor)
+    }
 }
 
 impl DateTimeBody {
@@ -264,11 +312,9 @@ impl DateTimeBody {
             value: m.to_string(),
         })
     }
-}
 
-impl DateTime {
     pub(crate) fn parse(self) -> extjson::de::Result<crate::DateTime> {
-        match self.body {
+        match self {
             DateTimeBody::Canonical(date) => {
                 let date = date.parse()?;
                 Ok(crate::DateTime::from_millis(date))
@@ -282,10 +328,17 @@ impl DateTime {
                 })?;
                 Ok(datetime)
             }
+            DateTimeBody::Legacy(millis) => Ok(crate::DateTime::from_millis(millis)),
         }
     }
 }
 
+impl DateTime {
+    pub(crate) fn parse(self) -> extjson::de::Result<crate::DateTime> {
+        self.body.parse()
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct MinKey {
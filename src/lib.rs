@@ -278,21 +278,42 @@
 #[doc(inline)]
 pub use self::{
     binary::Binary,
-    bson::{Array, Bson, DbPointer, Document, JavaScriptCodeWithScope, Regex, Timestamp},
+    bson::{
+        Array,
+        Bson,
+        DbPointer,
+        Document,
+        JavaScriptCodeWithScope,
+        NonFiniteDoubleError,
+        NonFinitePolicy,
+        Regex,
+        RegexError,
+        RelaxedExtJsonOptions,
+        RelaxedExtJsonOptionsBuilder,
+        Timestamp,
+    },
     datetime::DateTime,
     de::{
+        document_frame_len,
         from_bson,
+        from_bson_seed,
         from_bson_with_options,
         from_document,
         from_document_with_options,
         from_reader,
         from_reader_utf8_lossy,
         from_slice,
+        from_slice_interned,
+        from_slice_seed,
         from_slice_utf8_lossy,
+        from_slice_with_options,
+        peek_document_length,
         Deserializer,
         DeserializerOptions,
+        FrameStatus,
     },
     decimal128::Decimal128,
+    document::{validate_key, KeyError, KeyPolicy},
     raw::{
         RawArray,
         RawArrayBuf,
@@ -313,6 +334,7 @@ pub use self::{
         to_document_with_options,
         to_raw_document_buf,
         to_vec,
+        to_vec_with_options,
         Serializer,
         SerializerOptions,
     },
@@ -2,6 +2,7 @@
 //! For more information, see the documentation for the [`ObjectId`] type.
 
 use std::{
+    convert::TryFrom,
     error,
     fmt,
     result,
@@ -42,6 +43,10 @@ pub enum Error {
     /// hexadecimal string.
     #[non_exhaustive]
     InvalidHexStringLength { length: usize, hex: String },
+
+    /// An [`ObjectId`] must be constructed from exactly 12 bytes.
+    #[non_exhaustive]
+    InvalidByteLength { length: usize },
 }
 
 /// Alias for Result<T, oid::Error>.
@@ -66,6 +71,13 @@ impl fmt::Display for Error {
                     hex, length
                 )
             }
+            Error::InvalidByteLength { length } => {
+                write!(
+                    fmt,
+                    "an ObjectId must be exactly 12 bytes, instead got {} bytes",
+                    length
+                )
+            }
         }
     }
 }
@@ -137,7 +149,13 @@ impl error::Error for Error {}
 /// bson: { "oid": ObjectId("63ceeffd37518221cdc6cda2"), "oid_as_hex": "63ceeffd37518221cdc6cda3" }
 /// json: {"oid":{"$oid":"63ceeffd37518221cdc6cda2"},"oid_as_hex":"63ceeffd37518221cdc6cda3"}
 /// ```
-#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+///
+/// ## Ordering
+/// [`ObjectId`]s are ordered by their embedded timestamp first, then by the 5-byte process
+/// identifier, then by the counter, which matches the ordering the server uses for `_id` values.
+/// Because [`ObjectId::new`] increments the counter on every call, ids generated in the same
+/// process are ordered by generation time.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ObjectId {
     id: [u8; 12],
 }
@@ -162,6 +180,50 @@ impl From<[u8; 12]> for ObjectId {
     }
 }
 
+impl TryFrom<&[u8]> for ObjectId {
+    type Error = Error;
+
+    /// Constructs an [`ObjectId`] from a slice, returning an error if it is not exactly 12 bytes
+    /// long.
+    ///
+    /// ```
+    /// use std::convert::{TryFrom, TryInto};
+    /// use bson::oid::ObjectId;
+    ///
+    /// let bytes = [0u8; 16];
+    /// let oid: ObjectId = bytes[..12].try_into()?;
+    /// assert_eq!(oid.bytes(), [0u8; 12]);
+    /// assert!(ObjectId::try_from(&bytes[..]).is_err());
+    /// # Ok::<(), bson::oid::Error>(())
+    /// ```
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        let id = <[u8; 12]>::try_from(bytes).map_err(|_| Error::InvalidByteLength {
+            length: bytes.len(),
+        })?;
+        Ok(Self { id })
+    }
+}
+
+impl Ord for ObjectId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // All components are stored big-endian, so comparing each byte range lexicographically
+        // is equivalent to comparing the numeric values.
+        let timestamp = TIMESTAMP_OFFSET..PROCESS_ID_OFFSET;
+        let process_id = PROCESS_ID_OFFSET..COUNTER_OFFSET;
+        let counter = COUNTER_OFFSET..COUNTER_OFFSET + COUNTER_SIZE;
+        self.id[timestamp.clone()]
+            .cmp(&other.id[timestamp])
+            .then_with(|| self.id[process_id.clone()].cmp(&other.id[process_id]))
+            .then_with(|| self.id[counter.clone()].cmp(&other.id[counter]))
+    }
+}
+
+impl PartialOrd for ObjectId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl ObjectId {
     /// Generates a new [`ObjectId`], represented in bytes.
     /// See the [docs](http://www.mongodb.com/docs/manual/reference/object-id/)
@@ -6,8 +6,10 @@ use super::{
     error::{ValueAccessError, ValueAccessErrorKind, ValueAccessResult},
     serde::OwnedOrBorrowedRawArray,
     Error,
+    ErrorKind,
     RawBinaryRef,
     RawBsonRef,
+    RawDbPointerRef,
     RawDocument,
     RawIter,
     RawRegexRef,
@@ -19,6 +21,7 @@ use crate::{
     spec::ElementType,
     Bson,
     DateTime,
+    Decimal128,
     RawArrayBuf,
     Timestamp,
 };
@@ -104,6 +107,35 @@ impl RawArray {
         self.into_iter().nth(index).transpose()
     }
 
+    /// Gets a reference to the first value in the array, or `None` if the array is empty.
+    pub fn first(&self) -> Result<Option<RawBsonRef<'_>>> {
+        self.get(0)
+    }
+
+    /// Gets a reference to the last value in the array, or `None` if the array is empty.
+    ///
+    /// This is an O(N) operation, as it requires iterating through every element of the array to
+    /// find the final one.
+    ///
+    /// ```
+    /// use bson::{doc, raw::RawDocument};
+    ///
+    /// let bytes = bson::to_vec(&doc! { "x": ["a", "b", "c"] })?;
+    /// let rawdoc = RawDocument::from_bytes(bytes.as_slice())?;
+    /// let array = rawdoc.get_array("x")?;
+    ///
+    /// assert_eq!(array.first()?.and_then(|v| v.as_str()), Some("a"));
+    /// assert_eq!(array.last()?.and_then(|v| v.as_str()), Some("c"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn last(&self) -> Result<Option<RawBsonRef<'_>>> {
+        let mut last = None;
+        for value in self {
+            last = Some(value?);
+        }
+        Ok(last)
+    }
+
     fn get_with<'a, T>(
         &'a self,
         index: usize,
@@ -208,6 +240,65 @@ impl RawArray {
         self.get_with(index, ElementType::Int64, RawBsonRef::as_i64)
     }
 
+    /// Gets the BSON decimal128 at the given index or returns an error if the value at that index
+    /// isn't a decimal128.
+    pub fn get_decimal128(&self, index: usize) -> ValueAccessResult<Decimal128> {
+        self.get_with(index, ElementType::Decimal128, RawBsonRef::as_decimal128)
+    }
+
+    /// Gets the BSON null at the given index or returns an error if the value at that index isn't
+    /// null.
+    pub fn get_null(&self, index: usize) -> ValueAccessResult<()> {
+        self.get_with(index, ElementType::Null, RawBsonRef::as_null)
+    }
+
+    /// Gets a reference to the BSON symbol at the given index or returns an error if the value at
+    /// that index isn't a symbol.
+    pub fn get_symbol(&self, index: usize) -> ValueAccessResult<&str> {
+        self.get_with(index, ElementType::Symbol, RawBsonRef::as_symbol)
+    }
+
+    /// Gets a reference to the JavaScript code at the given index or returns an error if the value
+    /// at that index isn't JavaScript code.
+    pub fn get_javascript(&self, index: usize) -> ValueAccessResult<&str> {
+        self.get_with(
+            index,
+            ElementType::JavaScriptCode,
+            RawBsonRef::as_javascript,
+        )
+    }
+
+    /// Gets a reference to the BSON DBPointer at the given index or returns an error if the value
+    /// at that index isn't a DBPointer.
+    pub fn get_db_pointer(&self, index: usize) -> ValueAccessResult<RawDbPointerRef<'_>> {
+        self.get_with(index, ElementType::DbPointer, RawBsonRef::as_db_pointer)
+    }
+
+    /// Gets an iterator over the elements of this array paired with their indexes.
+    ///
+    /// Unlike plain iteration, this validates that the keys of the underlying document are the
+    /// sequential indexes `"0"`, `"1"`, `"2"`, etc., and yields an error if they are not.
+    ///
+    /// ```
+    /// use bson::{doc, raw::RawDocument};
+    ///
+    /// let bytes = bson::to_vec(&doc! { "x": ["a", "b"] })?;
+    /// let rawdoc = RawDocument::from_bytes(bytes.as_slice())?;
+    ///
+    /// for result in rawdoc.get_array("x")?.iter_indexed() {
+    ///     let (index, value) = result?;
+    ///     println!("{}: {:?}", index, value);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn iter_indexed(&self) -> RawArrayIndexedIter<'_> {
+        RawArrayIndexedIter {
+            inner: RawIter::new(&self.doc),
+            index: 0,
+            valid: true,
+        }
+    }
+
     /// Gets a reference to the raw bytes of the [`RawArray`].
     pub fn as_bytes(&self) -> &[u8] {
         self.doc.as_bytes()
@@ -285,6 +376,44 @@ impl<'a> Iterator for RawArrayIter<'a> {
     }
 }
 
+/// An iterator over borrowed raw BSON array values and their indexes, returned by
+/// [`RawArray::iter_indexed`].
+pub struct RawArrayIndexedIter<'a> {
+    inner: RawIter<'a>,
+    index: usize,
+
+    /// Whether an out-of-sequence key has been encountered. After that, all subsequent iterations
+    /// will return None.
+    valid: bool,
+}
+
+impl<'a> Iterator for RawArrayIndexedIter<'a> {
+    type Item = Result<(usize, RawBsonRef<'a>)>;
+
+    fn next(&mut self) -> Option<Result<(usize, RawBsonRef<'a>)>> {
+        if !self.valid {
+            return None;
+        }
+        let elem = match self.inner.next()? {
+            Ok(elem) => elem,
+            Err(e) => return Some(Err(e)),
+        };
+        let index = self.index;
+        if elem.key() != index.to_string() {
+            self.valid = false;
+            return Some(Err(Error::new_with_key(
+                elem.key(),
+                ErrorKind::new_malformed(format!(
+                    "array key out of sequence: expected \"{}\"",
+                    index
+                )),
+            )));
+        }
+        self.index += 1;
+        Some(elem.value().map(|value| (index, value)))
+    }
+}
+
 impl<'de: 'a, 'a> Deserialize<'de> for &'a RawArray {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
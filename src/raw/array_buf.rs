@@ -1,6 +1,6 @@
 use std::{
     borrow::{Borrow, Cow},
-    fmt::Debug,
+    fmt::{Debug, Write},
     iter::FromIterator,
 };
 
@@ -96,6 +96,23 @@ impl RawArrayBuf {
         self.inner.append(self.len.to_string(), value);
         self.len += 1;
     }
+
+    /// Returns the number of values in the array. This is tracked as values are pushed, so no
+    /// traversal is needed.
+    ///
+    /// ```
+    /// use bson::raw::RawArrayBuf;
+    ///
+    /// let mut array = RawArrayBuf::new();
+    /// assert_eq!(array.len(), 0);
+    /// array.push("a string");
+    /// array.extend([1, 2, 3]);
+    /// assert_eq!(array.len(), 4);
+    /// ```
+    #[allow(clippy::len_without_is_empty)] // `is_empty` is available via `Deref<Target = RawArray>`
+    pub fn len(&self) -> usize {
+        self.len
+    }
 }
 
 impl Debug for RawArrayBuf {
@@ -158,6 +175,21 @@ impl<T: Into<RawBson>> FromIterator<T> for RawArrayBuf {
     }
 }
 
+/// Appends each value in turn, with keys continuing on from the current length of the array.
+impl<'a, T: Into<RawBsonRef<'a>>> Extend<T> for RawArrayBuf {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // reuse one buffer for the keys rather than allocating a string for each
+        let mut key = String::new();
+        for value in iter {
+            key.clear();
+            // writing to a `String` can't fail
+            let _ = write!(key, "{}", self.len);
+            self.inner.append_ref(&key, value);
+            self.len += 1;
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for RawArrayBuf {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
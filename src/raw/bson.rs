@@ -151,6 +151,16 @@ impl RawBson {
 
     /// Gets a mutable reference to the [`RawDocumentBuf`] that's wrapped or returns [`None`] if the
     /// wrapped value isn't a BSON document.
+    ///
+    /// This allows an embedded document to be extended in place:
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::RawBson};
+    ///
+    /// let mut value = RawBson::Document(rawdoc! { "a": 1 });
+    /// value.as_document_mut().unwrap().append("b", "two");
+    /// assert_eq!(value, RawBson::Document(rawdoc! { "a": 1, "b": "two" }));
+    /// ```
     pub fn as_document_mut(&mut self) -> Option<&mut RawDocumentBuf> {
         match self {
             RawBson::Document(ref mut v) => Some(v),
@@ -531,6 +541,30 @@ pub struct RawJavaScriptCodeWithScope {
     pub scope: RawDocumentBuf,
 }
 
+impl RawJavaScriptCodeWithScope {
+    /// Creates a new code with scope value from the given code and scope document.
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::RawJavaScriptCodeWithScope};
+    ///
+    /// let code = RawJavaScriptCodeWithScope::new("x + y", rawdoc! { "x": 1 }).with_scope_var("y", 2);
+    /// assert_eq!(code.scope, rawdoc! { "x": 1, "y": 2 });
+    /// ```
+    pub fn new(code: impl Into<String>, scope: RawDocumentBuf) -> Self {
+        Self {
+            code: code.into(),
+            scope,
+        }
+    }
+
+    /// Appends a binding of `key` to `value` to the scope and returns `self`. As with
+    /// [`RawDocumentBuf::append`], no check is made for an existing binding with the same key.
+    pub fn with_scope_var(mut self, key: impl AsRef<str>, value: impl Into<RawBson>) -> Self {
+        self.scope.append(key, value);
+        self
+    }
+}
+
 impl<'de> Deserialize<'de> for RawJavaScriptCodeWithScope {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
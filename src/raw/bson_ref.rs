@@ -218,6 +218,15 @@ impl<'a> RawBsonRef<'a> {
         }
     }
 
+    /// Gets the [`crate::Decimal128`] that's referenced or returns [`None`] if the referenced value
+    /// isn't a BSON decimal128.
+    pub fn as_decimal128(self) -> Option<Decimal128> {
+        match self {
+            RawBsonRef::Decimal128(d) => Some(d),
+            _ => None,
+        }
+    }
+
     /// Gets the null value that's referenced or returns [`None`] if the referenced value isn't a
     /// BSON null.
     pub fn as_null(self) -> Option<()> {
@@ -254,6 +263,31 @@ impl<'a> RawBsonRef<'a> {
         }
     }
 
+    /// Converts this value into extended JSON in the given mode, walking embedded documents and
+    /// arrays directly rather than first converting them to owned values.
+    pub(crate) fn to_extjson(self, mode: extjson::ExtJsonMode) -> Result<serde_json::Value> {
+        Ok(match self {
+            RawBsonRef::Document(doc) => doc.to_extjson(mode)?,
+            RawBsonRef::Array(array) => serde_json::Value::Array(
+                array
+                    .into_iter()
+                    .map(|value| value?.to_extjson(mode))
+                    .collect::<Result<_>>()?,
+            ),
+            RawBsonRef::JavaScriptCodeWithScope(code_w_scope) => serde_json::json!({
+                "$code": code_w_scope.code,
+                "$scope": code_w_scope.scope.to_extjson(mode)?,
+            }),
+            other => {
+                let bson = Bson::try_from(other)?;
+                match mode {
+                    extjson::ExtJsonMode::Canonical => bson.into_canonical_extjson(),
+                    extjson::ExtJsonMode::Relaxed => bson.into_relaxed_extjson(),
+                }
+            }
+        })
+    }
+
     /// Convert this [`RawBsonRef`] to the equivalent [`RawBson`].
     pub fn to_raw_bson(self) -> RawBson {
         match self {
@@ -264,7 +298,7 @@ impl<'a> RawBsonRef<'a> {
             RawBsonRef::Boolean(b) => RawBson::Boolean(b),
             RawBsonRef::Null => RawBson::Null,
             RawBsonRef::RegularExpression(re) => {
-                RawBson::RegularExpression(Regex::new(re.pattern, re.options))
+                RawBson::RegularExpression(Regex::from_strings(re.pattern, re.options))
             }
             RawBsonRef::JavaScriptCode(c) => RawBson::JavaScriptCode(c.to_owned()),
             RawBsonRef::JavaScriptCodeWithScope(c_w_s) => {
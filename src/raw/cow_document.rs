@@ -0,0 +1,157 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Document;
+
+use super::{RawDocument, RawDocumentBuf, Result};
+
+/// A document that keeps the raw bytes it was created from until it is modified.
+///
+/// Serializing a [`CowDocument`] that hasn't been modified re-emits its original bytes without
+/// parsing or re-encoding them, which makes it well suited to pass-through workloads where most
+/// documents are forwarded unchanged. The first call to [`CowDocument::to_mut`] parses the bytes
+/// into an owned [`Document`], which is used from then on.
+///
+/// ```
+/// use bson::{doc, raw::CowDocument, rawdoc};
+///
+/// let bytes = bson::to_vec(&doc! { "a": 1 })?;
+/// let mut doc: CowDocument = bson::from_slice(&bytes)?;
+/// assert!(!doc.is_modified());
+/// assert_eq!(bson::to_vec(&doc)?, bytes);
+///
+/// doc.to_mut()?.insert("b", 2);
+/// assert!(doc.is_modified());
+/// assert_eq!(bson::to_vec(&doc)?, bson::to_vec(&doc! { "a": 1, "b": 2 })?);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct CowDocument {
+    inner: Inner,
+}
+
+#[derive(Clone, Debug)]
+enum Inner {
+    Raw(RawDocumentBuf),
+    Owned(Document),
+}
+
+impl CowDocument {
+    /// Creates a [`CowDocument`] backed by the given raw document.
+    pub fn from_raw(raw: RawDocumentBuf) -> Self {
+        Self {
+            inner: Inner::Raw(raw),
+        }
+    }
+
+    /// Whether this document has been converted to an owned [`Document`], either by
+    /// [`CowDocument::to_mut`] or by being created from one.
+    pub fn is_modified(&self) -> bool {
+        matches!(self.inner, Inner::Owned(_))
+    }
+
+    /// Gets the original raw document, or `None` if this document has been modified.
+    pub fn as_raw(&self) -> Option<&RawDocument> {
+        match &self.inner {
+            Inner::Raw(raw) => Some(raw),
+            Inner::Owned(_) => None,
+        }
+    }
+
+    /// Gets the owned [`Document`], or `None` if this document has not been modified.
+    pub fn as_document(&self) -> Option<&Document> {
+        match &self.inner {
+            Inner::Raw(_) => None,
+            Inner::Owned(doc) => Some(doc),
+        }
+    }
+
+    /// Gets a mutable reference to the owned [`Document`], parsing the raw bytes first if this
+    /// document has not been modified yet. Returns an error if the raw bytes are not valid BSON, in
+    /// which case the document is left unchanged.
+    pub fn to_mut(&mut self) -> Result<&mut Document> {
+        if let Inner::Raw(raw) = &self.inner {
+            self.inner = Inner::Owned(raw.to_document()?);
+        }
+        match &mut self.inner {
+            Inner::Owned(doc) => Ok(doc),
+            Inner::Raw(_) => unreachable!("raw document was just converted"),
+        }
+    }
+
+    /// The encoded bytes of this document, or `None` if it has been modified and can't be
+    /// encoded.
+    fn encoded(&self) -> Option<Cow<'_, RawDocument>> {
+        match &self.inner {
+            Inner::Raw(raw) => Some(Cow::Borrowed(raw.as_ref())),
+            Inner::Owned(doc) => RawDocumentBuf::from_document(doc).ok().map(Cow::Owned),
+        }
+    }
+
+    /// Converts this into an owned [`Document`], parsing the raw bytes if this document has not
+    /// been modified.
+    pub fn into_document(self) -> Result<Document> {
+        match self.inner {
+            Inner::Raw(raw) => raw.to_document(),
+            Inner::Owned(doc) => Ok(doc),
+        }
+    }
+
+    /// Converts this into a [`RawDocumentBuf`], encoding the owned [`Document`] if this document
+    /// has been modified.
+    pub fn into_raw_document_buf(self) -> Result<RawDocumentBuf> {
+        match self.inner {
+            Inner::Raw(raw) => Ok(raw),
+            Inner::Owned(doc) => RawDocumentBuf::from_document(&doc),
+        }
+    }
+}
+
+/// Documents are compared by their encoded bytes, whether or not they have been modified. This
+/// means that, unlike [`Document`]'s equality, key order matters, and a `NaN` value is equal to
+/// another `NaN` with the same bit pattern. A document that can't be encoded isn't equal to
+/// anything.
+impl PartialEq for CowDocument {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.encoded(), other.encoded()) {
+            (Some(a), Some(b)) => a.as_bytes() == b.as_bytes(),
+            _ => false,
+        }
+    }
+}
+
+impl From<RawDocumentBuf> for CowDocument {
+    fn from(raw: RawDocumentBuf) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<Document> for CowDocument {
+    fn from(doc: Document) -> Self {
+        Self {
+            inner: Inner::Owned(doc),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CowDocument {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        RawDocumentBuf::deserialize(deserializer).map(Self::from_raw)
+    }
+}
+
+impl Serialize for CowDocument {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match &self.inner {
+            Inner::Raw(raw) => raw.serialize(serializer),
+            Inner::Owned(doc) => doc.serialize(serializer),
+        }
+    }
+}
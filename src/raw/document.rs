@@ -1,19 +1,23 @@
 use std::{
     borrow::Cow,
+    collections::HashSet,
     convert::{TryFrom, TryInto},
+    ops::Range,
 };
 
 use serde::{ser::SerializeMap, Deserialize, Serialize};
 
 use crate::{
     de::MIN_BSON_DOCUMENT_SIZE,
+    extjson::ExtJsonMode,
     raw::{error::ErrorKind, serde::OwnedOrBorrowedRawDocument, RAW_DOCUMENT_NEWTYPE},
     DateTime,
+    Decimal128,
     Timestamp,
 };
 
 use super::{
-    error::{ValueAccessError, ValueAccessErrorKind, ValueAccessResult},
+    error::{try_with_key, ValueAccessError, ValueAccessErrorKind, ValueAccessResult},
     i32_from_slice,
     iter::Iter,
     try_to_str,
@@ -21,9 +25,11 @@ use super::{
     RawArray,
     RawBinaryRef,
     RawBsonRef,
+    RawDbPointerRef,
     RawDocumentBuf,
     RawIter,
     RawRegexRef,
+    RawVisitor,
     Result,
 };
 use crate::{oid::ObjectId, spec::ElementType, Document};
@@ -126,6 +132,33 @@ impl RawDocument {
         Ok(RawDocument::new_unchecked(data))
     }
 
+    /// Constructs a new [`RawDocument`] after fully validating `data`.
+    ///
+    /// In addition to the checks made by [`RawDocument::from_bytes`], this walks every element,
+    /// recursing into embedded documents, arrays, and code-with-scope values, and verifies the
+    /// structural requirements listed on [`RawDocument::is_canonical`]. Element access and
+    /// iteration on the returned document will not encounter malformed data, at the cost of one
+    /// full pass over the bytes up front. Duplicate or out-of-order keys are not rejected.
+    ///
+    /// ```
+    /// use bson::raw::RawDocument;
+    ///
+    /// let bytes = b"\x13\x00\x00\x00\x02hi\x00\x06\x00\x00\x00y'all\x00\x00";
+    /// let doc = RawDocument::from_bytes_validated(bytes)?;
+    /// assert_eq!(doc.get_str("hi")?, "y'all");
+    ///
+    /// // the string's declared length overruns the document
+    /// let bad = b"\x13\x00\x00\x00\x02hi\x00\x07\x00\x00\x00y'all\x00\x00";
+    /// assert!(RawDocument::from_bytes(bad).is_ok());
+    /// assert!(RawDocument::from_bytes_validated(bad).is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_bytes_validated<D: AsRef<[u8]> + ?Sized>(data: &D) -> Result<&RawDocument> {
+        let doc = RawDocument::from_bytes(data)?;
+        doc.validate()?;
+        Ok(doc)
+    }
+
     /// Creates a new [`RawDocument`] referencing the provided data slice.
     pub(crate) fn new_unchecked<D: AsRef<[u8]> + ?Sized>(data: &D) -> &RawDocument {
         // SAFETY:
@@ -154,6 +187,37 @@ impl RawDocument {
         RawDocumentBuf::from_bytes(self.data.to_owned()).unwrap()
     }
 
+    /// Converts this document into its [extended JSON](crate::extjson) representation in the
+    /// given mode.
+    ///
+    /// This reads values directly from the underlying bytes, so it avoids building the owned
+    /// [`Document`] that converting via [`Bson::into_relaxed_extjson`] or
+    /// [`Bson::into_canonical_extjson`] would require.
+    ///
+    /// ```
+    /// use bson::{extjson::ExtJsonMode, rawdoc};
+    /// use serde_json::json;
+    ///
+    /// let doc = rawdoc! { "x": 1, "y": { "z": [2_i64] } };
+    /// assert_eq!(doc.to_extjson(ExtJsonMode::Relaxed)?, json!({ "x": 1, "y": { "z": [2] } }));
+    /// assert_eq!(
+    ///     doc.to_extjson(ExtJsonMode::Canonical)?,
+    ///     json!({
+    ///         "x": { "$numberInt": "1" },
+    ///         "y": { "z": [{ "$numberLong": "2" }] },
+    ///     })
+    /// );
+    /// # Ok::<(), bson::raw::Error>(())
+    /// ```
+    pub fn to_extjson(&self, mode: ExtJsonMode) -> Result<serde_json::Value> {
+        let mut map = serde_json::Map::new();
+        for result in self {
+            let (key, value) = result?;
+            map.insert(key.to_string(), value.to_extjson(mode)?);
+        }
+        Ok(serde_json::Value::Object(map))
+    }
+
     /// Gets a reference to the value corresponding to the given key by iterating until the key is
     /// found.
     ///
@@ -181,6 +245,36 @@ impl RawDocument {
         Ok(None)
     }
 
+    /// Gets a reference to the value corresponding to the given key along with the byte range
+    /// the value occupies within [`RawDocument::as_bytes`], or [`None`] if the key is not present.
+    ///
+    /// The range covers only the encoded value; the element's type byte and null-terminated key
+    /// immediately precede it. Recording these ranges allows repeated lookups into a wide
+    /// document without rescanning it from the beginning each time.
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::{RawBsonRef, RawDocument}};
+    ///
+    /// let doc = rawdoc! { "a": 1, "b": "hi" };
+    /// let (value, span) = doc.get_with_span("b")?.unwrap();
+    /// assert_eq!(value, RawBsonRef::String("hi"));
+    /// assert_eq!(&doc.as_bytes()[span], b"\x03\x00\x00\x00hi\x00");
+    /// assert!(doc.get_with_span("c")?.is_none());
+    /// # Ok::<(), bson::raw::Error>(())
+    /// ```
+    pub fn get_with_span(
+        &self,
+        key: impl AsRef<str>,
+    ) -> Result<Option<(RawBsonRef<'_>, Range<usize>)>> {
+        for elem in RawIter::new(self) {
+            let elem = elem?;
+            if key.as_ref() == elem.key() {
+                return Ok(Some((elem.value()?, elem.value_range())));
+            }
+        }
+        Ok(None)
+    }
+
     /// Gets an iterator over the elements in the [`RawDocument`] that yields
     /// `Result<(&str, RawBson<'_>)>`.
     pub fn iter(&self) -> Iter<'_> {
@@ -201,6 +295,63 @@ impl RawDocument {
         RawIter::new(self)
     }
 
+    /// Walks the document depth-first, passing each field and array element to `visitor` without
+    /// materializing any owned values. The visitor decides whether to descend into each embedded
+    /// document or array and can end the traversal early; see [`RawVisitor`] for details.
+    ///
+    /// An error is returned if malformed data is encountered, after the visitor has seen all the
+    /// elements preceding it.
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::{RawBsonRef, RawVisitor, VisitControl}};
+    ///
+    /// /// Sums every `i32` in the document except those under `skip`.
+    /// struct Sum(i32);
+    ///
+    /// impl<'a> RawVisitor<'a> for Sum {
+    ///     fn on_field(&mut self, key: &'a str, value: RawBsonRef<'a>) -> VisitControl {
+    ///         if key == "skip" {
+    ///             return VisitControl::Skip;
+    ///         }
+    ///         self.on_array_element(0, value)
+    ///     }
+    ///
+    ///     fn on_array_element(&mut self, _index: usize, value: RawBsonRef<'a>) -> VisitControl {
+    ///         if let RawBsonRef::Int32(i) = value {
+    ///             self.0 += i;
+    ///         }
+    ///         VisitControl::Continue
+    ///     }
+    /// }
+    ///
+    /// let doc = rawdoc! { "a": 1, "b": [2, { "c": 3 }], "skip": { "d": 100 } };
+    /// let mut sum = Sum(0);
+    /// doc.visit(&mut sum)?;
+    /// assert_eq!(sum.0, 6);
+    /// # Ok::<(), bson::raw::Error>(())
+    /// ```
+    pub fn visit<'a, V: RawVisitor<'a> + ?Sized>(&'a self, visitor: &mut V) -> Result<()> {
+        super::visit::visit(self, visitor)
+    }
+
+    /// Gets an iterator over the keys of the [`RawDocument`].
+    ///
+    /// Only the type byte and key of each element are read; values are skipped over using their
+    /// encoded lengths and are never parsed. Iteration stops after the first error.
+    ///
+    /// ```
+    /// # use bson::raw::Error;
+    /// use bson::rawdoc;
+    ///
+    /// let doc = rawdoc! { "a": 1, "b": { "c": true }, "d": "x" };
+    /// let keys = doc.keys().collect::<Result<Vec<_>, Error>>()?;
+    /// assert_eq!(keys, ["a", "b", "d"]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = Result<&str>> + '_ {
+        self.iter_elements().map(|element| element.map(|e| e.key()))
+    }
+
     fn get_with<'a, T>(
         &'a self,
         key: impl AsRef<str>,
@@ -497,6 +648,102 @@ impl RawDocument {
         self.get_with(key, ElementType::Int64, RawBsonRef::as_i64)
     }
 
+    /// Gets the BSON decimal128 value corresponding to a given key or returns an error if the key
+    /// corresponds to a value which isn't a decimal128.
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::ValueAccessErrorKind, Decimal128};
+    ///
+    /// let decimal = Decimal128::from_bytes([0; 16]);
+    /// let doc = rawdoc! {
+    ///     "bool": true,
+    ///     "decimal": decimal,
+    /// };
+    ///
+    /// assert_eq!(doc.get_decimal128("decimal")?, decimal);
+    /// assert!(matches!(doc.get_decimal128("bool").unwrap_err().kind, ValueAccessErrorKind::UnexpectedType { .. }));
+    /// assert!(matches!(doc.get_decimal128("unknown").unwrap_err().kind, ValueAccessErrorKind::NotPresent));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_decimal128(&self, key: impl AsRef<str>) -> ValueAccessResult<Decimal128> {
+        self.get_with(key, ElementType::Decimal128, RawBsonRef::as_decimal128)
+    }
+
+    /// Gets the BSON null value corresponding to a given key or returns an error if the key
+    /// corresponds to a value which isn't null.
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::{RawBson, ValueAccessErrorKind}};
+    ///
+    /// let doc = rawdoc! {
+    ///     "bool": true,
+    ///     "null": RawBson::Null,
+    /// };
+    ///
+    /// assert_eq!(doc.get_null("null")?, ());
+    /// assert!(matches!(doc.get_null("bool").unwrap_err().kind, ValueAccessErrorKind::UnexpectedType { .. }));
+    /// assert!(matches!(doc.get_null("unknown").unwrap_err().kind, ValueAccessErrorKind::NotPresent));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_null(&self, key: impl AsRef<str>) -> ValueAccessResult<()> {
+        self.get_with(key, ElementType::Null, RawBsonRef::as_null)
+    }
+
+    /// Gets a reference to the BSON symbol corresponding to a given key or returns an error if the
+    /// key corresponds to a value which isn't a symbol.
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::{RawBson, ValueAccessErrorKind}};
+    ///
+    /// let doc = rawdoc! {
+    ///     "bool": true,
+    ///     "symbol": RawBson::Symbol("sym".to_string()),
+    /// };
+    ///
+    /// assert_eq!(doc.get_symbol("symbol")?, "sym");
+    /// assert!(matches!(doc.get_symbol("bool").unwrap_err().kind, ValueAccessErrorKind::UnexpectedType { .. }));
+    /// assert!(matches!(doc.get_symbol("unknown").unwrap_err().kind, ValueAccessErrorKind::NotPresent));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_symbol(&self, key: impl AsRef<str>) -> ValueAccessResult<&'_ str> {
+        self.get_with(key, ElementType::Symbol, RawBsonRef::as_symbol)
+    }
+
+    /// Gets a reference to the JavaScript code corresponding to a given key or returns an error if
+    /// the key corresponds to a value which isn't JavaScript code.
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::{RawBson, ValueAccessErrorKind}};
+    ///
+    /// let doc = rawdoc! {
+    ///     "bool": true,
+    ///     "code": RawBson::JavaScriptCode("return 1;".to_string()),
+    /// };
+    ///
+    /// assert_eq!(doc.get_javascript("code")?, "return 1;");
+    /// assert!(matches!(doc.get_javascript("bool").unwrap_err().kind, ValueAccessErrorKind::UnexpectedType { .. }));
+    /// assert!(matches!(doc.get_javascript("unknown").unwrap_err().kind, ValueAccessErrorKind::NotPresent));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_javascript(&self, key: impl AsRef<str>) -> ValueAccessResult<&'_ str> {
+        self.get_with(key, ElementType::JavaScriptCode, RawBsonRef::as_javascript)
+    }
+
+    /// Gets a reference to the BSON DBPointer corresponding to a given key or returns an error if
+    /// the key corresponds to a value which isn't a DBPointer.
+    ///
+    /// ```
+    /// use bson::{rawdoc, raw::ValueAccessErrorKind};
+    ///
+    /// let doc = rawdoc! { "bool": true };
+    ///
+    /// assert!(matches!(doc.get_db_pointer("bool").unwrap_err().kind, ValueAccessErrorKind::UnexpectedType { .. }));
+    /// assert!(matches!(doc.get_db_pointer("unknown").unwrap_err().kind, ValueAccessErrorKind::NotPresent));
+    /// ```
+    pub fn get_db_pointer(&self, key: impl AsRef<str>) -> ValueAccessResult<RawDbPointerRef<'_>> {
+        self.get_with(key, ElementType::DbPointer, RawBsonRef::as_db_pointer)
+    }
+
     /// Return a reference to the contained data as a `&[u8]`
     ///
     /// ```
@@ -510,11 +757,162 @@ impl RawDocument {
         &self.data
     }
 
+    /// Returns an owned copy of the contained data as a `Vec<u8>`.
+    ///
+    /// ```
+    /// # use bson::raw::Error;
+    /// use bson::rawdoc;
+    /// let docbuf = rawdoc! { "a": 1 };
+    /// let bytes: Vec<u8> = docbuf.to_vec();
+    /// assert_eq!(bytes, docbuf.as_bytes());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
     /// Returns whether this document contains any elements or not.
+    ///
+    /// An empty document still occupies 5 bytes (the length prefix and null terminator), so this
+    /// is not the same as having an empty byte buffer.
+    ///
+    /// ```
+    /// use bson::rawdoc;
+    ///
+    /// let doc = rawdoc! {};
+    /// assert!(doc.is_empty());
+    /// assert_eq!(doc.as_bytes().len(), 5);
+    /// assert!(!rawdoc! { "a": 1 }.is_empty());
+    /// ```
     pub fn is_empty(&self) -> bool {
         self.as_bytes().len() == MIN_BSON_DOCUMENT_SIZE as usize
     }
 
+    /// Counts the elements in this document, stopping as soon as more than `n` have been found.
+    ///
+    /// The returned count is at most `n + 1`; a result greater than `n` means the document has more
+    /// than `n` elements, without the rest of the document having been scanned. This bounds the
+    /// work needed for threshold checks on large documents.
+    ///
+    /// An error is returned if a malformed element is encountered before the scan stops.
+    ///
+    /// ```
+    /// # use bson::raw::Error;
+    /// use bson::rawdoc;
+    ///
+    /// let doc = rawdoc! { "a": 1, "b": 2, "c": 3 };
+    /// assert_eq!(doc.count_at_most(5)?, 3);
+    /// assert_eq!(doc.count_at_most(1)?, 2);
+    /// assert!(doc.count_at_most(2)? > 2);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn count_at_most(&self, n: usize) -> Result<usize> {
+        let mut count = 0;
+        for element in self.iter_elements().take(n.saturating_add(1)) {
+            element?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Fully validates the structure of this document and reports whether it is in canonical form.
+    ///
+    /// Unlike [`RawDocument::from_bytes`], which only checks the outer length and terminator, this
+    /// parses every element, recursing into embedded documents, arrays, and code-with-scope
+    /// values. An error is returned if any of the following are violated:
+    ///   * every element's declared length fits within its enclosing document
+    ///   * every document ends with a null terminator immediately after its last element
+    ///   * every key and string value is valid UTF-8 and properly null-terminated
+    ///   * every value is a valid encoding of its element type
+    ///
+    /// If the document is structurally valid, `Ok(false)` is returned if it is nonetheless not in
+    /// canonical form, i.e. if any document contains duplicate keys or any array's keys are not
+    /// the sequential indexes `"0"`, `"1"`, `"2"`, etc.
+    ///
+    /// ```
+    /// use bson::rawdoc;
+    ///
+    /// let doc = rawdoc! { "a": 1, "b": [true, { "c": "d" }] };
+    /// assert!(doc.is_canonical()?);
+    /// # Ok::<(), bson::raw::Error>(())
+    /// ```
+    pub fn is_canonical(&self) -> Result<bool> {
+        /// A document or array whose elements are being checked.
+        struct Frame<'a> {
+            iter: RawIter<'a>,
+            is_array: bool,
+            next_index: usize,
+            keys: HashSet<&'a str>,
+        }
+
+        impl<'a> Frame<'a> {
+            fn new(doc: &'a RawDocument, is_array: bool) -> Self {
+                Self {
+                    iter: doc.iter_elements(),
+                    is_array,
+                    next_index: 0,
+                    keys: HashSet::new(),
+                }
+            }
+        }
+
+        // An explicit stack rather than recursion, so that deeply nested untrusted input can't
+        // overflow the call stack.
+        let mut stack = vec![Frame::new(self, false)];
+        while let Some(frame) = stack.last_mut() {
+            let elem = match frame.iter.next() {
+                Some(elem) => elem?,
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            };
+            let key = elem.key();
+            if frame.is_array {
+                if !key_is_index(key, frame.next_index) {
+                    return Ok(false);
+                }
+                frame.next_index += 1;
+            } else if !frame.keys.insert(key) {
+                return Ok(false);
+            }
+            match try_with_key(key, || elem.value())? {
+                RawBsonRef::Document(doc) => stack.push(Frame::new(doc, false)),
+                RawBsonRef::Array(arr) => stack.push(Frame::new(&arr.doc, true)),
+                RawBsonRef::JavaScriptCodeWithScope(cws) => {
+                    stack.push(Frame::new(cws.scope, false))
+                }
+                _ => {}
+            }
+        }
+        Ok(true)
+    }
+
+    fn validate(&self) -> Result<()> {
+        // An explicit stack rather than recursion, so that deeply nested untrusted input can't
+        // overflow the call stack.
+        let mut stack = vec![self.iter_elements()];
+        loop {
+            let elem = match stack.last_mut() {
+                Some(iter) => iter.next(),
+                None => return Ok(()),
+            };
+            let elem = match elem {
+                Some(elem) => elem?,
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            };
+            match try_with_key(elem.key(), || elem.value())? {
+                RawBsonRef::Document(doc) => stack.push(doc.iter_elements()),
+                RawBsonRef::Array(arr) => stack.push(arr.doc.iter_elements()),
+                RawBsonRef::JavaScriptCodeWithScope(cws) => stack.push(cws.scope.iter_elements()),
+                _ => {}
+            }
+        }
+    }
+
     pub(crate) fn read_cstring_at(&self, start_at: usize) -> Result<&str> {
         let buf = &self.as_bytes()[start_at..];
 
@@ -532,6 +930,18 @@ impl RawDocument {
     }
 }
 
+/// Whether `key` is exactly the decimal form of `index`, as array keys must be, without
+/// allocating a string for the index.
+fn key_is_index(key: &str, index: usize) -> bool {
+    let bytes = key.as_bytes();
+    let canonical_digits = match bytes {
+        [] => false,
+        [b'0', _, ..] => false,
+        _ => bytes.iter().all(u8::is_ascii_digit),
+    };
+    canonical_digits && key.parse::<usize>() == Ok(index)
+}
+
 impl<'de: 'a, 'a> Deserialize<'de> for &'a RawDocument {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
@@ -1,5 +1,6 @@
 use std::{
     borrow::{Borrow, Cow},
+    collections::HashMap,
     convert::{TryFrom, TryInto},
     iter::FromIterator,
     ops::Deref,
@@ -7,15 +8,17 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::{de::MIN_BSON_DOCUMENT_SIZE, spec::BinarySubtype, Document};
+use crate::{de::MIN_BSON_DOCUMENT_SIZE, spec::BinarySubtype, Bson, Document};
 
 use super::{
     bson::RawBson,
+    error::try_with_key,
     iter::Iter,
     serde::OwnedOrBorrowedRawDocument,
     Error,
     ErrorKind,
     RawBsonRef,
+    RawDbPointerRef,
     RawDocument,
     RawIter,
     Result,
@@ -93,6 +96,13 @@ impl RawDocumentBuf {
         Ok(Self { data })
     }
 
+    /// Constructs a new [`RawDocumentBuf`] from bytes that are already known to hold a document,
+    /// such as the output of the raw serializer, without checking them.
+    pub(crate) fn from_bytes_unchecked(data: Vec<u8>) -> RawDocumentBuf {
+        debug_assert!(RawDocument::from_bytes(data.as_slice()).is_ok());
+        Self { data }
+    }
+
     /// Create a [`RawDocumentBuf`] from a [`Document`].
     ///
     /// ```
@@ -109,13 +119,7 @@ impl RawDocumentBuf {
     /// ```
     pub fn from_document(doc: &Document) -> Result<RawDocumentBuf> {
         let mut data = Vec::new();
-        doc.to_writer(&mut data).map_err(|e| Error {
-            key: None,
-            kind: ErrorKind::MalformedValue {
-                message: e.to_string(),
-            },
-        })?;
-
+        encode_document(&mut data, doc)?;
         Ok(Self { data })
     }
 
@@ -179,6 +183,23 @@ impl RawDocumentBuf {
         self.data
     }
 
+    /// Returns the current encoded size of the document in bytes, including the length prefix and
+    /// null terminator. This is never less than 5, the size of an empty document; use
+    /// [`RawDocument::is_empty`] to check whether the document has any elements.
+    ///
+    /// ```
+    /// use bson::rawdoc;
+    ///
+    /// let mut doc = rawdoc! {};
+    /// assert_eq!(doc.len_bytes(), 5);
+    /// doc.append("a", 1);
+    /// assert_eq!(doc.len_bytes(), 12);
+    /// assert_eq!(doc.len_bytes(), doc.as_bytes().len());
+    /// ```
+    pub fn len_bytes(&self) -> usize {
+        self.data.len()
+    }
+
     /// Append a key value pair to the end of the document without checking to see if
     /// the key already exists.
     ///
@@ -221,95 +242,15 @@ impl RawDocumentBuf {
     ///
     /// If the provided key contains an interior null byte, this method will panic.
     pub fn append_ref<'a>(&mut self, key: impl AsRef<str>, value: impl Into<RawBsonRef<'a>>) {
-        fn append_string(doc: &mut RawDocumentBuf, value: &str) {
-            doc.data
-                .extend(((value.as_bytes().len() + 1) as i32).to_le_bytes());
-            doc.data.extend(value.as_bytes());
-            doc.data.push(0);
-        }
-
-        fn append_cstring(doc: &mut RawDocumentBuf, value: &str) {
-            if value.contains('\0') {
-                panic!("cstr includes interior null byte: {}", value)
-            }
-            doc.data.extend(value.as_bytes());
-            doc.data.push(0);
-        }
-
         let original_len = self.data.len();
 
         // write the key for the next value to the end
         // the element type will replace the previous null byte terminator of the document
-        append_cstring(self, key.as_ref());
+        append_cstring(&mut self.data, key.as_ref());
 
         let value = value.into();
         let element_type = value.element_type();
-
-        match value {
-            RawBsonRef::Int32(i) => {
-                self.data.extend(i.to_le_bytes());
-            }
-            RawBsonRef::String(s) => {
-                append_string(self, s);
-            }
-            RawBsonRef::Document(d) => {
-                self.data.extend(d.as_bytes());
-            }
-            RawBsonRef::Array(a) => {
-                self.data.extend(a.as_bytes());
-            }
-            RawBsonRef::Binary(b) => {
-                let len = b.len();
-                self.data.extend(len.to_le_bytes());
-                self.data.push(b.subtype.into());
-                if let BinarySubtype::BinaryOld = b.subtype {
-                    self.data.extend((len - 4).to_le_bytes())
-                }
-                self.data.extend(b.bytes);
-            }
-            RawBsonRef::Boolean(b) => {
-                self.data.push(b as u8);
-            }
-            RawBsonRef::DateTime(dt) => {
-                self.data.extend(dt.timestamp_millis().to_le_bytes());
-            }
-            RawBsonRef::DbPointer(dbp) => {
-                append_string(self, dbp.namespace);
-                self.data.extend(dbp.id.bytes());
-            }
-            RawBsonRef::Decimal128(d) => {
-                self.data.extend(d.bytes());
-            }
-            RawBsonRef::Double(d) => {
-                self.data.extend(d.to_le_bytes());
-            }
-            RawBsonRef::Int64(i) => {
-                self.data.extend(i.to_le_bytes());
-            }
-            RawBsonRef::RegularExpression(re) => {
-                append_cstring(self, re.pattern);
-                append_cstring(self, re.options);
-            }
-            RawBsonRef::JavaScriptCode(js) => {
-                append_string(self, js);
-            }
-            RawBsonRef::JavaScriptCodeWithScope(code_w_scope) => {
-                let len = code_w_scope.len();
-                self.data.extend(len.to_le_bytes());
-                append_string(self, code_w_scope.code);
-                self.data.extend(code_w_scope.scope.as_bytes());
-            }
-            RawBsonRef::Timestamp(ts) => {
-                self.data.extend(ts.to_le_bytes());
-            }
-            RawBsonRef::ObjectId(oid) => {
-                self.data.extend(oid.bytes());
-            }
-            RawBsonRef::Symbol(s) => {
-                append_string(self, s);
-            }
-            RawBsonRef::Null | RawBsonRef::Undefined | RawBsonRef::MinKey | RawBsonRef::MaxKey => {}
-        }
+        append_value(&mut self.data, value);
 
         // update element type
         self.data[original_len - 1] = element_type as u8;
@@ -320,11 +261,157 @@ impl RawDocumentBuf {
         self.data[0..4].copy_from_slice(&new_len);
     }
 
+    /// Appends a pre-encoded element to the end of the document without parsing or checking it,
+    /// updating the length prefix.
+    ///
+    /// This is the fastest way to copy fields between documents, e.g. using
+    /// [`RawElement::element_bytes`](crate::raw::RawElement::element_bytes).
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be exactly one well-formed BSON element: an element type byte, a
+    /// null-terminated UTF-8 key, and a value correctly encoded for that type. Appending anything
+    /// else breaks the invariant that a [`RawDocumentBuf`] holds a valid document, and code relying
+    /// on that invariant may behave incorrectly.
+    ///
+    /// ```
+    /// # use bson::raw::Error;
+    /// use bson::{rawdoc, raw::RawDocumentBuf};
+    ///
+    /// let source = rawdoc! { "a": 1, "b": { "c": "d" } };
+    /// let mut projected = RawDocumentBuf::new();
+    /// for element in source.iter_elements() {
+    ///     let element = element?;
+    ///     if element.key() == "b" {
+    ///         // Safety: the bytes were produced by iterating a valid document.
+    ///         unsafe { projected.append_element_bytes(element.element_bytes()) };
+    ///     }
+    /// }
+    /// assert_eq!(projected, rawdoc! { "b": { "c": "d" } });
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub unsafe fn append_element_bytes(&mut self, bytes: &[u8]) {
+        // the new element replaces the previous null byte terminator of the document
+        self.data.pop();
+        self.data.extend_from_slice(bytes);
+        self.data.push(0);
+        let new_len = (self.data.len() as i32).to_le_bytes();
+        self.data[0..4].copy_from_slice(&new_len);
+    }
+
+    /// Rearranges the document's elements so that those with the keys in `key_order` come first,
+    /// in that order. Elements whose keys are not listed keep their relative order and follow the
+    /// listed ones, and listed keys that aren't present are ignored. If a key appears more than
+    /// once in the document, all of its elements are moved together.
+    ///
+    /// Elements are moved by copying their encoded bytes, so no values are parsed. If the
+    /// document is malformed, an error is returned and the document is left unchanged.
+    ///
+    /// ```
+    /// use bson::rawdoc;
+    ///
+    /// let mut doc = rawdoc! { "c": 3, "a": 1, "d": 4, "b": 2 };
+    /// doc.reorder(&["a", "b", "z"])?;
+    /// assert_eq!(doc, rawdoc! { "a": 1, "b": 2, "c": 3, "d": 4 });
+    /// # Ok::<(), bson::raw::Error>(())
+    /// ```
+    pub fn reorder(&mut self, key_order: &[&str]) -> Result<()> {
+        let mut elements = Vec::new();
+        let mut by_key: HashMap<&str, Vec<usize>> = HashMap::new();
+        for element in self.iter_elements() {
+            let element = element?;
+            by_key
+                .entry(element.key())
+                .or_default()
+                .push(elements.len());
+            elements.push(Some(element.element_bytes()));
+        }
+
+        // the total length is unchanged, so the length prefix can be copied as-is
+        let mut data = Vec::with_capacity(self.data.len());
+        data.extend_from_slice(&self.data[..4]);
+        for key in key_order {
+            for index in by_key.remove(key).unwrap_or_default() {
+                data.extend(elements[index].take().into_iter().flatten());
+            }
+        }
+        data.extend(elements.into_iter().flatten().flatten());
+        data.push(0);
+        self.data = data;
+        Ok(())
+    }
+
+    /// Shortens the document to its first `n` elements, dropping the rest. If `n` is greater than
+    /// or equal to the number of elements, this has no effect.
+    ///
+    /// Only the first `n` elements are scanned, and their values are not parsed. If the document
+    /// is malformed within those elements, an error is returned and the document is left
+    /// unchanged.
+    ///
+    /// ```
+    /// use bson::rawdoc;
+    ///
+    /// let mut doc = rawdoc! { "a": 1, "b": "two", "c": [3] };
+    /// doc.truncate(2)?;
+    /// assert_eq!(doc, rawdoc! { "a": 1, "b": "two" });
+    ///
+    /// doc.truncate(5)?;
+    /// assert_eq!(doc, rawdoc! { "a": 1, "b": "two" });
+    /// # Ok::<(), bson::raw::Error>(())
+    /// ```
+    pub fn truncate(&mut self, n: usize) -> Result<()> {
+        // the first element starts right after the length prefix
+        let mut end = 4;
+        for element in self.iter_elements().take(n) {
+            end = element?.value_range().end;
+        }
+        // everything after the last kept element, other than the null terminator, is dropped
+        if end + 1 < self.data.len() {
+            self.data.truncate(end);
+            self.data.push(0);
+            let new_len = (self.data.len() as i32).to_le_bytes();
+            self.data[0..4].copy_from_slice(&new_len);
+        }
+        Ok(())
+    }
+
     /// Convert this [`RawDocumentBuf`] to a [`Document`], returning an error
     /// if invalid BSON is encountered.
     pub fn to_document(&self) -> Result<Document> {
         self.as_ref().try_into()
     }
+
+    /// Convert this [`RawDocumentBuf`] to a [`Document`] containing only the top-level fields for
+    /// which `keep` returns true.
+    ///
+    /// Fields that are not kept are skipped over without their values being parsed or allocated,
+    /// which makes this much cheaper than [`RawDocumentBuf::to_document`] when projecting a few
+    /// fields out of a wide document. An error is still returned if the document's structure is
+    /// invalid.
+    ///
+    /// ```
+    /// # use bson::raw::Error;
+    /// use bson::{doc, rawdoc};
+    ///
+    /// let raw = rawdoc! { "a": 1, "b": { "big": [1, 2, 3] }, "c": "x" };
+    /// let doc = raw.into_document_partial(|key| key != "b")?;
+    /// assert_eq!(doc, doc! { "a": 1, "c": "x" });
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn into_document_partial<F>(self, mut keep: F) -> Result<Document>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let mut doc = Document::new();
+        for element in self.iter_elements() {
+            let element = element?;
+            if keep(element.key()) {
+                let value = try_with_key(element.key(), || Bson::try_from(element.value()?))?;
+                doc.insert(element.key(), value);
+            }
+        }
+        Ok(doc)
+    }
 }
 
 impl Default for RawDocumentBuf {
@@ -426,3 +513,178 @@ impl<S: AsRef<str>, T: Into<RawBson>> FromIterator<(S, T)> for RawDocumentBuf {
         buf
     }
 }
+
+fn append_string(data: &mut Vec<u8>, value: &str) {
+    data.extend(((value.as_bytes().len() + 1) as i32).to_le_bytes());
+    data.extend(value.as_bytes());
+    data.push(0);
+}
+
+/// Appends `value` as a null-terminated cstring, panicking if it contains an interior null byte.
+pub(crate) fn append_cstring(data: &mut Vec<u8>, value: &str) {
+    if value.contains('\0') {
+        panic!("cstr includes interior null byte: {}", value)
+    }
+    data.extend(value.as_bytes());
+    data.push(0);
+}
+
+/// Appends the encoded bytes of `value` (without its element type or key).
+pub(crate) fn append_value(data: &mut Vec<u8>, value: RawBsonRef<'_>) {
+    match value {
+        RawBsonRef::Int32(i) => {
+            data.extend(i.to_le_bytes());
+        }
+        RawBsonRef::String(s) => {
+            append_string(data, s);
+        }
+        RawBsonRef::Document(d) => {
+            data.extend(d.as_bytes());
+        }
+        RawBsonRef::Array(a) => {
+            data.extend(a.as_bytes());
+        }
+        RawBsonRef::Binary(b) => {
+            let len = b.len();
+            data.extend(len.to_le_bytes());
+            data.push(b.subtype.into());
+            if let BinarySubtype::BinaryOld = b.subtype {
+                data.extend((len - 4).to_le_bytes())
+            }
+            data.extend(b.bytes);
+        }
+        RawBsonRef::Boolean(b) => {
+            data.push(b as u8);
+        }
+        RawBsonRef::DateTime(dt) => {
+            data.extend(dt.timestamp_millis().to_le_bytes());
+        }
+        RawBsonRef::DbPointer(dbp) => {
+            append_string(data, dbp.namespace);
+            data.extend(dbp.id.bytes());
+        }
+        RawBsonRef::Decimal128(d) => {
+            data.extend(d.bytes());
+        }
+        RawBsonRef::Double(d) => {
+            data.extend(d.to_le_bytes());
+        }
+        RawBsonRef::Int64(i) => {
+            data.extend(i.to_le_bytes());
+        }
+        RawBsonRef::RegularExpression(re) => {
+            append_cstring(data, re.pattern);
+            append_cstring(data, re.options);
+        }
+        RawBsonRef::JavaScriptCode(js) => {
+            append_string(data, js);
+        }
+        RawBsonRef::JavaScriptCodeWithScope(code_w_scope) => {
+            let len = code_w_scope.len();
+            data.extend(len.to_le_bytes());
+            append_string(data, code_w_scope.code);
+            data.extend(code_w_scope.scope.as_bytes());
+        }
+        RawBsonRef::Timestamp(ts) => {
+            data.extend(ts.to_le_bytes());
+        }
+        RawBsonRef::ObjectId(oid) => {
+            data.extend(oid.bytes());
+        }
+        RawBsonRef::Symbol(s) => {
+            append_string(data, s);
+        }
+        RawBsonRef::Null | RawBsonRef::Undefined | RawBsonRef::MinKey | RawBsonRef::MaxKey => {}
+    }
+}
+
+fn check_cstring(key: &str, value: &str) -> Result<()> {
+    if value.contains('\0') {
+        return Err(Error::new_with_key(
+            key,
+            ErrorKind::new_malformed(format!("cstring with interior null: {:?}", value)),
+        ));
+    }
+    Ok(())
+}
+
+/// Writes the length-prefixed encoding of `start..` into the placeholder at `start`.
+fn backfill_length(data: &mut [u8], start: usize) {
+    let len = ((data.len() - start) as i32).to_le_bytes();
+    data[start..start + 4].copy_from_slice(&len);
+}
+
+/// Encodes `doc` directly into `data` without going through serde.
+fn encode_document(data: &mut Vec<u8>, doc: &Document) -> Result<()> {
+    let start = data.len();
+    data.extend([0; 4]);
+    for (key, value) in doc {
+        encode_element(data, key, value)?;
+    }
+    data.push(0);
+    backfill_length(data, start);
+    Ok(())
+}
+
+fn encode_array(data: &mut Vec<u8>, array: &[Bson]) -> Result<()> {
+    let start = data.len();
+    data.extend([0; 4]);
+    for (index, value) in array.iter().enumerate() {
+        encode_element(data, &index.to_string(), value)?;
+    }
+    data.push(0);
+    backfill_length(data, start);
+    Ok(())
+}
+
+fn encode_element(data: &mut Vec<u8>, key: &str, value: &Bson) -> Result<()> {
+    check_cstring(key, key)?;
+    data.push(value.element_type() as u8);
+    append_cstring(data, key);
+
+    let value = match value {
+        Bson::Document(doc) => return encode_document(data, doc),
+        Bson::Array(array) => return encode_array(data, array),
+        Bson::JavaScriptCodeWithScope(code_w_scope) => {
+            let start = data.len();
+            data.extend([0; 4]);
+            append_string(data, &code_w_scope.code);
+            encode_document(data, &code_w_scope.scope)?;
+            backfill_length(data, start);
+            return Ok(());
+        }
+        Bson::RegularExpression(regex) => {
+            check_cstring(key, &regex.pattern)?;
+            check_cstring(key, &regex.options)?;
+            // match the serializer, which always writes options in sorted order
+            let mut options: Vec<_> = regex.options.chars().collect();
+            options.sort_unstable();
+            let options: String = options.into_iter().collect();
+            append_cstring(data, &regex.pattern);
+            append_cstring(data, &options);
+            return Ok(());
+        }
+        Bson::Double(d) => RawBsonRef::Double(*d),
+        Bson::String(s) => RawBsonRef::String(s),
+        Bson::Binary(binary) => RawBsonRef::Binary(binary.as_raw_binary()),
+        Bson::ObjectId(oid) => RawBsonRef::ObjectId(*oid),
+        Bson::Boolean(b) => RawBsonRef::Boolean(*b),
+        Bson::DateTime(dt) => RawBsonRef::DateTime(*dt),
+        Bson::Null => RawBsonRef::Null,
+        Bson::JavaScriptCode(code) => RawBsonRef::JavaScriptCode(code),
+        Bson::Int32(i) => RawBsonRef::Int32(*i),
+        Bson::Timestamp(ts) => RawBsonRef::Timestamp(*ts),
+        Bson::Int64(i) => RawBsonRef::Int64(*i),
+        Bson::Undefined => RawBsonRef::Undefined,
+        Bson::DbPointer(dbp) => RawBsonRef::DbPointer(RawDbPointerRef {
+            namespace: &dbp.namespace,
+            id: dbp.id,
+        }),
+        Bson::Symbol(s) => RawBsonRef::Symbol(s),
+        Bson::Decimal128(d) => RawBsonRef::Decimal128(*d),
+        Bson::MaxKey => RawBsonRef::MaxKey,
+        Bson::MinKey => RawBsonRef::MinKey,
+    };
+    append_value(data, value);
+    Ok(())
+}
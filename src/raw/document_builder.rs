@@ -0,0 +1,209 @@
+use crate::spec::ElementType;
+
+use super::{
+    document_buf::{append_cstring, append_value},
+    Error,
+    ErrorKind,
+    RawBson,
+    RawBsonRef,
+    RawDocumentBuf,
+    Result,
+};
+
+/// A streaming builder for [`RawDocumentBuf`] that writes nested documents and arrays directly
+/// into a single buffer.
+///
+/// Opening a nested document or array with [`RawDocumentBuilder::start_document`] or
+/// [`RawDocumentBuilder::start_array`] writes a length placeholder and returns a scope guard that
+/// values can be appended to. Calling `end` on the guard closes the scope and backfills its
+/// length, so no intermediate buffers are allocated for nested values.
+///
+/// ```
+/// # use bson::raw::Error;
+/// use bson::{rawdoc, raw::RawDocumentBuilder};
+///
+/// let mut builder = RawDocumentBuilder::new();
+/// builder.append("name", "Jane");
+///
+/// let mut addr = builder.start_document("addr");
+/// addr.append("city", "NYC");
+/// let mut zips = addr.start_array("zips");
+/// zips.push(10001);
+/// zips.push(10002);
+/// zips.end();
+/// addr.end();
+///
+/// let doc = builder.into_buf()?;
+/// assert_eq!(
+///     doc,
+///     rawdoc! { "name": "Jane", "addr": { "city": "NYC", "zips": [10001, 10002] } },
+/// );
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// A scope guard that is dropped without calling `end` leaves its scope open, which causes
+/// [`RawDocumentBuilder::into_buf`] to return an error.
+#[derive(Debug)]
+pub struct RawDocumentBuilder {
+    data: Vec<u8>,
+    /// Start offsets of the currently open nested scopes, innermost last.
+    scopes: Vec<usize>,
+}
+
+impl RawDocumentBuilder {
+    /// Creates a new builder for an empty document.
+    pub fn new() -> Self {
+        Self {
+            // length placeholder for the top-level document
+            data: vec![0; 4],
+            scopes: Vec::new(),
+        }
+    }
+
+    /// Appends a key value pair to the innermost open document.
+    ///
+    /// As with [`RawDocumentBuf::append`], no check is made for duplicate keys.
+    pub fn append(&mut self, key: impl AsRef<str>, value: impl Into<RawBson>) {
+        let value = value.into();
+        self.append_ref(key.as_ref(), value.as_raw_bson_ref());
+    }
+
+    /// Starts a nested document under `key`. Values appended to the returned scope are written
+    /// into the nested document until [`RawDocumentScope::end`] is called.
+    pub fn start_document(&mut self, key: impl AsRef<str>) -> RawDocumentScope<'_> {
+        let start = self.open(key.as_ref(), ElementType::EmbeddedDocument);
+        RawDocumentScope {
+            builder: self,
+            start,
+        }
+    }
+
+    /// Starts a nested array under `key`. Values pushed to the returned scope are written into
+    /// the nested array until [`RawArrayScope::end`] is called.
+    pub fn start_array(&mut self, key: impl AsRef<str>) -> RawArrayScope<'_> {
+        let start = self.open(key.as_ref(), ElementType::Array);
+        RawArrayScope {
+            builder: self,
+            start,
+            len: 0,
+        }
+    }
+
+    /// Finishes the document, returning an error if any nested scope was not closed.
+    pub fn into_buf(mut self) -> Result<RawDocumentBuf> {
+        if !self.scopes.is_empty() {
+            return Err(Error::new_without_key(ErrorKind::new_malformed(format!(
+                "{} nested scope(s) were not closed",
+                self.scopes.len()
+            ))));
+        }
+        Self::finish(&mut self.data, 0);
+        RawDocumentBuf::from_bytes(self.data)
+    }
+
+    fn append_ref(&mut self, key: &str, value: RawBsonRef<'_>) {
+        self.data.push(value.element_type() as u8);
+        append_cstring(&mut self.data, key);
+        append_value(&mut self.data, value);
+    }
+
+    fn open(&mut self, key: &str, element_type: ElementType) -> usize {
+        self.data.push(element_type as u8);
+        append_cstring(&mut self.data, key);
+        let start = self.data.len();
+        self.data.extend([0; 4]);
+        self.scopes.push(start);
+        start
+    }
+
+    fn close(&mut self, start: usize) {
+        // Only close the innermost scope; if a nested guard was dropped without being ended, the
+        // mismatch is left in place to be reported by `into_buf`.
+        if self.scopes.last() == Some(&start) {
+            self.scopes.pop();
+            Self::finish(&mut self.data, start);
+        }
+    }
+
+    fn finish(data: &mut Vec<u8>, start: usize) {
+        data.push(0);
+        let len = ((data.len() - start) as i32).to_le_bytes();
+        data[start..start + 4].copy_from_slice(&len);
+    }
+}
+
+impl Default for RawDocumentBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A nested document opened by [`RawDocumentBuilder::start_document`] or one of the scope types.
+#[derive(Debug)]
+#[must_use = "the scope must be closed with `end`"]
+pub struct RawDocumentScope<'a> {
+    builder: &'a mut RawDocumentBuilder,
+    start: usize,
+}
+
+impl RawDocumentScope<'_> {
+    /// Appends a key value pair to this document.
+    pub fn append(&mut self, key: impl AsRef<str>, value: impl Into<RawBson>) {
+        self.builder.append(key, value);
+    }
+
+    /// Starts a document nested within this one under `key`.
+    pub fn start_document(&mut self, key: impl AsRef<str>) -> RawDocumentScope<'_> {
+        self.builder.start_document(key)
+    }
+
+    /// Starts an array nested within this document under `key`.
+    pub fn start_array(&mut self, key: impl AsRef<str>) -> RawArrayScope<'_> {
+        self.builder.start_array(key)
+    }
+
+    /// Closes this document, backfilling its length.
+    pub fn end(self) {
+        self.builder.close(self.start);
+    }
+}
+
+/// A nested array opened by [`RawDocumentBuilder::start_array`] or one of the scope types.
+#[derive(Debug)]
+#[must_use = "the scope must be closed with `end`"]
+pub struct RawArrayScope<'a> {
+    builder: &'a mut RawDocumentBuilder,
+    start: usize,
+    len: usize,
+}
+
+impl RawArrayScope<'_> {
+    /// Appends a value to the end of this array.
+    pub fn push(&mut self, value: impl Into<RawBson>) {
+        let key = self.next_key();
+        self.builder.append(key, value);
+    }
+
+    /// Starts a document as the next element of this array.
+    pub fn start_document(&mut self) -> RawDocumentScope<'_> {
+        let key = self.next_key();
+        self.builder.start_document(key)
+    }
+
+    /// Starts an array as the next element of this array.
+    pub fn start_array(&mut self) -> RawArrayScope<'_> {
+        let key = self.next_key();
+        self.builder.start_array(key)
+    }
+
+    /// Closes this array, backfilling its length.
+    pub fn end(self) {
+        self.builder.close(self.start);
+    }
+
+    fn next_key(&mut self) -> String {
+        let key = self.len.to_string();
+        self.len += 1;
+        key
+    }
+}
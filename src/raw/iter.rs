@@ -1,7 +1,13 @@
-use std::convert::TryInto;
+use std::{convert::TryInto, ops::Range};
 
 use crate::{
-    de::{read_bool, MIN_BSON_DOCUMENT_SIZE, MIN_CODE_WITH_SCOPE_SIZE},
+    de::{
+        code_with_scope_contents_length,
+        code_with_scope_length_mismatch,
+        read_bool,
+        MIN_BSON_DOCUMENT_SIZE,
+        MIN_CODE_WITH_SCOPE_SIZE,
+    },
     oid::ObjectId,
     raw::{Error, ErrorKind, Result},
     spec::{BinarySubtype, ElementType},
@@ -77,6 +83,14 @@ impl<'a> RawIter<'a> {
         }
     }
 
+    /// Returns the byte offset within the document of the next element to be read.
+    ///
+    /// Before iteration starts this is 4 (just past the length prefix); once all elements have
+    /// been read it is the offset of the document's null terminator.
+    pub fn byte_offset(&self) -> usize {
+        self.offset
+    }
+
     fn verify_enough_bytes(&self, start: usize, num_bytes: usize) -> Result<()> {
         let end = checked_add(start, num_bytes)?;
         if self.doc.as_bytes().get(start..end).is_none() {
@@ -154,10 +168,45 @@ impl<'a> RawElement<'a> {
         self.key
     }
 
+    /// The bytes of this element's key, without the trailing null byte.
+    ///
+    /// Keys are always validated as UTF-8 while iterating, before the element is returned, so
+    /// this is the same data as [`RawElement::key`] viewed as bytes. It is a convenience for
+    /// matching keys against byte string literals and never allocates.
+    ///
+    /// ```
+    /// use bson::rawdoc;
+    ///
+    /// let doc = rawdoc! { "a": 1, "target": 2 };
+    /// let found = doc
+    ///     .iter_elements()
+    ///     .filter_map(Result::ok)
+    ///     .find(|element| element.key_bytes() == b"target");
+    /// assert_eq!(found.unwrap().value()?.as_i32(), Some(2));
+    /// # Ok::<(), bson::raw::Error>(())
+    /// ```
+    pub fn key_bytes(&self) -> &'a [u8] {
+        self.key.as_bytes()
+    }
+
     pub fn element_type(&self) -> ElementType {
         self.kind
     }
 
+    /// The complete encoded bytes of this element (type byte, key and value), borrowed from the
+    /// underlying document. These can be copied into another document with
+    /// [`RawDocumentBuf::append_element_bytes`](crate::RawDocumentBuf::append_element_bytes).
+    pub fn element_bytes(&self) -> &'a [u8] {
+        // type byte + key + null terminator precede the value
+        let start = self.start_at - self.key.len() - 2;
+        &self.doc.as_bytes()[start..self.start_at + self.size]
+    }
+
+    /// The byte range of this element's value within the underlying document.
+    pub(crate) fn value_range(&self) -> Range<usize> {
+        self.start_at..(self.start_at + self.size)
+    }
+
     pub fn value(&self) -> Result<RawBsonRef<'a>> {
         Ok(match self.kind {
             ElementType::Null => RawBsonRef::Null,
@@ -247,6 +296,13 @@ impl<'a> RawElement<'a> {
                 }
 
                 let slice = self.slice();
+                let contents_length = code_with_scope_contents_length(&slice[4..]);
+                if contents_length != Some(self.size as i64) {
+                    return Err(self.malformed_error(code_with_scope_length_mismatch(
+                        self.size as i32,
+                        contents_length,
+                    )));
+                }
                 let code = read_lenencode(&slice[4..])?;
                 let scope_start = 4 + 4 + code.len() + 1;
                 let scope = RawDocument::from_bytes(&slice[scope_start..])?;
@@ -116,20 +116,23 @@ mod array;
 mod array_buf;
 mod bson;
 mod bson_ref;
+mod cow_document;
 mod document;
 mod document_buf;
+mod document_builder;
 mod error;
 mod iter;
 pub(crate) mod serde;
 #[cfg(test)]
 mod test;
+mod visit;
 
 use std::convert::{TryFrom, TryInto};
 
 use crate::de::MIN_BSON_STRING_SIZE;
 
 pub use self::{
-    array::{RawArray, RawArrayIter},
+    array::{RawArray, RawArrayIndexedIter, RawArrayIter},
     array_buf::RawArrayBuf,
     bson::{RawBson, RawJavaScriptCodeWithScope},
     bson_ref::{
@@ -139,10 +142,13 @@ pub use self::{
         RawJavaScriptCodeWithScopeRef,
         RawRegexRef,
     },
+    cow_document::CowDocument,
     document::RawDocument,
     document_buf::RawDocumentBuf,
+    document_builder::{RawArrayScope, RawDocumentBuilder, RawDocumentScope},
     error::{Error, ErrorKind, Result, ValueAccessError, ValueAccessErrorKind, ValueAccessResult},
     iter::{RawElement, RawIter},
+    visit::{RawVisitor, VisitControl},
 };
 
 /// Special newtype name indicating that the type being (de)serialized is a raw BSON document.
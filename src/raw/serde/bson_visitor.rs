@@ -9,13 +9,13 @@ use crate::{
         BorrowedBinaryBody,
         BorrowedDbPointerBody,
         BorrowedRegexBody,
+        DateTimeBody,
         TimestampBody,
     },
     oid::ObjectId,
     raw::{RAW_ARRAY_NEWTYPE, RAW_DOCUMENT_NEWTYPE},
     spec::BinarySubtype,
     Binary,
-    DateTime,
     DbPointer,
     Decimal128,
     RawArray,
@@ -273,8 +273,9 @@ impl<'de> Visitor<'de> for OwnedOrBorrowedRawBsonVisitor {
                 }
             }
             "$date" => {
-                let date: i64 = map.next_value()?;
-                Ok(RawBsonRef::DateTime(DateTime::from_millis(date)).into())
+                let date: DateTimeBody = map.next_value()?;
+                let date = date.parse().map_err(SerdeError::custom)?;
+                Ok(RawBsonRef::DateTime(date).into())
             }
             "$timestamp" => {
                 let timestamp: TimestampBody = map.next_value()?;
@@ -12,6 +12,7 @@ use crate::{
         BorrowedBinaryBody,
         BorrowedDbPointerBody,
         BorrowedRegexBody,
+        DateTimeBody,
         TimestampBody,
     },
     oid::ObjectId,
@@ -326,8 +327,10 @@ impl<'a, 'de> Visitor<'de> for SeededVisitor<'a, 'de> {
                 Ok(ElementType::Binary)
             }
             "$date" => {
-                let date: i64 = map.next_value()?;
-                self.buffer.append_bytes(&date.to_le_bytes());
+                let date: DateTimeBody = map.next_value()?;
+                let date = date.parse().map_err(SerdeError::custom)?;
+                self.buffer
+                    .append_bytes(&date.timestamp_millis().to_le_bytes());
                 Ok(ElementType::DateTime)
             }
             "$timestamp" => {
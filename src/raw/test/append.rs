@@ -2,7 +2,7 @@ use std::iter::FromIterator;
 
 use crate::{
     oid::ObjectId,
-    raw::RawJavaScriptCodeWithScope,
+    raw::{RawDocumentBuilder, RawJavaScriptCodeWithScope},
     spec::BinarySubtype,
     tests::LOCK,
     Binary,
@@ -249,11 +249,11 @@ fn undefined() {
 #[test]
 fn regex() {
     let expected = doc! {
-        "regex": Regex::new("some pattern", "abc"),
+        "regex": Regex::from_strings("some pattern", "abc"),
     };
 
     append_test(expected, |doc| {
-        doc.append("regex", Regex::new("some pattern", "abc"));
+        doc.append("regex", Regex::from_strings("some pattern", "abc"));
     });
 }
 
@@ -435,3 +435,240 @@ fn array_buf() {
 
     assert_eq!(arr_buf.as_ref(), arr.as_array().unwrap());
 }
+
+#[test]
+fn builder_nested_scopes() {
+    let mut builder = RawDocumentBuilder::new();
+    builder.append("a", 1_i32);
+
+    let mut addr = builder.start_document("addr");
+    addr.append("city", "NYC");
+    let mut arr = addr.start_array("arr");
+    arr.push(true);
+    let mut inner = arr.start_document();
+    inner.append("x", 3_i32);
+    inner.end();
+    let inner_arr = arr.start_array();
+    inner_arr.end();
+    arr.end();
+    addr.end();
+
+    builder.append("b", "end");
+
+    let expected = rawdoc! {
+        "a": 1_i32,
+        "addr": {
+            "city": "NYC",
+            "arr": [true, { "x": 3_i32 }, []],
+        },
+        "b": "end",
+    };
+    assert_eq!(builder.into_buf().unwrap(), expected);
+    assert_eq!(
+        RawDocumentBuilder::new().into_buf().unwrap(),
+        RawDocumentBuf::new()
+    );
+}
+
+#[test]
+fn builder_unclosed_scope() {
+    let mut builder = RawDocumentBuilder::new();
+    let mut sub = builder.start_document("sub");
+    sub.append("x", 1_i32);
+    drop(sub);
+    assert!(builder.into_buf().is_err());
+
+    let mut builder = RawDocumentBuilder::new();
+    let mut outer = builder.start_document("outer");
+    drop(outer.start_array("arr"));
+    outer.end();
+    assert!(builder.into_buf().is_err());
+}
+
+#[test]
+fn append_element_bytes() {
+    let _guard = LOCK.run_concurrently();
+
+    let source = rawdoc! {
+        "null": null,
+        "int": 1,
+        "str": "hello",
+        "doc": { "a": [1, "two", { "three": 3.0 }] },
+        "bin": Binary { subtype: BinarySubtype::Generic, bytes: vec![1, 2, 3] },
+        "max": RawBson::MaxKey,
+    };
+
+    let mut copy = RawDocumentBuf::new();
+    let mut reversed = RawDocumentBuf::new();
+    let elements: Vec<_> = source.iter_elements().map(Result::unwrap).collect();
+    for element in &elements {
+        unsafe { copy.append_element_bytes(element.element_bytes()) };
+    }
+    for element in elements.iter().rev() {
+        unsafe { reversed.append_element_bytes(element.element_bytes()) };
+    }
+    assert_eq!(copy, source);
+
+    let expected: Document = source
+        .to_document()
+        .unwrap()
+        .into_iter()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    assert_eq!(reversed.to_document().unwrap(), expected);
+    assert_eq!(reversed.as_bytes().len(), source.as_bytes().len());
+}
+
+#[test]
+fn modify_raw_bson_in_place() {
+    let _guard = LOCK.run_concurrently();
+
+    let mut inner = RawBson::Document(rawdoc! { "a": 1 });
+    inner.as_document_mut().unwrap().append("b", "two");
+    let mut array = RawBson::Array(RawArrayBuf::new());
+    array.as_array_mut().unwrap().push(inner.clone());
+
+    let mut outer = RawBson::Document(RawDocumentBuf::new());
+    let doc = outer.as_document_mut().unwrap();
+    doc.append("inner", inner);
+    doc.append("array", array);
+
+    let expected = rawdoc! {
+        "inner": { "a": 1, "b": "two" },
+        "array": [{ "a": 1, "b": "two" }],
+    };
+    assert_eq!(outer, RawBson::Document(expected));
+    assert!(RawBson::Int32(1).as_document_mut().is_none());
+}
+
+#[test]
+fn array_buf_len_and_extend() {
+    let _guard = LOCK.run_concurrently();
+
+    let mut array = RawArrayBuf::new();
+    assert_eq!(array.len(), 0);
+    array.push("first");
+    array.extend(0..25);
+    array.extend(["a", "b"]);
+    assert_eq!(array.len(), 28);
+
+    let keys: Vec<_> = array.doc.keys().collect::<crate::raw::Result<_>>().unwrap();
+    let expected: Vec<_> = (0..28).map(|i| i.to_string()).collect();
+    assert_eq!(keys, expected);
+
+    let mut expected = vec![Bson::String("first".into())];
+    expected.extend((0..25).map(Bson::Int32));
+    expected.extend([Bson::String("a".into()), Bson::String("b".into())]);
+    let doc: Document =
+        crate::from_slice(&crate::to_vec(&rawdoc! { "array": array.clone() }).unwrap()).unwrap();
+    assert_eq!(doc, doc! { "array": expected });
+
+    // the length stays in step with `push` after extending
+    array.push(true);
+    assert_eq!(array.len(), 29);
+    assert_eq!(array.get_bool(28).unwrap(), true);
+}
+
+#[test]
+fn reorder() {
+    let _guard = LOCK.run_concurrently();
+
+    let mut doc = rawdoc! {
+        "c": { "nested": [1, 2] },
+        "a": "one",
+        "d": null,
+        "b": 2.5,
+    };
+    doc.reorder(&["b", "missing", "c"]).unwrap();
+    assert_eq!(
+        doc,
+        rawdoc! {
+            "b": 2.5,
+            "c": { "nested": [1, 2] },
+            "a": "one",
+            "d": null,
+        }
+    );
+
+    // a full ordering, listing a key twice
+    doc.reorder(&["a", "b", "c", "d", "a"]).unwrap();
+    assert_eq!(
+        doc,
+        rawdoc! { "a": "one", "b": 2.5, "c": { "nested": [1, 2] }, "d": null }
+    );
+
+    doc.reorder(&[]).unwrap();
+    assert_eq!(
+        doc,
+        rawdoc! { "a": "one", "b": 2.5, "c": { "nested": [1, 2] }, "d": null }
+    );
+
+    // duplicate keys move together, keeping their relative order
+    let mut dup = RawDocumentBuf::new();
+    dup.append("x", 1);
+    dup.append("y", 2);
+    dup.append("x", 3);
+    dup.reorder(&["x"]).unwrap();
+    let mut expected = RawDocumentBuf::new();
+    expected.append("x", 1);
+    expected.append("x", 3);
+    expected.append("y", 2);
+    assert_eq!(dup, expected);
+
+    let mut empty = RawDocumentBuf::new();
+    empty.reorder(&["a"]).unwrap();
+    assert_eq!(empty, RawDocumentBuf::new());
+
+    // malformed documents are left untouched
+    let mut bytes = rawdoc! { "a": 1, "s": "abc" }.into_bytes();
+    let len = bytes.len();
+    bytes[len - 9] += 1;
+    let mut malformed = RawDocumentBuf::from_bytes(bytes.clone()).unwrap();
+    assert!(malformed.reorder(&["s", "a"]).is_err());
+    assert_eq!(malformed.as_bytes(), bytes.as_slice());
+}
+
+#[test]
+fn truncate() {
+    let _guard = LOCK.run_concurrently();
+
+    let mut doc = rawdoc! {
+        "a": "one",
+        "b": { "nested": [1, 2] },
+        "c": 3_i64,
+        "d": null,
+    };
+    doc.truncate(10).unwrap();
+    assert_eq!(
+        doc,
+        rawdoc! { "a": "one", "b": { "nested": [1, 2] }, "c": 3_i64, "d": null }
+    );
+
+    doc.truncate(4).unwrap();
+    assert_eq!(doc.iter().count(), 4);
+
+    doc.truncate(2).unwrap();
+    assert_eq!(doc, rawdoc! { "a": "one", "b": { "nested": [1, 2] } });
+
+    // the truncated document can still be appended to
+    doc.append("e", true);
+    assert_eq!(
+        doc,
+        rawdoc! { "a": "one", "b": { "nested": [1, 2] }, "e": true }
+    );
+
+    doc.truncate(0).unwrap();
+    assert_eq!(doc, RawDocumentBuf::new());
+
+    // only the kept elements need to be well-formed
+    let mut bytes = rawdoc! { "a": 1, "s": "abc" }.into_bytes();
+    let len = bytes.len();
+    bytes[len - 9] = 100;
+    let mut malformed = RawDocumentBuf::from_bytes(bytes.clone()).unwrap();
+    assert!(malformed.truncate(2).is_err());
+    assert_eq!(malformed.as_bytes(), bytes.as_slice());
+    malformed.truncate(1).unwrap();
+    assert_eq!(malformed, rawdoc! { "a": 1 });
+}
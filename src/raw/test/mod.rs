@@ -6,7 +6,7 @@ use crate::{
     doc,
     oid::ObjectId,
     raw::error::ValueAccessErrorKind,
-    spec::BinarySubtype,
+    spec::{BinarySubtype, ElementType},
     Binary,
     Bson,
     DateTime,
@@ -179,6 +179,140 @@ fn array() {
     );
 }
 
+#[test]
+fn array_less_common_getters() {
+    let oid = ObjectId::new();
+    let rawdoc = rawdoc! {
+        "array": [
+            RawBson::Null,
+            RawBson::Symbol("sym".into()),
+            RawBson::JavaScriptCode("1 + 1".into()),
+            RawBson::DbPointer(crate::DbPointer { namespace: "db.coll".into(), id: oid }),
+        ]
+    };
+    let array = rawdoc.get_array("array").unwrap();
+    assert_eq!(array.get_null(0), Ok(()));
+    assert_eq!(array.get_symbol(1), Ok("sym"));
+    assert_eq!(array.get_javascript(2), Ok("1 + 1"));
+    let db_pointer = array.get_db_pointer(3).unwrap();
+    assert_eq!(db_pointer.namespace, "db.coll");
+    assert_eq!(db_pointer.id, oid);
+
+    assert_eq!(
+        array.get_symbol(2).unwrap_err().kind,
+        ValueAccessErrorKind::UnexpectedType {
+            expected: ElementType::Symbol,
+            actual: ElementType::JavaScriptCode,
+        }
+    );
+    assert_eq!(
+        array.get_null(4).unwrap_err().kind,
+        ValueAccessErrorKind::NotPresent
+    );
+}
+
+#[test]
+fn document_less_common_getters() {
+    let oid = ObjectId::new();
+    let decimal = crate::Decimal128::from_bytes([1; 16]);
+    let rawdoc = rawdoc! {
+        "null": RawBson::Null,
+        "symbol": RawBson::Symbol("sym".into()),
+        "code": RawBson::JavaScriptCode("1 + 1".into()),
+        "pointer": RawBson::DbPointer(crate::DbPointer { namespace: "db.coll".into(), id: oid }),
+        "decimal": decimal,
+        "array": [decimal],
+    };
+    assert_eq!(rawdoc.get_null("null"), Ok(()));
+    assert_eq!(rawdoc.get_symbol("symbol"), Ok("sym"));
+    assert_eq!(rawdoc.get_javascript("code"), Ok("1 + 1"));
+    let db_pointer = rawdoc.get_db_pointer("pointer").unwrap();
+    assert_eq!(db_pointer.namespace, "db.coll");
+    assert_eq!(db_pointer.id, oid);
+    assert_eq!(rawdoc.get_decimal128("decimal"), Ok(decimal));
+    let array = rawdoc.get_array("array").unwrap();
+    assert_eq!(array.get_decimal128(0), Ok(decimal));
+
+    assert_eq!(
+        rawdoc.get_symbol("code").unwrap_err().kind,
+        ValueAccessErrorKind::UnexpectedType {
+            expected: ElementType::Symbol,
+            actual: ElementType::JavaScriptCode,
+        }
+    );
+    assert_eq!(
+        rawdoc.get_decimal128("null").unwrap_err().kind,
+        ValueAccessErrorKind::UnexpectedType {
+            expected: ElementType::Decimal128,
+            actual: ElementType::Null,
+        }
+    );
+    assert_eq!(
+        rawdoc.get_null("missing").unwrap_err().kind,
+        ValueAccessErrorKind::NotPresent
+    );
+    assert_eq!(
+        array.get_decimal128(1).unwrap_err().kind,
+        ValueAccessErrorKind::NotPresent
+    );
+}
+
+#[test]
+fn array_iter_indexed() {
+    let rawdoc = rawdoc! { "array": ["a", 1, true] };
+    let array = rawdoc.get_array("array").unwrap();
+    let indexed: Vec<_> = array.iter_indexed().collect::<Result<_>>().unwrap();
+    assert_eq!(
+        indexed,
+        vec![
+            (0, RawBsonRef::String("a")),
+            (1, RawBsonRef::Int32(1)),
+            (2, RawBsonRef::Boolean(true)),
+        ]
+    );
+
+    // An embedded document with non-sequential keys, relabeled as an array.
+    let mut bytes = crate::to_vec(&doc! { "array": { "0": "a", "2": "b", "3": "c" } }).unwrap();
+    assert_eq!(bytes[4], ElementType::EmbeddedDocument as u8);
+    bytes[4] = ElementType::Array as u8;
+    let rawdoc = RawDocument::from_bytes(&bytes).unwrap();
+    let array = rawdoc.get_array("array").unwrap();
+    assert_eq!(array.into_iter().count(), 3);
+
+    let mut iter = array.iter_indexed();
+    assert_eq!(iter.next(), Some(Ok((0, RawBsonRef::String("a")))));
+    let error = iter.next().unwrap().unwrap_err();
+    assert_eq!(error.key(), Some("2"));
+    assert!(matches!(error.kind, ErrorKind::MalformedValue { .. }));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn array_first_last() {
+    let rawdoc = rawdoc! { "array": ["a", 1, true], "single": [5], "empty": [] };
+
+    let array = rawdoc.get_array("array").unwrap();
+    assert_eq!(array.first().unwrap(), Some(RawBsonRef::String("a")));
+    assert_eq!(array.last().unwrap(), Some(RawBsonRef::Boolean(true)));
+
+    let single = rawdoc.get_array("single").unwrap();
+    assert_eq!(single.first().unwrap(), Some(RawBsonRef::Int32(5)));
+    assert_eq!(single.last().unwrap(), Some(RawBsonRef::Int32(5)));
+
+    let empty = rawdoc.get_array("empty").unwrap();
+    assert_eq!(empty.first().unwrap(), None);
+    assert_eq!(empty.last().unwrap(), None);
+
+    // Truncate the final element's value so only scanning to the end fails.
+    let mut bytes = crate::to_vec(&doc! { "array": ["a", "bcd"] }).unwrap();
+    let len = bytes.len();
+    bytes[len - 10] = 100;
+    let rawdoc = RawDocument::from_bytes(&bytes).unwrap();
+    let array = rawdoc.get_array("array").unwrap();
+    assert_eq!(array.first().unwrap(), Some(RawBsonRef::String("a")));
+    assert!(array.last().is_err());
+}
+
 #[test]
 fn binary() {
     let rawdoc = rawdoc! {
@@ -411,6 +545,133 @@ fn document_iteration() {
     assert_eq!(end, "END");
 }
 
+#[test]
+fn element_key_bytes() {
+    let rawdoc = rawdoc! { "ascii": 1, "ünïcode": 2, "": 3 };
+    let keys: Vec<&[u8]> = rawdoc
+        .iter_elements()
+        .map(|element| element.unwrap().key_bytes())
+        .collect();
+    assert_eq!(keys, vec![&b"ascii"[..], "ünïcode".as_bytes(), b""]);
+}
+
+#[test]
+fn iter_byte_offset() {
+    let rawdoc = rawdoc! { "a": 1, "bb": "x" };
+    let mut iter = rawdoc.iter_elements();
+    assert_eq!(iter.byte_offset(), 4);
+    iter.next().unwrap().unwrap();
+    // type byte + "a\0" + i32
+    assert_eq!(iter.byte_offset(), 4 + 1 + 2 + 4);
+    iter.next().unwrap().unwrap();
+    assert!(iter.next().is_none());
+    assert_eq!(iter.byte_offset(), rawdoc.as_bytes().len() - 1);
+}
+
+#[test]
+fn into_document_partial() {
+    let rawdoc = rawdoc! { "a": 1, "b": { "c": [1, 2] }, "d": "x" };
+    let mut seen = Vec::new();
+    let doc = rawdoc
+        .clone()
+        .into_document_partial(|key| {
+            seen.push(key.to_string());
+            key != "b"
+        })
+        .unwrap();
+    assert_eq!(doc, doc! { "a": 1, "d": "x" });
+    assert_eq!(seen, vec!["a", "b", "d"]);
+
+    // a malformed value is only an error if its field is kept
+    let mut bytes = rawdoc! { "s": "abc", "t": 1 }.into_bytes();
+    bytes[11] = 0xFF;
+    let broken = RawDocumentBuf::from_bytes(bytes).unwrap();
+    assert_eq!(
+        broken
+            .clone()
+            .into_document_partial(|key| key == "t")
+            .unwrap(),
+        doc! { "t": 1 }
+    );
+    let error = broken.into_document_partial(|_| true).unwrap_err();
+    assert_eq!(error.key(), Some("s"));
+}
+
+#[test]
+fn is_canonical() {
+    let rawdoc = rawdoc! {
+        "a": 1,
+        "b": [true, { "c": "d" }],
+        "code": RawBson::JavaScriptCodeWithScope(RawJavaScriptCodeWithScope {
+            code: "x".into(),
+            scope: rawdoc! { "x": 1 },
+        }),
+    };
+    assert_eq!(rawdoc.is_canonical(), Ok(true));
+    assert_eq!(rawdoc! {}.is_canonical(), Ok(true));
+
+    // Duplicate keys are parseable but not canonical, including in nested documents.
+    let mut dup = RawDocumentBuf::new();
+    dup.append("a", 1);
+    dup.append("a", 2);
+    assert_eq!(dup.is_canonical(), Ok(false));
+    assert_eq!(rawdoc! { "nested": dup.clone() }.is_canonical(), Ok(false));
+
+    // Array keys must be sequential indexes.
+    let mut bytes = crate::to_vec(&doc! { "array": { "0": "a", "2": "b" } }).unwrap();
+    bytes[4] = ElementType::Array as u8;
+    assert_eq!(
+        RawDocument::from_bytes(&bytes).unwrap().is_canonical(),
+        Ok(false)
+    );
+
+    // An out-of-range boolean passes the shallow check in `from_bytes` but is malformed.
+    let mut bytes = rawdoc! { "nested": { "b": true } }.into_bytes();
+    let len = bytes.len();
+    bytes[len - 3] = 2;
+    let rawdoc = RawDocument::from_bytes(&bytes).unwrap();
+    let error = rawdoc.is_canonical().unwrap_err();
+    assert_eq!(error.key(), Some("b"));
+
+    // An embedded length that overruns its parent is malformed.
+    let mut bytes = rawdoc! { "nested": { "s": "abc" } }.into_bytes();
+    bytes[12] += 1;
+    let rawdoc = RawDocument::from_bytes(&bytes).unwrap();
+    assert!(rawdoc.is_canonical().is_err());
+
+    // Array indexes must not have leading zeros.
+    let mut bytes = crate::to_vec(&doc! { "array": { "0": "a", "01": "b" } }).unwrap();
+    bytes[4] = ElementType::Array as u8;
+    assert_eq!(
+        RawDocument::from_bytes(&bytes).unwrap().is_canonical(),
+        Ok(false)
+    );
+}
+
+#[test]
+fn is_canonical_deeply_nested() {
+    // { "": { "": { ... {} ... } } }, nested far more deeply than the call stack could handle
+    // recursively.
+    const DEPTH: usize = 200_000;
+    let mut bytes = Vec::with_capacity(5 + 7 * DEPTH);
+    for level in 0..DEPTH {
+        let len = 5 + 7 * (DEPTH - level) as i32;
+        bytes.extend(len.to_le_bytes());
+        bytes.extend([ElementType::EmbeddedDocument as u8, 0]);
+    }
+    bytes.extend([5, 0, 0, 0, 0]);
+    bytes.resize(bytes.capacity(), 0);
+
+    let rawdoc = RawDocument::from_bytes(&bytes).unwrap();
+    assert_eq!(rawdoc.is_canonical(), Ok(true));
+
+    // Corrupt the innermost document's contents.
+    let innermost = 6 * DEPTH;
+    bytes[innermost + 4] = 1;
+    let rawdoc = RawDocument::from_bytes(&bytes).unwrap();
+    assert!(rawdoc.is_canonical().is_err());
+}
+
 #[test]
 fn into_bson_conversion() {
     let rawdoc = rawdoc! {
@@ -462,6 +723,42 @@ fn into_bson_conversion() {
     );
 }
 
+#[test]
+fn owned_getters_match_ref() {
+    let rawdoc = rawdoc! {
+        "f64": 2.5,
+        "string": "hello",
+        "document": { "a": 1 },
+        "array": [1, 2],
+        "object_id": ObjectId::from_bytes([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]),
+        "binary": Binary { subtype: BinarySubtype::Generic, bytes: vec![1u8, 2, 3] },
+        "boolean": false,
+        "datetime": DateTime::from_millis(1000),
+        "null": null,
+        "regex": Regex { pattern: "^a".into(), options: "i".into() },
+        "i32": 12,
+        "i64": 12_i64,
+        "timestamp": Timestamp { time: 1, increment: 2 },
+    };
+    for result in rawdoc.iter() {
+        let (key, value) = result.unwrap();
+        let owned = value.to_raw_bson();
+        assert_eq!(owned.as_f64(), value.as_f64(), "{}", key);
+        assert_eq!(owned.as_str(), value.as_str(), "{}", key);
+        assert_eq!(owned.as_document(), value.as_document(), "{}", key);
+        assert_eq!(owned.as_array(), value.as_array(), "{}", key);
+        assert_eq!(owned.as_object_id(), value.as_object_id(), "{}", key);
+        assert_eq!(owned.as_binary(), value.as_binary(), "{}", key);
+        assert_eq!(owned.as_bool(), value.as_bool(), "{}", key);
+        assert_eq!(owned.as_datetime(), value.as_datetime(), "{}", key);
+        assert_eq!(owned.as_null(), value.as_null(), "{}", key);
+        assert_eq!(owned.as_regex(), value.as_regex(), "{}", key);
+        assert_eq!(owned.as_i32(), value.as_i32(), "{}", key);
+        assert_eq!(owned.as_i64(), value.as_i64(), "{}", key);
+        assert_eq!(owned.as_timestamp(), value.as_timestamp(), "{}", key);
+    }
+}
+
 use props::arbitrary_bson;
 use proptest::prelude::*;
 use std::convert::TryInto;
@@ -486,3 +783,414 @@ proptest! {
         prop_assert_eq!(doc, roundtrip);
     }
 }
+
+#[test]
+fn count_at_most() {
+    let rawdoc = rawdoc! { "a": 1, "b": 2 };
+    assert_eq!(rawdoc.count_at_most(0).unwrap(), 1);
+    assert_eq!(rawdoc.count_at_most(1).unwrap(), 2);
+    assert_eq!(rawdoc.count_at_most(2).unwrap(), 2);
+    assert_eq!(rawdoc.count_at_most(usize::MAX).unwrap(), 2);
+    assert_eq!(rawdoc! {}.count_at_most(0).unwrap(), 0);
+
+    // append an element with an invalid type after the valid ones; it is only reached if the
+    // scan goes past them
+    let mut bytes = rawdoc.as_bytes().to_vec();
+    bytes.pop();
+    bytes.extend([0x20, b'c', 0, 0]);
+    let len = bytes.len() as i32;
+    bytes[..4].copy_from_slice(&len.to_le_bytes());
+    let malformed = RawDocument::from_bytes(&bytes).unwrap();
+    assert_eq!(malformed.count_at_most(1).unwrap(), 2);
+    assert!(malformed.count_at_most(2).is_err());
+}
+
+#[test]
+fn keys() {
+    let rawdoc = rawdoc! { "a": 1, "b": { "c": [1, 2] }, "d": "x", "e": null };
+    let keys: Vec<&str> = rawdoc.keys().collect::<Result<_>>().unwrap();
+    assert_eq!(keys, ["a", "b", "d", "e"]);
+    assert_eq!(rawdoc! {}.keys().count(), 0);
+
+    // values are not parsed, so a string holding invalid UTF-8 doesn't stop the keys being read
+    let mut bytes = rawdoc! { "s": "x", "t": 1 }.into_bytes();
+    let pos = bytes.iter().position(|b| *b == b'x').unwrap();
+    bytes[pos] = 0xFF;
+    let doc = RawDocument::from_bytes(&bytes).unwrap();
+    assert!(doc.get("s").is_err());
+    let keys: Vec<&str> = doc.keys().collect::<Result<_>>().unwrap();
+    assert_eq!(keys, ["s", "t"]);
+
+    // a value length running past the end of the document is reported
+    let mut bytes = rawdoc! { "s": "x" }.into_bytes();
+    bytes[7] = 0x7F;
+    let doc = RawDocument::from_bytes(&bytes).unwrap();
+    let mut keys = doc.keys();
+    assert!(keys.next().unwrap().is_err());
+    assert!(keys.next().is_none());
+}
+
+#[test]
+fn to_vec() {
+    let rawdoc = rawdoc! { "a": 1, "b": { "c": "d" } };
+    let bytes = rawdoc.to_vec();
+    assert_eq!(bytes, rawdoc.as_bytes());
+    assert_eq!(RawDocument::from_bytes(&bytes).unwrap(), rawdoc.as_ref());
+    assert_eq!(
+        bytes,
+        crate::to_vec(&doc! { "a": 1, "b": { "c": "d" } }).unwrap()
+    );
+}
+
+#[test]
+fn len_bytes() {
+    let mut rawdoc = RawDocumentBuf::new();
+    assert_eq!(rawdoc.len_bytes(), 5);
+    assert!(rawdoc.is_empty());
+
+    rawdoc.append("key", "value");
+    assert_eq!(rawdoc.len_bytes(), rawdoc.as_bytes().len());
+    assert_eq!(
+        rawdoc.len_bytes(),
+        crate::to_vec(&doc! { "key": "value" }).unwrap().len()
+    );
+    assert!(!rawdoc.is_empty());
+}
+
+#[test]
+fn from_bytes_validated() {
+    let rawdoc = rawdoc! {
+        "a": 1,
+        "b": [true, { "c": "d" }],
+        "code": RawBson::JavaScriptCodeWithScope(RawJavaScriptCodeWithScope {
+            code: "x".into(),
+            scope: rawdoc! { "x": 1 },
+        }),
+    };
+    let validated = RawDocument::from_bytes_validated(rawdoc.as_bytes()).unwrap();
+    assert_eq!(validated, rawdoc.as_ref());
+
+    // Non-canonical documents are still valid, and malformation after the duplicate is caught.
+    let mut dup = RawDocumentBuf::new();
+    dup.append("a", 1);
+    dup.append("a", 2);
+    dup.append("b", true);
+    let mut bytes = dup.into_bytes();
+    assert!(RawDocument::from_bytes_validated(&bytes).is_ok());
+    let len = bytes.len();
+    bytes[len - 2] = 2;
+    let error = RawDocument::from_bytes_validated(&bytes).unwrap_err();
+    assert_eq!(error.key(), Some("b"));
+
+    // Errors in nested values are reported.
+    let mut bytes = rawdoc! { "nested": { "s": "abc" } }.into_bytes();
+    bytes[12] += 1;
+    assert!(RawDocument::from_bytes(&bytes).is_ok());
+    assert!(RawDocument::from_bytes_validated(&bytes).is_err());
+
+    let mut bytes = rawdoc! { "nested": { "b\u{e9}": 1 } }.into_bytes();
+    // the last byte of the key's UTF-8 encoding
+    let index = bytes.len() - 8;
+    assert_eq!(bytes[index], 0xa9);
+    bytes[index] = 0xff;
+    assert!(RawDocument::from_bytes(&bytes).is_ok());
+    assert!(RawDocument::from_bytes_validated(&bytes).is_err());
+
+    // The shallow checks still apply.
+    assert!(RawDocument::from_bytes_validated(b"\x06\0\0\0\0").is_err());
+}
+
+#[test]
+fn from_bytes_validated_deeply_nested() {
+    // { "": { "": { ... {} ... } } }, nested far more deeply than the call stack could handle
+    // recursively but still within the maximum BSON document size.
+    const DEPTH: usize = 1_000_000;
+    let mut bytes = Vec::with_capacity(5 + 7 * DEPTH);
+    for level in 0..DEPTH {
+        let len = 5 + 7 * (DEPTH - level) as i32;
+        bytes.extend(len.to_le_bytes());
+        bytes.extend([ElementType::EmbeddedDocument as u8, 0]);
+    }
+    bytes.extend([5, 0, 0, 0, 0]);
+    bytes.resize(bytes.capacity(), 0);
+
+    assert!(RawDocument::from_bytes_validated(&bytes).is_ok());
+
+    // Corrupt the innermost document's contents.
+    let innermost = 6 * DEPTH;
+    bytes[innermost + 4] = 1;
+    assert!(RawDocument::from_bytes(&bytes).is_ok());
+    assert!(RawDocument::from_bytes_validated(&bytes).is_err());
+}
+
+#[test]
+fn visit() {
+    /// Records the traversal as a flat list of events.
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        skip: &'static str,
+        stop: &'static str,
+    }
+
+    impl<'a> RawVisitor<'a> for Recorder {
+        fn on_field(&mut self, key: &'a str, value: RawBsonRef<'a>) -> VisitControl {
+            self.events
+                .push(format!("{}={:?}", key, value.element_type()));
+            if key == self.stop {
+                VisitControl::Stop
+            } else if key == self.skip {
+                VisitControl::Skip
+            } else {
+                VisitControl::Continue
+            }
+        }
+
+        fn on_array_element(&mut self, index: usize, value: RawBsonRef<'a>) -> VisitControl {
+            self.events
+                .push(format!("[{}]={:?}", index, value.element_type()));
+            VisitControl::Continue
+        }
+
+        fn on_container_end(&mut self) {
+            self.events.push("end".into());
+        }
+    }
+
+    let rawdoc = rawdoc! {
+        "a": 1,
+        "b": [true, { "c": "d" }, []],
+        "e": { "f": null },
+        "g": 2.5,
+    };
+
+    let mut recorder = Recorder::default();
+    rawdoc.visit(&mut recorder).unwrap();
+    assert_eq!(
+        recorder.events,
+        [
+            "a=Int32",
+            "b=Array",
+            "[0]=Boolean",
+            "[1]=EmbeddedDocument",
+            "c=String",
+            "end",
+            "[2]=Array",
+            "end",
+            "end",
+            "e=EmbeddedDocument",
+            "f=Null",
+            "end",
+            "g=Double",
+        ]
+    );
+
+    let mut recorder = Recorder {
+        skip: "b",
+        stop: "f",
+        ..Default::default()
+    };
+    rawdoc.visit(&mut recorder).unwrap();
+    assert_eq!(
+        recorder.events,
+        ["a=Int32", "b=Array", "e=EmbeddedDocument", "f=Null"]
+    );
+
+    // the defaults visit everything
+    struct Nothing;
+    impl<'a> RawVisitor<'a> for Nothing {}
+    rawdoc.visit(&mut Nothing).unwrap();
+
+    // elements before malformed data are still visited
+    let mut bytes = rawdoc! { "a": 1, "nested": { "b": true } }.into_bytes();
+    let len = bytes.len();
+    bytes[len - 3] = 2;
+    let mut recorder = Recorder::default();
+    let error = RawDocument::from_bytes(&bytes)
+        .unwrap()
+        .visit(&mut recorder)
+        .unwrap_err();
+    assert_eq!(error.key(), Some("b"));
+    assert_eq!(recorder.events, ["a=Int32", "nested=EmbeddedDocument"]);
+
+    // deep nesting doesn't overflow the stack
+    let mut deep = rawdoc! { "x": 1 };
+    for _ in 0..10_000 {
+        deep = rawdoc! { "x": deep };
+    }
+    let mut recorder = Recorder::default();
+    deep.visit(&mut recorder).unwrap();
+    assert_eq!(recorder.events.len(), 2 * 10_000 + 1);
+}
+
+#[test]
+fn get_with_span() {
+    let rawdoc = rawdoc! {
+        "int": 1,
+        "doc": { "nested": [1, 2] },
+        "str": "hello",
+        "null": null,
+    };
+
+    // every span covers exactly the value's encoding and can be re-read in isolation
+    let (value, span) = rawdoc.get_with_span("doc").unwrap().unwrap();
+    assert_eq!(
+        value.as_document().unwrap().as_bytes(),
+        &rawdoc.as_bytes()[span.clone()]
+    );
+    assert_eq!(
+        RawDocument::from_bytes(&rawdoc.as_bytes()[span]).unwrap(),
+        rawdoc! { "nested": [1, 2] }.as_ref()
+    );
+
+    let (value, span) = rawdoc.get_with_span("int").unwrap().unwrap();
+    assert_eq!(value, RawBsonRef::Int32(1));
+    assert_eq!(&rawdoc.as_bytes()[span.clone()], &1i32.to_le_bytes());
+    // the value follows the type byte and the null-terminated key
+    assert_eq!(&rawdoc.as_bytes()[span.start - 5..span.start], b"\x10int\0");
+
+    let (value, span) = rawdoc.get_with_span("null").unwrap().unwrap();
+    assert_eq!(value, RawBsonRef::Null);
+    assert!(span.is_empty());
+    assert_eq!(span.end, rawdoc.as_bytes().len() - 1);
+
+    // the spans of consecutive elements don't overlap and appear in document order
+    let spans: Vec<_> = ["int", "doc", "str", "null"]
+        .iter()
+        .map(|key| rawdoc.get_with_span(key).unwrap().unwrap().1)
+        .collect();
+    assert!(spans.windows(2).all(|w| w[0].end < w[1].start));
+
+    assert!(rawdoc.get_with_span("missing").unwrap().is_none());
+}
+
+#[test]
+fn to_extjson() {
+    use crate::extjson::ExtJsonMode;
+
+    let doc = doc! {
+        "double": 1.5,
+        "nan": f64::NAN,
+        "string": "hello",
+        "array": [1, 2_i64, { "nested": DateTime::from_millis(-1) }],
+        "doc": { "bool": true, "null": null },
+        "regex": Regex { pattern: "^a".into(), options: "i".into() },
+        "code": Bson::JavaScriptCode("x".into()),
+        "code_w_scope": crate::JavaScriptCodeWithScope::new("y", doc! { "z": 3 }),
+        "i32": 5,
+        "i64": 6_i64,
+        "ts": Timestamp { time: 1, increment: 2 },
+        "binary": Binary { subtype: BinarySubtype::Generic, bytes: vec![1, 2, 3] },
+        "oid": ObjectId::new(),
+        "date": DateTime::from_millis(1_000),
+        "decimal": "1.50".parse::<crate::Decimal128>().unwrap(),
+        "min": Bson::MinKey,
+        "max": Bson::MaxKey,
+        "undefined": Bson::Undefined,
+    };
+    let rawdoc = RawDocumentBuf::from_document(&doc).unwrap();
+
+    assert_eq!(
+        rawdoc.to_extjson(ExtJsonMode::Relaxed).unwrap(),
+        Bson::Document(doc.clone()).into_relaxed_extjson()
+    );
+    assert_eq!(
+        rawdoc.to_extjson(ExtJsonMode::Canonical).unwrap(),
+        Bson::Document(doc).into_canonical_extjson()
+    );
+
+    let mut bytes = crate::to_vec(&doc! { "ok": 1, "bad": "x" }).unwrap();
+    let len = bytes.len();
+    // Overrun the length of the "bad" string.
+    bytes[len - 7] = 100;
+    let rawdoc = RawDocument::from_bytes(&bytes).unwrap();
+    let error = rawdoc.to_extjson(ExtJsonMode::Relaxed).unwrap_err();
+    assert_eq!(error.key(), Some("bad"));
+}
+
+#[test]
+fn cow_document() {
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Envelope {
+        id: i32,
+        body: CowDocument,
+    }
+
+    // duplicate keys would be collapsed by a round trip through `Document`
+    let mut body = RawDocumentBuf::new();
+    body.append("x", 1);
+    body.append("x", 2);
+    let mut outer = RawDocumentBuf::new();
+    outer.append("id", 7);
+    outer.append("body", body.clone());
+    let bytes = outer.as_bytes();
+
+    let mut envelope: Envelope = crate::from_slice(bytes).unwrap();
+    assert!(!envelope.body.is_modified());
+    assert_eq!(envelope.body.as_raw(), Some(body.as_ref()));
+    assert_eq!(envelope.body.as_document(), None);
+    assert_eq!(crate::to_vec(&envelope).unwrap(), bytes);
+    // converting to a `Document` still collapses them
+    let via_value = crate::to_document(&envelope).unwrap();
+    assert_eq!(via_value, doc! { "id": 7, "body": { "x": 2 } });
+    let parsed: Envelope = crate::from_document(via_value).unwrap();
+    assert_eq!(parsed.body.as_raw(), Some(rawdoc! { "x": 2 }.as_ref()));
+
+    envelope.body.to_mut().unwrap().insert("y", "new");
+    assert!(envelope.body.is_modified());
+    assert_eq!(envelope.body.as_raw(), None);
+    assert_eq!(
+        crate::to_document(&envelope).unwrap(),
+        doc! { "id": 7, "body": { "x": 2, "y": "new" } }
+    );
+    assert_eq!(
+        envelope.body.clone().into_raw_document_buf().unwrap(),
+        rawdoc! { "x": 2, "y": "new" }
+    );
+
+    // equality compares encoded bytes, whether or not the document was modified
+    let unmodified = CowDocument::from_raw(rawdoc! { "x": 2, "y": "new" });
+    assert_eq!(envelope.body, unmodified);
+    assert_eq!(unmodified, envelope.body);
+    assert_ne!(CowDocument::from_raw(body.clone()), envelope.body);
+    let mut converted = unmodified.clone();
+    converted.to_mut().unwrap();
+    assert_eq!(converted, unmodified);
+
+    // so key order matters for every combination of variants
+    let owned_ab = CowDocument::from(doc! { "a": 1, "b": 2 });
+    let owned_ba = CowDocument::from(doc! { "b": 2, "a": 1 });
+    let raw_ab = CowDocument::from_raw(rawdoc! { "a": 1, "b": 2 });
+    let raw_ba = CowDocument::from_raw(rawdoc! { "b": 2, "a": 1 });
+    assert_eq!(owned_ab, owned_ab.clone());
+    assert_ne!(owned_ab, owned_ba);
+    assert_eq!(raw_ab, raw_ab.clone());
+    assert_ne!(raw_ab, raw_ba);
+    assert_eq!(owned_ab, raw_ab);
+    assert_eq!(raw_ab, owned_ab);
+    assert_ne!(owned_ba, raw_ab);
+    assert_ne!(raw_ab, owned_ba);
+
+    // and NaN compares equal to itself regardless of variant
+    let owned_nan = CowDocument::from(doc! { "n": f64::NAN });
+    let raw_nan = CowDocument::from_raw(rawdoc! { "n": f64::NAN });
+    assert_eq!(owned_nan, owned_nan.clone());
+    assert_eq!(raw_nan, raw_nan.clone());
+    assert_eq!(owned_nan, raw_nan);
+
+    let owned = CowDocument::from(doc! { "a": 1 });
+    assert!(owned.is_modified());
+    assert_eq!(owned.into_document().unwrap(), doc! { "a": 1 });
+
+    // invalid bytes are only reported once they need to be parsed
+    let mut bytes = crate::to_vec(&doc! { "s": "abc" }).unwrap();
+    let len = bytes.len();
+    bytes[len - 9] = 100;
+    let raw = RawDocumentBuf::from_bytes(bytes.clone()).unwrap();
+    let mut malformed = CowDocument::from_raw(raw);
+    assert_eq!(crate::to_vec(&malformed).unwrap(), bytes);
+    assert!(malformed.to_mut().is_err());
+    assert!(!malformed.is_modified());
+}
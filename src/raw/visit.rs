@@ -0,0 +1,80 @@
+use super::{error::try_with_key, RawBsonRef, RawDocument, RawIter, Result};
+
+/// Returned from [`RawVisitor`] callbacks to direct the rest of the traversal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisitControl {
+    /// Continue the traversal, descending into the value if it is a document or array.
+    Continue,
+    /// Continue the traversal without descending into the value.
+    Skip,
+    /// End the traversal immediately.
+    Stop,
+}
+
+/// Callbacks for a streaming, depth-first traversal of a [`RawDocument`] via
+/// [`RawDocument::visit`].
+///
+/// Values are passed as [`RawBsonRef`]s borrowing from the document, so a traversal never builds
+/// an owned tree and uses memory proportional only to the nesting depth. Every method has a
+/// default implementation that descends into everything, so implementors only need to override
+/// the callbacks they're interested in.
+pub trait RawVisitor<'a> {
+    /// Called for each field of a document, including embedded documents that were descended
+    /// into. If `value` is a document or array, the returned [`VisitControl`] determines whether
+    /// its contents are visited next.
+    fn on_field(&mut self, key: &'a str, value: RawBsonRef<'a>) -> VisitControl {
+        let _ = (key, value);
+        VisitControl::Continue
+    }
+
+    /// Called for each element of an array that was descended into, with its position in the
+    /// array. If `value` is a document or array, the returned [`VisitControl`] determines whether
+    /// its contents are visited next.
+    fn on_array_element(&mut self, index: usize, value: RawBsonRef<'a>) -> VisitControl {
+        let _ = (index, value);
+        VisitControl::Continue
+    }
+
+    /// Called after the last element of an embedded document or array that was descended into.
+    fn on_container_end(&mut self) {}
+}
+
+pub(super) fn visit<'a, V: RawVisitor<'a> + ?Sized>(
+    doc: &'a RawDocument,
+    visitor: &mut V,
+) -> Result<()> {
+    // The open containers, innermost last, along with the next index for arrays. Keeping these
+    // on the heap means arbitrarily deep nesting can't overflow the call stack.
+    let mut stack: Vec<(RawIter<'a>, Option<usize>)> = vec![(doc.iter_elements(), None)];
+    while let Some((iter, index)) = stack.last_mut() {
+        let elem = match iter.next() {
+            Some(elem) => elem?,
+            None => {
+                stack.pop();
+                if !stack.is_empty() {
+                    visitor.on_container_end();
+                }
+                continue;
+            }
+        };
+        let value = try_with_key(elem.key(), || elem.value())?;
+        let control = match index {
+            Some(index) => {
+                *index += 1;
+                visitor.on_array_element(*index - 1, value)
+            }
+            None => visitor.on_field(elem.key(), value),
+        };
+        match (control, value) {
+            (VisitControl::Stop, _) => return Ok(()),
+            (VisitControl::Continue, RawBsonRef::Document(doc)) => {
+                stack.push((doc.iter_elements(), None))
+            }
+            (VisitControl::Continue, RawBsonRef::Array(array)) => {
+                stack.push((array.doc.iter_elements(), Some(0)))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
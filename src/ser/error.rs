@@ -11,7 +11,13 @@ pub enum Error {
     /// A [`std::io::Error`](https://doc.rust-lang.org/std/io/struct.Error.html) encountered while serializing.
     Io(Arc<io::Error>),
 
-    /// A key could not be serialized to a BSON string.
+    /// A key could not be serialized to a BSON string. Strings, booleans, characters, and numbers
+    /// are accepted as map keys, with non-string keys converted via their `Display`
+    /// implementations; any other key type produces this error.
+    ///
+    /// Map keys are converted the same way by [`to_vec`](crate::to_vec) and
+    /// [`to_document`](crate::to_document), so a key containing a null byte is rejected by both
+    /// with [`Error::InvalidCString`].
     InvalidDocumentKey(Bson),
 
     /// An invalid string was specified.
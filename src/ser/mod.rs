@@ -205,8 +205,39 @@ where
     Ok(serializer.into_vec())
 }
 
+/// Serialize the given `T` as a BSON byte vector, configuring the underlying serializer with the
+/// provided options.
+///
+/// With [`SerializerOptions::sort_keys`] set, the fields of every document are written in sorted
+/// key order, while array elements keep their order:
+/// ```
+/// # use serde::Serialize;
+/// # use bson::{rawdoc, SerializerOptions};
+/// #[derive(Serialize)]
+/// struct MyData {
+///     b: i32,
+///     a: i32,
+/// }
+///
+/// let options = SerializerOptions::builder().sort_keys(true).build();
+/// let bytes = bson::to_vec_with_options(&MyData { b: 2, a: 1 }, options)?;
+/// assert_eq!(bytes, rawdoc! { "a": 1, "b": 2 }.into_bytes());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_vec_with_options<T>(value: &T, options: SerializerOptions) -> Result<Vec<u8>>
+where
+    T: Serialize,
+{
+    let mut serializer = raw::Serializer::new_with_options(&options);
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_vec())
+}
+
 /// Serialize the given `T` as a [`RawDocumentBuf`].
 ///
+/// The value is serialized directly into the buffer that backs the returned document, so no
+/// intermediate [`Document`] is built and the bytes are not parsed again afterwards.
+///
 /// ```rust
 /// use serde::Serialize;
 /// use bson::rawdoc;
@@ -227,5 +258,7 @@ pub fn to_raw_document_buf<T>(value: &T) -> Result<RawDocumentBuf>
 where
     T: Serialize,
 {
-    RawDocumentBuf::from_bytes(to_vec(value)?).map_err(Error::custom)
+    // The raw serializer only produces complete documents at the top level, so its output
+    // doesn't need to be checked again.
+    Ok(RawDocumentBuf::from_bytes_unchecked(to_vec(value)?))
 }
@@ -1,4 +1,9 @@
-use serde::{ser::Impossible, Serialize};
+use std::fmt::Display;
+
+use serde::{
+    ser::{Error as SerdeError, Impossible},
+    Serialize,
+};
 
 use crate::{
     ser::{write_cstring, write_i32, Error, Result},
@@ -17,16 +22,26 @@ pub(crate) struct DocumentSerializer<'a> {
     root_serializer: &'a mut Serializer,
     num_keys_serialized: usize,
     start: usize,
+    is_array: bool,
 }
 
 impl<'a> DocumentSerializer<'a> {
     pub(crate) fn start(rs: &'a mut Serializer) -> crate::ser::Result<Self> {
+        Self::start_inner(rs, false)
+    }
+
+    pub(crate) fn start_array(rs: &'a mut Serializer) -> crate::ser::Result<Self> {
+        Self::start_inner(rs, true)
+    }
+
+    fn start_inner(rs: &'a mut Serializer, is_array: bool) -> crate::ser::Result<Self> {
         let start = rs.bytes.len();
         write_i32(&mut rs.bytes, 0)?;
         Ok(Self {
             root_serializer: rs,
             num_keys_serialized: 0,
             start,
+            is_array,
         })
     }
 
@@ -60,6 +75,10 @@ impl<'a> DocumentSerializer<'a> {
         self.root_serializer.bytes.push(0);
         let length = (self.root_serializer.bytes.len() - self.start) as i32;
         self.root_serializer.replace_i32(self.start, length);
+        // array elements keep their order
+        if self.root_serializer.sort_keys && !self.is_array {
+            self.root_serializer.sort_document_at(self.start)?;
+        }
         Ok(DocumentSerializationResult {
             root_serializer: self.root_serializer,
         })
@@ -177,8 +196,24 @@ impl<'a> serde::ser::SerializeTupleStruct for DocumentSerializer<'a> {
     }
 }
 
+/// Serializes a map key to the string that would be used for it in a BSON document, following
+/// the same rules as [`KeySerializer`].
+pub(crate) fn key_to_string<T: Serialize + ?Sized>(key: &T) -> Result<String> {
+    let mut serializer = Serializer::new();
+    key.serialize(KeySerializer {
+        root_serializer: &mut serializer,
+    })?;
+    let mut bytes = serializer.into_vec();
+    // strip the null terminator
+    bytes.pop();
+    String::from_utf8(bytes).map_err(|e| Error::custom(e.to_string()))
+}
+
 /// Serializer used specifically for serializing document keys.
-/// Only keys that serialize to strings will be accepted.
+///
+/// Keys that serialize to strings are used as is. Booleans, characters, and numeric keys are
+/// converted to strings via their [`Display`] implementations, so a `HashMap<i32, T>` serializes
+/// with keys such as `"1"`. All other keys are rejected with [`Error::InvalidDocumentKey`].
 struct KeySerializer<'a> {
     root_serializer: &'a mut Serializer,
 }
@@ -187,6 +222,10 @@ impl<'a> KeySerializer<'a> {
     fn invalid_key<T: Serialize>(v: T) -> Error {
         Error::InvalidDocumentKey(to_bson(&v).unwrap_or(Bson::Null))
     }
+
+    fn write_key(self, v: impl Display) -> Result<()> {
+        write_cstring(&mut self.root_serializer.bytes, &v.to_string())
+    }
 }
 
 impl<'a> serde::Serializer for KeySerializer<'a> {
@@ -204,62 +243,62 @@ impl<'a> serde::Serializer for KeySerializer<'a> {
 
     #[inline]
     fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_u16(self, v: u16) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_u32(self, v: u32) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
     fn serialize_char(self, v: char) -> Result<Self::Ok> {
-        Err(Self::invalid_key(v))
+        self.write_key(v)
     }
 
     #[inline]
@@ -12,12 +12,13 @@ use self::value_serializer::{ValueSerializer, ValueType};
 
 use super::{write_binary, write_cstring, write_f64, write_i32, write_i64, write_string};
 use crate::{
-    raw::{RAW_ARRAY_NEWTYPE, RAW_DOCUMENT_NEWTYPE},
-    ser::{Error, Result},
+    raw::{RawDocument, RAW_ARRAY_NEWTYPE, RAW_DOCUMENT_NEWTYPE},
+    ser::{Error, Result, SerializerOptions},
     serde_helpers::HUMAN_READABLE_NEWTYPE,
     spec::{BinarySubtype, ElementType},
     uuid::UUID_NEWTYPE_NAME,
 };
+pub(crate) use document_serializer::key_to_string;
 use document_serializer::DocumentSerializer;
 
 /// Serializer used to convert a type `T` into raw BSON bytes.
@@ -33,6 +34,9 @@ pub(crate) struct Serializer {
     hint: SerializerHint,
 
     human_readable: bool,
+
+    /// Whether the fields of each document are written in sorted key order.
+    sort_keys: bool,
 }
 
 /// Various bits of information that the serialized type can provide to the serializer to
@@ -64,6 +68,16 @@ impl Serializer {
             type_index: 0,
             hint: SerializerHint::None,
             human_readable: false,
+            sort_keys: false,
+        }
+    }
+
+    pub(crate) fn new_with_options(options: &SerializerOptions) -> Self {
+        Self {
+            #[allow(deprecated)]
+            human_readable: options.human_readable.unwrap_or(false),
+            sort_keys: options.sort_keys,
+            ..Self::new()
         }
     }
 
@@ -98,6 +112,33 @@ impl Serializer {
         Ok(())
     }
 
+    /// Reorders the elements of the complete document at the end of the buffer, starting at
+    /// `start`, by key. The sort is stable, so duplicate keys keep their relative order.
+    fn sort_document_at(&mut self, start: usize) -> Result<()> {
+        let doc = RawDocument::from_bytes(&self.bytes[start..]).map_err(Error::custom)?;
+        let mut elements = Vec::new();
+        let mut offset = 4;
+        for element in doc.iter_elements() {
+            let element = element.map_err(Error::custom)?;
+            // type byte + key + null terminator + value
+            let len = 1 + element.key().len() + 1 + element.len();
+            elements.push((element.key(), &doc.as_bytes()[offset..offset + len]));
+            offset += len;
+        }
+        elements.sort_by_key(|(key, _)| *key);
+
+        let mut sorted = Vec::with_capacity(doc.as_bytes().len());
+        sorted.extend_from_slice(&doc.as_bytes()[..4]);
+        for (_, bytes) in elements {
+            sorted.extend_from_slice(bytes);
+        }
+        sorted.push(0);
+
+        self.bytes.truncate(start);
+        self.bytes.extend(sorted);
+        Ok(())
+    }
+
     /// Replace an i32 value at the given index with the given value.
     #[inline]
     fn replace_i32(&mut self, at: usize, with: i32) {
@@ -304,7 +345,7 @@ impl<'a> serde::Serializer for &'a mut Serializer {
     #[inline]
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
         self.update_element_type(ElementType::Array)?;
-        DocumentSerializer::start(&mut *self)
+        DocumentSerializer::start_array(&mut *self)
     }
 
     #[inline]
@@ -433,6 +474,9 @@ pub(crate) struct VariantSerializer<'a> {
 
     /// How many elements have been serialized in the inner document / array so far.
     num_elements_serialized: usize,
+
+    /// Whether the inner value is a document, rather than an array.
+    inner_is_document: bool,
 }
 
 impl<'a> VariantSerializer<'a> {
@@ -445,9 +489,11 @@ impl<'a> VariantSerializer<'a> {
         // write placeholder length for document, will be updated at end
         write_i32(&mut rs.bytes, 0)?;
 
-        let inner = match inner_type {
-            VariantInnerType::Struct => ElementType::EmbeddedDocument,
-            VariantInnerType::Tuple => ElementType::Array,
+        let inner_is_document = matches!(inner_type, VariantInnerType::Struct);
+        let inner = if inner_is_document {
+            ElementType::EmbeddedDocument
+        } else {
+            ElementType::Array
         };
         rs.bytes.push(inner as u8);
         write_cstring(&mut rs.bytes, variant)?;
@@ -460,6 +506,7 @@ impl<'a> VariantSerializer<'a> {
             num_elements_serialized: 0,
             doc_start,
             inner_start,
+            inner_is_document,
         })
     }
 
@@ -483,6 +530,9 @@ impl<'a> VariantSerializer<'a> {
         let arr_length = (self.root_serializer.bytes.len() - self.inner_start) as i32;
         self.root_serializer
             .replace_i32(self.inner_start, arr_length);
+        if self.inner_is_document && self.root_serializer.sort_keys {
+            self.root_serializer.sort_document_at(self.inner_start)?;
+        }
 
         // null byte for document
         self.root_serializer.bytes.push(0);
@@ -123,6 +123,17 @@ pub struct SerializerOptions {
     /// The default value is true.
     #[deprecated = "use bson::serde_helpers::HumanReadable"]
     pub human_readable: Option<bool>,
+
+    /// Whether documents should be emitted with their keys in sorted order, recursively. Array
+    /// element order is never changed. This is useful for producing reproducible output, e.g. for
+    /// hashing or caching. The default value is false.
+    ///
+    /// This is honored by [`to_bson_with_options`](crate::to_bson_with_options),
+    /// [`to_document_with_options`](crate::to_document_with_options) and
+    /// [`to_vec_with_options`](crate::to_vec_with_options). When serializing to bytes, raw values
+    /// such as a [`RawDocumentBuf`](crate::RawDocumentBuf) field are copied as-is and are not
+    /// reordered.
+    pub sort_keys: bool,
 }
 
 impl SerializerOptions {
@@ -148,6 +159,12 @@ impl SerializerOptionsBuilder {
         self
     }
 
+    /// Set the value for [`SerializerOptions::sort_keys`].
+    pub fn sort_keys(mut self, value: bool) -> Self {
+        self.options.sort_keys = value;
+        self
+    }
+
     /// Consume this builder and produce a [`SerializerOptions`].
     pub fn build(self) -> SerializerOptions {
         self.options
@@ -549,6 +566,16 @@ impl SerializeTupleVariant for TupleVariantSerializer {
     }
 }
 
+fn finish_document(doc: Document, options: &SerializerOptions) -> Bson {
+    let mut bson = Bson::from_extended_document(doc);
+    if options.sort_keys {
+        if let Bson::Document(ref mut doc) = bson {
+            doc.sort_keys();
+        }
+    }
+    bson
+}
+
 #[doc(hidden)]
 pub struct MapSerializer {
     inner: Document,
@@ -561,10 +588,7 @@ impl SerializeMap for MapSerializer {
     type Error = Error;
 
     fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> crate::ser::Result<()> {
-        self.next_key = match to_bson_with_options(&key, self.options.clone())? {
-            Bson::String(s) => Some(s),
-            other => return Err(Error::InvalidDocumentKey(other)),
-        };
+        self.next_key = Some(super::raw::key_to_string(key)?);
         Ok(())
     }
 
@@ -576,7 +600,7 @@ impl SerializeMap for MapSerializer {
     }
 
     fn end(self) -> crate::ser::Result<Bson> {
-        Ok(Bson::from_extended_document(self.inner))
+        Ok(finish_document(self.inner, &self.options))
     }
 }
 
@@ -601,7 +625,7 @@ impl SerializeStruct for StructSerializer {
     }
 
     fn end(self) -> crate::ser::Result<Bson> {
-        Ok(Bson::from_extended_document(self.inner))
+        Ok(finish_document(self.inner, &self.options))
     }
 }
 
@@ -627,7 +651,7 @@ impl SerializeStructVariant for StructVariantSerializer {
     }
 
     fn end(self) -> crate::ser::Result<Bson> {
-        let var = Bson::from_extended_document(self.inner);
+        let var = finish_document(self.inner, &self.options);
 
         let mut struct_variant = Document::new();
         struct_variant.insert(self.name, var);
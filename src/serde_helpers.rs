@@ -18,6 +18,16 @@ pub use chrono_datetime_as_bson_datetime::{
     serialize as serialize_chrono_datetime_as_bson_datetime,
 };
 #[doc(inline)]
+pub use duration_as_f64_seconds::{
+    deserialize as deserialize_duration_from_f64_seconds,
+    serialize as serialize_duration_as_f64_seconds,
+};
+#[doc(inline)]
+pub use duration_as_i64_millis::{
+    deserialize as deserialize_duration_from_i64_millis,
+    serialize as serialize_duration_as_i64_millis,
+};
+#[doc(inline)]
 pub use hex_string_as_object_id::{
     deserialize as deserialize_hex_string_from_object_id,
     serialize as serialize_hex_string_as_object_id,
@@ -28,10 +38,25 @@ pub use i64_as_bson_datetime::{
     serialize as serialize_i64_as_bson_datetime,
 };
 #[doc(inline)]
+pub use i64_as_hex_string::{
+    deserialize as deserialize_i64_from_hex_string,
+    serialize as serialize_i64_as_hex_string,
+};
+#[doc(inline)]
+pub use json_number_as_decimal128::{
+    deserialize as deserialize_decimal128_from_json_number,
+    serialize as serialize_json_number_as_decimal128,
+};
+#[doc(inline)]
 pub use rfc3339_string_as_bson_datetime::{
     deserialize as deserialize_rfc3339_string_from_bson_datetime,
     serialize as serialize_rfc3339_string_as_bson_datetime,
 };
+#[doc(inline)]
+pub use system_time_as_bson_datetime::{
+    deserialize as deserialize_system_time_from_bson_datetime,
+    serialize as serialize_system_time_as_bson_datetime,
+};
 #[cfg(feature = "time-0_3")]
 #[doc(inline)]
 pub use time_0_3_offsetdatetime_as_bson_datetime::{
@@ -51,7 +76,17 @@ pub use u32_as_timestamp::{
     serialize as serialize_u32_as_timestamp,
 };
 #[doc(inline)]
+pub use u32_pair_as_timestamp::{
+    deserialize as deserialize_u32_pair_from_timestamp,
+    serialize as serialize_u32_pair_as_timestamp,
+};
+#[doc(inline)]
 pub use u64_as_f64::{deserialize as deserialize_u64_from_f64, serialize as serialize_u64_as_f64};
+#[doc(inline)]
+pub use u64_as_hex_string::{
+    deserialize as deserialize_u64_from_hex_string,
+    serialize as serialize_u64_as_hex_string,
+};
 
 #[cfg(feature = "uuid-1")]
 #[doc(inline)]
@@ -220,6 +255,367 @@ pub mod u64_as_f64 {
     }
 }
 
+/// Contains functions to serialize a u64 as a hexadecimal string such as `"0x1f"` and deserialize a
+/// u64 from one. This keeps values like bit flags and masks readable when inspecting the stored
+/// documents.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::serde_helpers::u64_as_hex_string;
+/// #[derive(Serialize, Deserialize)]
+/// struct Permissions {
+///     #[serde(with = "u64_as_hex_string")]
+///     pub mask: u64,
+/// }
+/// ```
+pub mod u64_as_hex_string {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Deserializes a u64 from a hexadecimal string, with or without a leading `0x`. Errors if
+    /// the string contains anything other than hexadecimal digits or the value does not fit in a
+    /// u64.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        super::parse_hex_u64(&s).map_err(de::Error::custom)
+    }
+
+    /// Serializes a u64 as a lowercase hexadecimal string with a leading `0x`.
+    pub fn serialize<S: Serializer>(val: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        format!("{:#x}", val).serialize(serializer)
+    }
+}
+
+/// Contains functions to serialize an i64 as a hexadecimal string such as `"0x1f"` or `"-0x1f"`
+/// and deserialize an i64 from one.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::serde_helpers::i64_as_hex_string;
+/// #[derive(Serialize, Deserialize)]
+/// struct Register {
+///     #[serde(with = "i64_as_hex_string")]
+///     pub value: i64,
+/// }
+/// ```
+pub mod i64_as_hex_string {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use std::convert::TryFrom;
+
+    /// Deserializes an i64 from a hexadecimal string, with an optional leading `-` followed by
+    /// an optional `0x`. Errors if the string contains anything other than hexadecimal digits or
+    /// the value does not fit in an i64.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.as_str()),
+        };
+        let magnitude = super::parse_hex_u64(digits).map_err(de::Error::custom)?;
+        let value = if negative && magnitude == i64::MIN.unsigned_abs() {
+            Ok(i64::MIN)
+        } else {
+            i64::try_from(magnitude).map(|m| if negative { -m } else { m })
+        };
+        value.map_err(|_| de::Error::custom(format!("hex string {:?} does not fit in an i64", s)))
+    }
+
+    /// Serializes an i64 as a lowercase hexadecimal string with a leading `0x`, preceded by `-`
+    /// for negative values.
+    pub fn serialize<S: Serializer>(val: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        let sign = if *val < 0 { "-" } else { "" };
+        format!("{}{:#x}", sign, val.unsigned_abs()).serialize(serializer)
+    }
+}
+
+/// Parses hexadecimal digits with an optional `0x` or `0X` prefix.
+fn parse_hex_u64(s: &str) -> Result<u64, String> {
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    // `from_str_radix` would also accept a leading `+`
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("invalid hex string {:?}", s));
+    }
+    u64::from_str_radix(digits, 16).map_err(|_| format!("hex string {:?} does not fit in a u64", s))
+}
+
+/// Contains functions to serialize an f32 as an f64 (BSON double) and deserialize an f32 from an
+/// f64 (BSON double), erroring if the stored value cannot be represented exactly.
+///
+/// By default, an `f32` field is serialized by widening it to an f64, which is lossless, but
+/// deserializing a BSON double into an `f32` field silently rounds to the nearest f32. Use this
+/// module instead when that precision loss is unacceptable.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::serde_helpers::f32_checked;
+/// #[derive(Serialize, Deserialize)]
+/// struct Measurement {
+///     #[serde(with = "f32_checked")]
+///     pub value: f32,
+/// }
+/// ```
+pub mod f32_checked {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    /// Deserializes an f32 from an f64 (BSON double). Errors if an exact conversion is not
+    /// possible.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<f32, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let f = f64::deserialize(deserializer)?;
+        if f as f32 as f64 == f || f.is_nan() {
+            Ok(f as f32)
+        } else {
+            Err(de::Error::custom(format!(
+                "cannot convert f64 (BSON double) {} to f32 without loss of precision",
+                f
+            )))
+        }
+    }
+
+    /// Serializes an f32 as an f64 (BSON double).
+    pub fn serialize<S: Serializer>(val: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(*val as f64)
+    }
+}
+
+/// Contains functions to serialize an integer as its native BSON numeric type and deserialize
+/// an integer from either a BSON integer or a string containing one, for data sources that
+/// sometimes store numbers as strings. Any primitive integer type can be used.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::{doc, serde_helpers::string_or_int};
+/// #[derive(Serialize, Deserialize)]
+/// struct Item {
+///     #[serde(with = "string_or_int")]
+///     pub quantity: i32,
+/// }
+///
+/// let item: Item = bson::from_document(doc! { "quantity": "42" })?;
+/// assert_eq!(item.quantity, 42);
+/// let item: Item = bson::from_document(doc! { "quantity": 42_i64 })?;
+/// assert_eq!(bson::to_document(&item)?, doc! { "quantity": 42 });
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub mod string_or_int {
+    use serde::{
+        de::{self, Unexpected, Visitor},
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+    use std::{convert::TryFrom, fmt, marker::PhantomData, result::Result, str::FromStr};
+
+    /// Deserializes an integer from a BSON integer or a string that parses as one. Errors if the
+    /// value is out of range for the target type.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i64> + TryFrom<u64> + FromStr,
+    {
+        deserializer.deserialize_any(IntVisitor(PhantomData))
+    }
+
+    /// Serializes an integer as its native BSON numeric type.
+    pub fn serialize<S: Serializer, T: Serialize>(
+        val: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        val.serialize(serializer)
+    }
+
+    struct IntVisitor<T>(PhantomData<fn() -> T>);
+
+    impl<'de, T> Visitor<'de> for IntVisitor<T>
+    where
+        T: TryFrom<i64> + TryFrom<u64> + FromStr,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an integer or a string containing an in-range integer")
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<T, E> {
+            T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<T, E> {
+            T::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+            v.parse()
+                .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+}
+
+/// Contains functions to serialize an f64 as a BSON double and deserialize an f64 from a BSON
+/// double, a BSON integer, or a string that parses as a number, for data sources that sometimes
+/// store numbers as strings. Integers are converted to the nearest f64.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::{doc, serde_helpers::string_or_f64};
+/// #[derive(Serialize, Deserialize)]
+/// struct Reading {
+///     #[serde(with = "string_or_f64")]
+///     pub value: f64,
+/// }
+///
+/// let reading: Reading = bson::from_document(doc! { "value": "1.5" })?;
+/// assert_eq!(reading.value, 1.5);
+/// let reading: Reading = bson::from_document(doc! { "value": 2 })?;
+/// assert_eq!(bson::to_document(&reading)?, doc! { "value": 2.0 });
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub mod string_or_f64 {
+    use serde::{
+        de::{self, Unexpected, Visitor},
+        Deserializer,
+        Serializer,
+    };
+    use std::{fmt, result::Result};
+
+    /// Deserializes an f64 from a BSON double, a BSON integer, or a string that parses as a
+    /// number.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(F64Visitor)
+    }
+
+    /// Serializes an f64 as a BSON double.
+    pub fn serialize<S: Serializer>(val: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(*val)
+    }
+
+    struct F64Visitor;
+
+    impl<'de> Visitor<'de> for F64Visitor {
+        type Value = f64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number or a string containing a number")
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<f64, E> {
+            Ok(v)
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<f64, E> {
+            Ok(v as f64)
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<f64, E> {
+            Ok(v as f64)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<f64, E> {
+            v.parse()
+                .map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+}
+
+/// Contains functions to serialize a [`std::time::Duration`] as an i64 (BSON int64) number of
+/// milliseconds and deserialize a [`std::time::Duration`] from one.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::serde_helpers::duration_as_i64_millis;
+/// #[derive(Serialize, Deserialize)]
+/// struct Job {
+///     #[serde(with = "duration_as_i64_millis")]
+///     pub timeout: std::time::Duration,
+/// }
+/// ```
+pub mod duration_as_i64_millis {
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+    use std::{convert::TryFrom, time::Duration};
+
+    /// Deserializes a [`Duration`] from an i64 number of milliseconds. Errors if the value is
+    /// negative.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let millis = i64::deserialize(deserializer)?;
+        match u64::try_from(millis) {
+            Ok(millis) => Ok(Duration::from_millis(millis)),
+            Err(_) => Err(de::Error::custom(format!(
+                "cannot convert negative milliseconds {} to a duration",
+                millis
+            ))),
+        }
+    }
+
+    /// Serializes a [`Duration`] as an i64 number of milliseconds, truncating any sub-millisecond
+    /// precision. Errors if the number of milliseconds does not fit in an i64.
+    pub fn serialize<S: Serializer>(val: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        match i64::try_from(val.as_millis()) {
+            Ok(millis) => serializer.serialize_i64(millis),
+            Err(_) => Err(ser::Error::custom(format!(
+                "cannot convert duration {:?} to i64 milliseconds",
+                val
+            ))),
+        }
+    }
+}
+
+/// Contains functions to serialize a [`std::time::Duration`] as an f64 (BSON double) number of
+/// seconds and deserialize a [`std::time::Duration`] from one. This keeps sub-millisecond
+/// precision, at the cost of rounding very long durations.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::serde_helpers::duration_as_f64_seconds;
+/// #[derive(Serialize, Deserialize)]
+/// struct Sample {
+///     #[serde(with = "duration_as_f64_seconds")]
+///     pub latency: std::time::Duration,
+/// }
+/// ```
+pub mod duration_as_f64_seconds {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    /// Deserializes a [`Duration`] from an f64 (BSON double) number of seconds. Errors if the
+    /// value is negative, not finite, or too large for a [`Duration`].
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let secs = f64::deserialize(deserializer)?;
+        // `u64::MAX as f64` rounds up to 2^64, which is one past the largest whole second
+        if secs >= 0.0 && secs < u64::MAX as f64 {
+            Ok(Duration::from_secs_f64(secs))
+        } else {
+            Err(de::Error::custom(format!(
+                "cannot convert f64 (BSON double) {} seconds to a duration",
+                secs
+            )))
+        }
+    }
+
+    /// Serializes a [`Duration`] as an f64 (BSON double) number of seconds.
+    pub fn serialize<S: Serializer>(val: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(val.as_secs_f64())
+    }
+}
+
 /// Contains functions to serialize a [`time::OffsetDateTime`] as a [`crate::DateTime`] and
 /// deserialize a [`time::OffsetDateTime`] from a [`crate::DateTime`].
 ///
@@ -307,6 +703,41 @@ pub mod chrono_datetime_as_bson_datetime {
     }
 }
 
+/// Contains functions to serialize a [`std::time::SystemTime`] as a [`crate::DateTime`] and
+/// deserialize a [`std::time::SystemTime`] from a [`crate::DateTime`]. Unlike the chrono and time
+/// helpers, this does not require any feature flags.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::serde_helpers::system_time_as_bson_datetime;
+/// #[derive(Serialize, Deserialize)]
+/// struct Event {
+///     #[serde(with = "system_time_as_bson_datetime")]
+///     pub date: std::time::SystemTime,
+/// }
+/// ```
+pub mod system_time_as_bson_datetime {
+    use crate::DateTime;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::{result::Result, time::SystemTime};
+
+    /// Deserializes a [`SystemTime`] from a [`crate::DateTime`].
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<SystemTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let datetime = DateTime::deserialize(deserializer)?;
+        Ok(datetime.to_system_time())
+    }
+
+    /// Serializes a [`SystemTime`] as a [`crate::DateTime`], truncating it to millisecond
+    /// precision.
+    pub fn serialize<S: Serializer>(val: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let datetime = DateTime::from_system_time(*val);
+        datetime.serialize(serializer)
+    }
+}
+
 /// Contains functions to serialize an RFC 3339 (ISO 8601) formatted string as a [`crate::DateTime`]
 /// and deserialize an RFC 3339 (ISO 8601) formatted string from a [`crate::DateTime`].
 ///
@@ -420,28 +851,44 @@ pub mod hex_string_as_object_id {
 /// Contains functions to `serialize` a `i64` integer as [`DateTime`](crate::DateTime) and
 /// `deserialize` a `i64` integer from [`DateTime`](crate::DateTime).
 ///
-/// ### The i64 should represent seconds `(DateTime::timestamp_millis(..))`.
+/// ### The i64 should represent milliseconds `(DateTime::timestamp_millis(..))`.
+///
+/// To ease migrating from schemas that store epoch milliseconds as plain integers, deserialization
+/// also accepts a BSON int64 or int32 value, which is interpreted as milliseconds since the Unix
+/// epoch. Serialization always produces a BSON datetime.
 ///
 /// ```rust
 /// # use serde::{Serialize, Deserialize};
-/// # use bson::serde_helpers::i64_as_bson_datetime;
+/// # use bson::{doc, serde_helpers::i64_as_bson_datetime};
 /// #[derive(Serialize, Deserialize)]
 /// struct Item {
 ///     #[serde(with = "i64_as_bson_datetime")]
 ///     pub now: i64,
 /// }
+///
+/// let item: Item = bson::from_document(doc! { "now": 1_591_700_287_095_i64 })?;
+/// let doc = bson::to_document(&item)?;
+/// assert_eq!(doc.get_datetime("now")?.timestamp_millis(), 1_591_700_287_095);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
 pub mod i64_as_bson_datetime {
-    use crate::DateTime;
-    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use crate::{Bson, DateTime};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
-    /// Deserializes a i64 integer from a DateTime.
+    /// Deserializes a i64 integer from a DateTime, or from an integer number of milliseconds.
     pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let date: DateTime = DateTime::deserialize(deserializer)?;
-        Ok(date.timestamp_millis())
+        match Bson::deserialize(deserializer)? {
+            Bson::DateTime(date) => Ok(date.timestamp_millis()),
+            Bson::Int64(millis) => Ok(millis),
+            Bson::Int32(millis) => Ok(millis.into()),
+            other => Err(de::Error::invalid_type(
+                other.as_unexpected(),
+                &"a BSON datetime or an integer number of milliseconds",
+            )),
+        }
     }
 
     /// Serializes a i64 integer as a DateTime.
@@ -451,6 +898,97 @@ pub mod i64_as_bson_datetime {
     }
 }
 
+/// Contains functions to serialize a [`crate::Decimal128`] as a BSON decimal128 and deserialize a
+/// [`crate::Decimal128`] from a plain number or numeric string, as well as from a BSON decimal128.
+///
+/// This is useful when bridging from JSON APIs that write precise decimals as bare numbers.
+/// Integers are converted exactly. Floats are converted using the shortest decimal representation
+/// that round-trips to the same `f64`, so `123.456` becomes the decimal `123.456`; an error is
+/// returned if the resulting decimal does not convert back to the original float.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::{serde_helpers::json_number_as_decimal128, Decimal128};
+/// #[derive(Serialize, Deserialize)]
+/// struct Payment {
+///     #[serde(with = "json_number_as_decimal128")]
+///     pub amount: Decimal128,
+/// }
+///
+/// let payment: Payment = serde_json::from_str(r#"{ "amount": 123.456 }"#)?;
+/// assert_eq!(payment.amount.to_string(), "123.456");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub mod json_number_as_decimal128 {
+    use crate::Decimal128;
+    use serde::{
+        de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+    use std::{fmt, result::Result};
+
+    /// Deserializes a [`crate::Decimal128`] from an integer, a float, a numeric string, or a BSON
+    /// decimal128.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Decimal128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(Decimal128Visitor)
+    }
+
+    /// Serializes a [`crate::Decimal128`] as a BSON decimal128.
+    pub fn serialize<S: Serializer>(val: &Decimal128, serializer: S) -> Result<S::Ok, S::Error> {
+        val.serialize(serializer)
+    }
+
+    struct Decimal128Visitor;
+
+    impl Decimal128Visitor {
+        fn parse<E: de::Error>(s: &str) -> Result<Decimal128, E> {
+            s.parse()
+                .map_err(|e| E::custom(format!("cannot convert {} to Decimal128: {}", s, e)))
+        }
+    }
+
+    impl<'de> Visitor<'de> for Decimal128Visitor {
+        type Value = Decimal128;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number, a numeric string, or a BSON decimal128")
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal128, E> {
+            Self::parse(&v.to_string())
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal128, E> {
+            Self::parse(&v.to_string())
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal128, E> {
+            let decimal = Self::parse(&format!("{:e}", v))?;
+            if v.is_finite() && decimal.to_string().parse::<f64>() != Ok(v) {
+                return Err(E::custom(format!(
+                    "cannot convert f64 {} to Decimal128 without loss of precision",
+                    v
+                )));
+            }
+            Ok(decimal)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Decimal128, E> {
+            Self::parse(v)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Decimal128, A::Error> {
+            Decimal128::deserialize(MapAccessDeserializer::new(map))
+        }
+    }
+}
+
 #[allow(unused_macros)]
 macro_rules! as_binary_mod {
     ($feat:meta, $uu:path) => {
@@ -756,6 +1294,37 @@ pub mod u32_as_timestamp {
     }
 }
 
+/// Contains functions to serialize a `(time, increment)` pair of u32s as a bson::Timestamp and
+/// deserialize one from a bson::Timestamp.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use bson::serde_helpers::u32_pair_as_timestamp;
+/// #[derive(Serialize, Deserialize)]
+/// struct OplogEntry {
+///     #[serde(with = "u32_pair_as_timestamp")]
+///     pub ts: (u32, u32),
+/// }
+/// ```
+pub mod u32_pair_as_timestamp {
+    use crate::{Bson, Timestamp};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::result::Result;
+
+    /// Serializes a `(time, increment)` pair as a bson::Timestamp.
+    pub fn serialize<S: Serializer>(val: &(u32, u32), serializer: S) -> Result<S::Ok, S::Error> {
+        Bson::Timestamp(Timestamp::from(*val)).serialize(serializer)
+    }
+
+    /// Deserializes a `(time, increment)` pair from a bson::Timestamp.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<(u32, u32), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Timestamp::deserialize(deserializer).map(Into::into)
+    }
+}
+
 /// Contains functions to serialize a bson::Timestamp as a u32 and deserialize a bson::Timestamp
 /// from a u32. The u32 should represent seconds since the Unix epoch. Serialization will return an
 /// error if the Timestamp has a non-zero increment.
@@ -795,6 +1364,55 @@ pub mod timestamp_as_u32 {
     }
 }
 
+/// Provides functions to serialize a [`Binary`](crate::Binary) and to deserialize one only if its
+/// subtype is `SUBTYPE`, erroring otherwise. Since `#[serde(with = ...)]` takes a path, the
+/// subtype is given as the raw subtype byte, e.g. `4` for [`BinarySubtype::Uuid`] or `0x80` for
+/// the first user-defined subtype.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// use bson::{doc, spec::BinarySubtype, Binary};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Session {
+///     #[serde(with = "bson::serde_helpers::BinarySubtypeExact::<4>")]
+///     pub id: Binary,
+/// }
+///
+/// let bytes = vec![0; 16];
+/// let uuid = Binary { subtype: BinarySubtype::Uuid, bytes: bytes.clone() };
+/// let generic = Binary { subtype: BinarySubtype::Generic, bytes };
+/// assert!(bson::from_document::<Session>(doc! { "id": uuid }).is_ok());
+/// assert!(bson::from_document::<Session>(doc! { "id": generic }).is_err());
+/// ```
+///
+/// [`BinarySubtype::Uuid`]: crate::spec::BinarySubtype::Uuid
+#[derive(Clone, Copy, Debug)]
+pub struct BinarySubtypeExact<const SUBTYPE: u8>;
+
+impl<const SUBTYPE: u8> BinarySubtypeExact<SUBTYPE> {
+    /// Deserializes a [`Binary`](crate::Binary), erroring if its subtype is not `SUBTYPE`.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<crate::Binary, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let binary = crate::Binary::deserialize(deserializer)?;
+        let expected = crate::spec::BinarySubtype::from(SUBTYPE);
+        if binary.subtype != expected {
+            return Err(serde::de::Error::custom(format!(
+                "expected Binary with subtype {:?}, instead got subtype {:?}",
+                expected, binary.subtype
+            )));
+        }
+        Ok(binary)
+    }
+
+    /// Serializes a [`Binary`](crate::Binary) as-is, without checking its subtype.
+    pub fn serialize<S: Serializer>(val: &crate::Binary, serializer: S) -> Result<S::Ok, S::Error> {
+        val.serialize(serializer)
+    }
+}
+
 /// Wrapping a type in `HumanReadable` signals to the BSON serde integration that it and all
 /// recursively contained types should be handled as if
 /// [`SerializerOptions::human_readable`](crate::SerializerOptions::human_readable) and
@@ -106,14 +106,32 @@ pub enum ElementType {
     Int64 = ELEMENT_TYPE_64BIT_INTEGER,
     /// [128-bit decimal floating point](https://github.com/mongodb/specifications/blob/master/source/bson-decimal128/decimal128.rst)
     Decimal128 = ELEMENT_TYPE_128BIT_DECIMAL,
+    /// Max key
     MaxKey = ELEMENT_TYPE_MAXKEY,
+    /// Min key
     MinKey = ELEMENT_TYPE_MINKEY,
 }
 
 impl ElementType {
-    /// Attempt to convert from a `u8`.
+    /// Attempt to convert from a `u8`. This is equivalent to [`ElementType::from_u8`].
     #[inline]
     pub fn from(tag: u8) -> Option<ElementType> {
+        Self::from_u8(tag)
+    }
+
+    /// Returns the element type identified by the given type byte, or [`None`] if the byte does
+    /// not identify a known element type.
+    ///
+    /// ```
+    /// use bson::spec::ElementType;
+    ///
+    /// assert_eq!(ElementType::from_u8(0x02), Some(ElementType::String));
+    /// assert_eq!(ElementType::from_u8(0xFF), Some(ElementType::MinKey));
+    /// assert_eq!(ElementType::from_u8(0x7F), Some(ElementType::MaxKey));
+    /// assert_eq!(ElementType::from_u8(0x20), None);
+    /// ```
+    #[inline]
+    pub const fn from_u8(tag: u8) -> Option<ElementType> {
         use self::ElementType::*;
         Some(match tag {
             ELEMENT_TYPE_FLOATING_POINT => Self::Double,
@@ -140,6 +158,79 @@ impl ElementType {
             _ => return None,
         })
     }
+
+    /// Returns the type byte that identifies this element type in encoded BSON.
+    ///
+    /// ```
+    /// use bson::spec::ElementType;
+    ///
+    /// assert_eq!(ElementType::Int32.as_u8(), 0x10);
+    /// assert_eq!(ElementType::from_u8(ElementType::MaxKey.as_u8()), Some(ElementType::MaxKey));
+    /// ```
+    #[inline]
+    pub const fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the rank of this element type in the [BSON comparison order](https://www.mongodb.com/docs/manual/reference/bson-type-comparison-order/),
+    /// which is the first criterion used by [`Bson::canonical_cmp`](crate::Bson::canonical_cmp).
+    /// Values of types with a lower rank sort before values of types with a higher rank, and
+    /// values of types that share a rank are compared by value.
+    ///
+    /// | Rank | Element types                                 |
+    /// |------|-----------------------------------------------|
+    /// | 0    | `MinKey`                                      |
+    /// | 1    | `Undefined`                                   |
+    /// | 2    | `Null`                                        |
+    /// | 3    | `Double`, `Int32`, `Int64`, `Decimal128`      |
+    /// | 4    | `String`, `Symbol`                            |
+    /// | 5    | `EmbeddedDocument`                            |
+    /// | 6    | `Array`                                       |
+    /// | 7    | `Binary`                                      |
+    /// | 8    | `ObjectId`                                    |
+    /// | 9    | `Boolean`                                     |
+    /// | 10   | `DateTime`                                    |
+    /// | 11   | `Timestamp`                                   |
+    /// | 12   | `RegularExpression`                           |
+    /// | 13   | `DbPointer`                                   |
+    /// | 14   | `JavaScriptCode`                              |
+    /// | 15   | `JavaScriptCodeWithScope`                     |
+    /// | 16   | `MaxKey`                                      |
+    ///
+    /// ```
+    /// use bson::spec::ElementType;
+    ///
+    /// assert!(ElementType::MinKey.canonical_order_rank() < ElementType::Null.canonical_order_rank());
+    /// assert_eq!(
+    ///     ElementType::Int32.canonical_order_rank(),
+    ///     ElementType::Double.canonical_order_rank()
+    /// );
+    /// assert!(ElementType::String.canonical_order_rank() < ElementType::ObjectId.canonical_order_rank());
+    /// ```
+    pub const fn canonical_order_rank(self) -> u8 {
+        match self {
+            ElementType::MinKey => 0,
+            ElementType::Undefined => 1,
+            ElementType::Null => 2,
+            ElementType::Double
+            | ElementType::Int32
+            | ElementType::Int64
+            | ElementType::Decimal128 => 3,
+            ElementType::String | ElementType::Symbol => 4,
+            ElementType::EmbeddedDocument => 5,
+            ElementType::Array => 6,
+            ElementType::Binary => 7,
+            ElementType::ObjectId => 8,
+            ElementType::Boolean => 9,
+            ElementType::DateTime => 10,
+            ElementType::Timestamp => 11,
+            ElementType::RegularExpression => 12,
+            ElementType::DbPointer => 13,
+            ElementType::JavaScriptCode => 14,
+            ElementType::JavaScriptCodeWithScope => 15,
+            ElementType::MaxKey => 16,
+        }
+    }
 }
 
 /// The available binary subtypes, plus a user-defined slot.
@@ -159,6 +250,26 @@ pub enum BinarySubtype {
     Reserved(u8),
 }
 
+impl BinarySubtype {
+    /// Constructs a [`BinarySubtype::UserDefined`] from the given value, returning an error if it
+    /// is not in the user-defined range of `0x80` through `0xFF`. Values below `0x80` are reserved
+    /// by the BSON specification.
+    ///
+    /// ```
+    /// use bson::spec::BinarySubtype;
+    ///
+    /// assert_eq!(BinarySubtype::user_defined(0x81)?, BinarySubtype::UserDefined(0x81));
+    /// assert!(BinarySubtype::user_defined(0x10).is_err());
+    /// # Ok::<(), bson::binary::Error>(())
+    /// ```
+    pub fn user_defined(subtype: u8) -> crate::binary::Result<Self> {
+        if subtype < BINARY_SUBTYPE_USER_DEFINED {
+            return Err(crate::binary::Error::InvalidUserDefinedSubtype { subtype });
+        }
+        Ok(BinarySubtype::UserDefined(subtype))
+    }
+}
+
 impl From<BinarySubtype> for u8 {
     #[inline]
     fn from(t: BinarySubtype) -> u8 {
@@ -1,4 +1,6 @@
-use crate::{spec::BinarySubtype, tests::LOCK};
+use std::convert::TryFrom;
+
+use crate::{doc, spec::BinarySubtype, tests::LOCK, Binary, Bson, Document, RawDocument};
 
 #[test]
 fn from_u8() {
@@ -12,3 +14,67 @@ fn from_u8() {
     assert_eq!(BinarySubtype::from(0x80), BinarySubtype::UserDefined(0x80));
     assert_eq!(BinarySubtype::from(0xFF), BinarySubtype::UserDefined(0xFF));
 }
+
+#[test]
+fn user_defined() {
+    let _guard = LOCK.run_concurrently();
+    assert_eq!(
+        BinarySubtype::user_defined(0x80).unwrap(),
+        BinarySubtype::UserDefined(0x80)
+    );
+    assert_eq!(
+        BinarySubtype::user_defined(0xFF).unwrap(),
+        BinarySubtype::UserDefined(0xFF)
+    );
+    assert!(matches!(
+        BinarySubtype::user_defined(0x7F),
+        Err(crate::binary::Error::InvalidUserDefinedSubtype { subtype: 0x7F })
+    ));
+    assert!(BinarySubtype::user_defined(0x00).is_err());
+}
+
+#[test]
+fn user_defined_roundtrip() {
+    let _guard = LOCK.run_concurrently();
+    for subtype in [0x80, 0x9A, 0xFF] {
+        let binary = Binary {
+            subtype: BinarySubtype::user_defined(subtype).unwrap(),
+            bytes: vec![1, 2, 3],
+        };
+        let doc = doc! { "b": binary.clone() };
+
+        // BSON
+        let bytes = crate::to_vec(&doc).unwrap();
+        assert_eq!(crate::from_slice::<Document>(&bytes).unwrap(), doc);
+        let raw = RawDocument::from_bytes(&bytes).unwrap();
+        assert_eq!(raw.get_binary("b").unwrap().subtype, binary.subtype);
+
+        // extended JSON
+        for json in [
+            Bson::Document(doc.clone()).into_canonical_extjson(),
+            Bson::Document(doc.clone()).into_relaxed_extjson(),
+        ] {
+            assert_eq!(
+                json["b"]["$binary"]["subType"],
+                serde_json::json!(format!("{:x}", subtype))
+            );
+            assert_eq!(
+                Bson::try_from(json.clone()).unwrap(),
+                Bson::Document(doc.clone())
+            );
+            let parsed: Document = serde_json::from_value(json).unwrap();
+            assert_eq!(parsed, doc);
+        }
+
+        // serde round trip through a struct field
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Foo {
+            b: Binary,
+        }
+        let foo = Foo { b: binary.clone() };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(serde_json::from_str::<Foo>(&json).unwrap(), foo);
+        let bytes = crate::to_vec(&foo).unwrap();
+        assert_eq!(crate::from_slice::<Foo>(&bytes).unwrap(), foo);
+    }
+}
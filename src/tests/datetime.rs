@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{convert::TryFrom, time::Duration};
 
 use crate::tests::LOCK;
 
@@ -36,6 +36,31 @@ fn datetime_to_rfc3339() {
     );
 }
 
+#[test]
+fn rfc3339_millisecond_precision() {
+    let _guard = LOCK.run_concurrently();
+
+    let parsed = crate::DateTime::parse_rfc3339_str("2020-06-09T10:58:07.095999999Z").unwrap();
+    assert_eq!(parsed.timestamp_millis(), 1_591_700_287_095);
+    assert_eq!(
+        parsed.try_to_rfc3339_string().unwrap(),
+        "2020-06-09T10:58:07.095Z"
+    );
+
+    let offset = crate::DateTime::parse_rfc3339_str("2020-06-09T05:58:07.1-05:00").unwrap();
+    assert_eq!(offset.timestamp_millis(), 1_591_700_287_100);
+    assert_eq!(
+        offset.try_to_rfc3339_string().unwrap(),
+        "2020-06-09T10:58:07.1Z"
+    );
+
+    let before_epoch = crate::DateTime::from_millis(-1);
+    assert_eq!(
+        before_epoch.try_to_rfc3339_string().unwrap(),
+        "1969-12-31T23:59:59.999Z"
+    );
+}
+
 #[test]
 fn invalid_datetime_to_rfc3339() {
     assert!(crate::DateTime::MAX.try_to_rfc3339_string().is_err());
@@ -59,3 +84,147 @@ fn duration_since() {
     assert!(date1.checked_duration_since(date2).is_none());
     assert_eq!(date1.saturating_duration_since(date2), Duration::ZERO);
 }
+
+#[test]
+fn extjson_date_forms() {
+    use std::convert::TryFrom;
+
+    use serde_json::json;
+
+    use crate::Bson;
+
+    let _guard = LOCK.run_concurrently();
+
+    // (millis, canonical extjson, relaxed extjson, other accepted input forms)
+    let cases = [
+        (
+            -284_643_869_501i64,
+            json!({ "$date": { "$numberLong": "-284643869501" } }),
+            json!({ "$date": { "$numberLong": "-284643869501" } }),
+            vec![
+                json!({ "$date": "1960-12-24T12:15:30.499Z" }),
+                json!({ "$date": -284_643_869_501i64 }),
+            ],
+        ),
+        (
+            253_402_300_800_000,
+            json!({ "$date": { "$numberLong": "253402300800000" } }),
+            json!({ "$date": { "$numberLong": "253402300800000" } }),
+            vec![json!({ "$date": 253_402_300_800_000i64 })],
+        ),
+        (
+            1_356_351_330_501,
+            json!({ "$date": { "$numberLong": "1356351330501" } }),
+            json!({ "$date": "2012-12-24T12:15:30.501Z" }),
+            vec![json!({ "$date": 1_356_351_330_501i64 })],
+        ),
+    ];
+
+    for (millis, canonical, relaxed, others) in cases {
+        let expected = Bson::DateTime(crate::DateTime::from_millis(millis));
+        assert_eq!(expected.clone().into_canonical_extjson(), canonical);
+        assert_eq!(expected.clone().into_relaxed_extjson(), relaxed);
+
+        for input in std::iter::once(canonical)
+            .chain(std::iter::once(relaxed))
+            .chain(others)
+        {
+            assert_eq!(
+                Bson::try_from(input.clone()).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+            assert_eq!(
+                serde_json::from_value::<Bson>(input.clone()).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+            assert_eq!(
+                serde_json::from_value::<crate::DateTime>(input.clone()).unwrap(),
+                crate::DateTime::from_millis(millis),
+                "{}",
+                input
+            );
+        }
+    }
+
+    for invalid in [
+        json!({ "$date": "garbage" }),
+        json!({ "$date": { "$numberLong": "abc" } }),
+        json!({ "$date": { "$numberLong": "99999999999999999999" } }),
+        json!({ "$date": u64::MAX }),
+        json!({ "$date": true }),
+    ] {
+        assert!(Bson::try_from(invalid.clone()).is_err(), "{}", invalid);
+        assert!(
+            serde_json::from_value::<Bson>(invalid.clone()).is_err(),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn checked_from_millis() {
+    let _guard = LOCK.run_concurrently();
+
+    let min = crate::DateTime::parse_rfc3339_str("0000-01-01T00:00:00Z").unwrap();
+    let max = crate::DateTime::parse_rfc3339_str("9999-12-31T23:59:59.999Z").unwrap();
+
+    for dt in [min, max] {
+        let checked = crate::DateTime::checked_from_millis(dt.timestamp_millis()).unwrap();
+        assert_eq!(checked, dt);
+        assert!(checked.try_to_rfc3339_string().is_ok());
+        let relaxed = crate::Bson::DateTime(checked).into_relaxed_extjson();
+        let parsed = crate::Bson::try_from(relaxed).unwrap();
+        assert_eq!(parsed, crate::Bson::DateTime(checked));
+    }
+
+    for millis in [
+        min.timestamp_millis() - 1,
+        max.timestamp_millis() + 1,
+        i64::MIN,
+        i64::MAX,
+    ] {
+        assert!(crate::DateTime::checked_from_millis(millis).is_none());
+        assert!(crate::DateTime::from_millis(millis)
+            .try_to_rfc3339_string()
+            .is_err());
+    }
+}
+
+#[test]
+#[cfg(feature = "time-0_3")]
+fn time_0_3_primitive_conversions() {
+    let _guard = LOCK.run_concurrently();
+
+    let primitive = time::macros::datetime!(2020-06-09 10:58:07.095);
+    let bson_dt = crate::DateTime::from(primitive);
+    assert_eq!(bson_dt, crate::DateTime::from(primitive.assume_utc()));
+    assert_eq!(time::PrimitiveDateTime::from(bson_dt), primitive);
+
+    // out-of-range values are clamped, as with `to_time_0_3`
+    assert_eq!(
+        time::PrimitiveDateTime::from(crate::DateTime::MAX),
+        time::PrimitiveDateTime::MAX
+    );
+}
+
+#[test]
+#[cfg(feature = "chrono-0_4")]
+fn chrono_naive_conversions() {
+    let _guard = LOCK.run_concurrently();
+
+    let naive = chrono::NaiveDate::from_ymd_opt(2020, 6, 9)
+        .unwrap()
+        .and_hms_milli_opt(10, 58, 7, 95)
+        .unwrap();
+    let bson_dt = crate::DateTime::from(naive);
+    assert_eq!(
+        bson_dt,
+        crate::DateTime::parse_rfc3339_str("2020-06-09T10:58:07.095Z").unwrap()
+    );
+    assert_eq!(chrono::NaiveDateTime::from(bson_dt), naive);
+}
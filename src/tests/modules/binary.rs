@@ -1,4 +1,6 @@
-use crate::{spec::BinarySubtype, tests::LOCK, Binary};
+use std::convert::TryFrom;
+
+use crate::{spec::BinarySubtype, tests::LOCK, Binary, Bson, Document};
 
 #[test]
 fn binary_from_base64() {
@@ -19,3 +21,113 @@ fn binary_from_base64() {
     };
     assert_eq!(produced, expected);
 }
+
+#[test]
+fn binary_as_raw_document() {
+    let _guard = LOCK.run_concurrently();
+
+    let embedded = doc! { "x": 1, "y": { "z": "a" } };
+    let binary = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes: crate::to_vec(&embedded).unwrap(),
+    };
+    let raw = binary.as_raw_document().unwrap();
+    assert_eq!(raw.as_bytes().as_ptr(), binary.bytes.as_ptr());
+    assert_eq!(Document::try_from(raw).unwrap(), embedded);
+
+    let bson = Bson::Binary(binary.clone());
+    assert_eq!(bson.as_raw_document(), Some(raw));
+    assert_eq!(Bson::Document(embedded).as_raw_document(), None);
+
+    // Bytes that pass the shallow length check but contain malformed elements are rejected.
+    let mut bytes = binary.bytes.clone();
+    bytes[4] = 0x42;
+    let malformed = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes,
+    };
+    assert!(malformed.as_raw_document().is_err());
+    assert_eq!(Bson::Binary(malformed).as_raw_document(), None);
+
+    let not_a_document = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes: vec![1, 2, 3],
+    };
+    assert!(not_a_document.as_raw_document().is_err());
+}
+
+#[test]
+fn binary_writer() {
+    let _guard = LOCK.run_concurrently();
+
+    use crate::binary::BinaryWriter;
+    use std::io::Write;
+
+    let mut writer = BinaryWriter::with_capacity(8);
+    writer.write_all(&[1, 2, 3]).unwrap();
+    writer.write_all(&[]).unwrap();
+    assert_eq!(writer.write(&[4, 5]).unwrap(), 2);
+    writer.flush().unwrap();
+    assert_eq!(writer.as_bytes(), &[1, 2, 3, 4, 5]);
+
+    let binary = writer.into_binary(BinarySubtype::UserDefined(0x80));
+    assert_eq!(
+        binary,
+        Binary {
+            subtype: BinarySubtype::UserDefined(0x80),
+            bytes: vec![1, 2, 3, 4, 5],
+        }
+    );
+    assert_eq!(
+        BinaryWriter::new()
+            .into_binary(BinarySubtype::Generic)
+            .bytes,
+        Vec::<u8>::new()
+    );
+}
+
+#[test]
+fn binary_as_fixed() {
+    let _guard = LOCK.run_concurrently();
+
+    let binary = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes: (0..32).collect(),
+    };
+    let expected: [u8; 32] = std::array::from_fn(|i| i as u8);
+    assert_eq!(binary.as_fixed::<32>(), Some(expected));
+    assert_eq!(binary.as_fixed::<31>(), None);
+    assert_eq!(binary.as_fixed::<33>(), None);
+
+    let empty = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes: Vec::new(),
+    };
+    assert_eq!(empty.as_fixed::<0>(), Some([]));
+    assert_eq!(empty.as_fixed::<1>(), None);
+}
+
+#[test]
+fn binary_encrypted() {
+    let _guard = LOCK.run_concurrently();
+
+    let binary = Binary::from_encrypted(1, &[0xAA, 0xBB]);
+    assert_eq!(binary.subtype, BinarySubtype::Encrypted);
+    assert_eq!(binary.bytes, vec![1, 0xAA, 0xBB]);
+    assert_eq!(binary.as_encrypted(), Some((1, &[0xAA, 0xBB][..])));
+
+    let no_ciphertext = Binary::from_encrypted(2, &[]);
+    assert_eq!(no_ciphertext.as_encrypted(), Some((2, &[][..])));
+
+    let empty = Binary {
+        subtype: BinarySubtype::Encrypted,
+        bytes: Vec::new(),
+    };
+    assert_eq!(empty.as_encrypted(), None);
+
+    let generic = Binary {
+        subtype: BinarySubtype::Generic,
+        bytes: binary.bytes.clone(),
+    };
+    assert_eq!(generic.as_encrypted(), None);
+}
@@ -1,19 +1,27 @@
 use std::{
+    cmp::Ordering,
+    collections::{hash_map::DefaultHasher, HashMap},
     convert::TryFrom,
+    hash::{Hash, Hasher},
     time::{Duration, SystemTime},
 };
 
 use crate::{
     doc,
     oid::ObjectId,
-    spec::BinarySubtype,
+    spec::{BinarySubtype, ElementType},
     tests::LOCK,
     Binary,
     Bson,
     DateTime,
+    Decimal128,
     Document,
     JavaScriptCodeWithScope,
+    NonFiniteDoubleError,
+    NonFinitePolicy,
     Regex,
+    RegexError,
+    RelaxedExtJsonOptions,
     Timestamp,
 };
 
@@ -172,6 +180,17 @@ fn from_impls() {
         Bson::from(vec![1, 2, 3]),
         Bson::Array(vec![Bson::Int32(1), Bson::Int32(2), Bson::Int32(3)])
     );
+    assert_eq!(
+        Bson::from(vec![Bson::Null, Bson::from("a")]),
+        Bson::Array(vec![Bson::Null, Bson::String("a".into())])
+    );
+    let collected: Bson = [1i64, 2, 3].iter().map(|n| Bson::from(n * 2)).collect();
+    assert_eq!(
+        collected,
+        Bson::Array(vec![Bson::Int64(2), Bson::Int64(4), Bson::Int64(6)])
+    );
+    let collected: Bson = vec!["x", "y"].into_iter().collect();
+    assert_eq!(collected, Bson::from(vec!["x", "y"]));
     assert_eq!(
         Bson::try_from(json!({"_id": {"$oid": oid.to_hex()}, "name": ["bson-rs"]})).unwrap(),
         Bson::Document(doc! {"_id": &oid, "name": ["bson-rs"]})
@@ -234,6 +253,35 @@ fn timestamp_ordering() {
     assert!(ts2 < ts3);
 }
 
+#[test]
+fn timestamp_u64() {
+    let _guard = LOCK.run_concurrently();
+    let ts = Timestamp {
+        time: 0x0102_0304,
+        increment: 0x0506_0708,
+    };
+    assert_eq!(ts.to_u64(), 0x0102_0304_0506_0708);
+    assert_eq!(Timestamp::from_u64(0x0102_0304_0506_0708), ts);
+
+    let max = Timestamp {
+        time: u32::MAX,
+        increment: u32::MAX,
+    };
+    assert_eq!(max.to_u64(), u64::MAX);
+    assert_eq!(Timestamp::from_u64(u64::MAX), max);
+
+    let earlier = Timestamp {
+        time: 1,
+        increment: u32::MAX,
+    };
+    let later = Timestamp {
+        time: 2,
+        increment: 0,
+    };
+    assert!(earlier < later);
+    assert!(earlier.to_u64() < later.to_u64());
+}
+
 #[test]
 fn from_external_datetime() {
     use time::macros::datetime;
@@ -445,6 +493,15 @@ fn system_time() {
         crate::DateTime::from_system_time(SystemTime::UNIX_EPOCH).timestamp_millis(),
         0
     );
+
+    let millis = [i64::MIN, -1, 0, 1234, i64::MAX];
+    let dates: Vec<crate::DateTime> = millis.iter().copied().map(crate::DateTime::from).collect();
+    assert_eq!(dates[3], bt);
+    assert_eq!(dates[0], crate::DateTime::MIN);
+    assert_eq!(dates[4], crate::DateTime::MAX);
+    let round_trip: Vec<i64> = dates.into_iter().map(i64::from).collect();
+    assert_eq!(round_trip, millis);
+    assert_eq!(Bson::from(1234_i64), Bson::Int64(1234));
 }
 
 #[test]
@@ -486,3 +543,698 @@ fn debug_print() {
     assert_eq!(format!("{:?}", doc), normal_print);
     assert_eq!(format!("{:#?}", doc), pretty_print);
 }
+
+#[test]
+fn array_from_iter() {
+    let _guard = LOCK.run_concurrently();
+    let names = vec!["a".to_string(), "b".to_string()];
+    assert_eq!(Bson::array(names), Bson::from(vec!["a", "b"]));
+    assert_eq!(
+        Bson::array(1..=3),
+        Bson::Array(vec![Bson::Int32(1), Bson::Int32(2), Bson::Int32(3)])
+    );
+    assert_eq!(Bson::array(Vec::<i64>::new()), Bson::Array(Vec::new()));
+
+    let mut array = Bson::array_with_capacity(100);
+    assert_eq!(array, Bson::Array(Vec::new()));
+    let values = array.as_array_mut().unwrap();
+    let capacity = values.capacity();
+    assert!(capacity >= 100);
+    values.extend((0..100).map(Bson::Int32));
+    assert_eq!(values.capacity(), capacity);
+    assert_eq!(array, Bson::array(0..100));
+}
+
+#[test]
+fn hash_consistent_with_eq() {
+    let _guard = LOCK.run_concurrently();
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    assert_eq!(Bson::Double(0.0), Bson::Double(-0.0));
+    assert_eq!(hash_of(&Bson::Double(0.0)), hash_of(&Bson::Double(-0.0)));
+    assert_ne!(hash_of(&Bson::Int32(1)), hash_of(&Bson::Int64(1)));
+
+    // document equality ignores key order, so hashing must as well
+    let a = doc! { "x": 1, "y": { "z": [1, 2] } };
+    let b = doc! { "y": { "z": [1, 2] }, "x": 1 };
+    assert_eq!(a, b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+    assert_eq!(
+        hash_of(&Bson::Document(a.clone())),
+        hash_of(&Bson::Document(b))
+    );
+
+    let mut cache = HashMap::new();
+    cache.insert(a, "cached");
+    assert_eq!(
+        cache.get(&doc! { "y": { "z": [1, 2] }, "x": 1 }),
+        Some(&"cached")
+    );
+    assert_eq!(cache.get(&doc! { "x": 1 }), None);
+}
+
+#[test]
+fn regex_new_validates_options() {
+    let _guard = LOCK.run_concurrently();
+
+    let regex = Regex::new("^a.*b$", "ilmsux").unwrap();
+    assert_eq!(regex.pattern, "^a.*b$");
+    assert_eq!(regex.options, "ilmsux");
+    assert_eq!(Regex::new("", "").unwrap().options, "");
+
+    assert_eq!(
+        Regex::new("a", "xi").unwrap_err(),
+        RegexError::UnsortedOptions {
+            options: "xi".to_string()
+        }
+    );
+    assert_eq!(
+        Regex::new("a", "iim").unwrap_err(),
+        RegexError::DuplicateOption { option: 'i' }
+    );
+    assert_eq!(
+        Regex::new("a", "ig").unwrap_err(),
+        RegexError::InvalidOption { option: 'g' }
+    );
+    assert_eq!(
+        Regex::new("a\0b", "").unwrap_err(),
+        RegexError::InteriorNull
+    );
+}
+
+#[test]
+fn numeric_cmp_across_types() {
+    let _guard = LOCK.run_concurrently();
+
+    let dec = |s: &str| Bson::Decimal128(s.parse().unwrap());
+
+    let ones = [
+        Bson::Int32(1),
+        Bson::Int64(1),
+        Bson::Double(1.0),
+        dec("1"),
+        dec("1.000"),
+        dec("0.1E+1"),
+    ];
+    for a in &ones {
+        for b in &ones {
+            assert!(a.numeric_eq(b), "{:?} != {:?}", a, b);
+        }
+    }
+
+    // zeroes of either sign are equal
+    assert!(Bson::Double(-0.0).numeric_eq(&Bson::Int32(0)));
+    assert!(dec("-0").numeric_eq(&dec("0E+10")));
+
+    // the Int64 / Double precision boundary is compared exactly
+    let big = Bson::Int64(i64::MAX);
+    assert_eq!(
+        big.numeric_cmp(&Bson::Double(i64::MAX as f64)),
+        Some(Ordering::Less)
+    );
+    assert!(Bson::Int64(1 << 53).numeric_eq(&Bson::Double(9007199254740992.0)));
+    assert_eq!(
+        Bson::Int64((1 << 53) + 1).numeric_cmp(&Bson::Double(9007199254740992.0)),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(
+        Bson::Int64(i64::MIN).numeric_cmp(&Bson::Double(-9.3e18)),
+        Some(Ordering::Greater)
+    );
+
+    // doubles are binary fractions, so they are only equal to decimals with the same exact value
+    assert!(!Bson::Double(0.1).numeric_eq(&dec("0.1")));
+    assert_eq!(
+        Bson::Double(0.1).numeric_cmp(&dec("0.1")),
+        Some(Ordering::Greater)
+    );
+    assert!(Bson::Double(0.5).numeric_eq(&dec("0.50")));
+    assert!(
+        Bson::Double(f64::MIN_POSITIVE / 4.0).numeric_cmp(&dec("0")) == Some(Ordering::Greater)
+    );
+    assert_eq!(
+        dec("-1E+6111").numeric_cmp(&Bson::Double(f64::MIN)),
+        Some(Ordering::Less)
+    );
+    assert_eq!(
+        dec("1E-6176").numeric_cmp(&Bson::Double(f64::from_bits(1))),
+        Some(Ordering::Less)
+    );
+
+    // ordering of negatives
+    assert_eq!(
+        Bson::Int32(-2).numeric_cmp(&dec("-1.5")),
+        Some(Ordering::Less)
+    );
+    assert_eq!(
+        Bson::Double(-1.25).numeric_cmp(&Bson::Int64(-2)),
+        Some(Ordering::Greater)
+    );
+
+    // infinities
+    assert!(Bson::Double(f64::INFINITY).numeric_eq(&dec("Infinity")));
+    assert_eq!(
+        dec("-Infinity").numeric_cmp(&Bson::Int64(i64::MIN)),
+        Some(Ordering::Less)
+    );
+    assert_eq!(
+        Bson::Double(f64::INFINITY).numeric_cmp(&dec("9.999999999999999999999999999999999E+6144")),
+        Some(Ordering::Greater)
+    );
+
+    // NaN and non-numeric values are incomparable
+    assert_eq!(
+        Bson::Double(f64::NAN).numeric_cmp(&Bson::Double(f64::NAN)),
+        None
+    );
+    assert!(!dec("NaN").numeric_eq(&dec("NaN")));
+    assert!(!Bson::Int32(1).numeric_eq(&Bson::String("1".into())));
+    assert!(!Bson::Boolean(true).numeric_eq(&Bson::Int32(1)));
+
+    // derived equality stays type-strict
+    assert_ne!(Bson::Int32(1), Bson::Int64(1));
+}
+
+#[test]
+fn canonical_cmp() {
+    let _guard = LOCK.run_concurrently();
+
+    let others = [
+        Bson::Undefined,
+        Bson::Null,
+        Bson::Double(f64::NAN),
+        Bson::Double(f64::NEG_INFINITY),
+        Bson::Int32(i32::MIN),
+        Bson::Int64(i64::MAX),
+        Bson::String(String::new()),
+        Bson::String("zzz".into()),
+        Bson::Document(doc! {}),
+        Bson::Array(vec![]),
+        Bson::Binary(Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: vec![],
+        }),
+        Bson::ObjectId(ObjectId::new()),
+        Bson::Boolean(false),
+        Bson::DateTime(DateTime::MIN),
+        Bson::Timestamp(Timestamp {
+            time: 0,
+            increment: 0,
+        }),
+        Bson::RegularExpression(Regex::new("", "").unwrap()),
+        Bson::JavaScriptCode(String::new()),
+        Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope::new("", doc! {})),
+    ];
+    for value in &others {
+        assert_eq!(
+            Bson::MinKey.canonical_cmp(value),
+            Ordering::Less,
+            "{:?}",
+            value
+        );
+        assert_eq!(value.canonical_cmp(&Bson::MinKey), Ordering::Greater);
+        assert_eq!(
+            Bson::MaxKey.canonical_cmp(value),
+            Ordering::Greater,
+            "{:?}",
+            value
+        );
+        assert_eq!(value.canonical_cmp(&Bson::MaxKey), Ordering::Less);
+    }
+    assert_eq!(Bson::MinKey.canonical_cmp(&Bson::MinKey), Ordering::Equal);
+    assert_eq!(Bson::MaxKey.canonical_cmp(&Bson::MaxKey), Ordering::Equal);
+    assert_eq!(Bson::MinKey.canonical_cmp(&Bson::MaxKey), Ordering::Less);
+
+    // The type ranks are consistent with the value ordering.
+    for pair in others.windows(2) {
+        assert!(
+            pair[0].element_type().canonical_order_rank()
+                <= pair[1].element_type().canonical_order_rank(),
+            "{:?}",
+            pair
+        );
+    }
+    assert_eq!(ElementType::MinKey.canonical_order_rank(), 0);
+    assert_eq!(ElementType::MaxKey.canonical_order_rank(), 16);
+
+    // Sorting by the canonical order reproduces the list above.
+    let mut shuffled: Vec<_> = others.iter().rev().cloned().collect();
+    shuffled.sort_by(Bson::canonical_cmp);
+    assert_eq!(format!("{:?}", shuffled), format!("{:?}", others));
+
+    // Inclusive range bounds.
+    let in_range = |value: &Bson, lower: &Bson, upper: &Bson| {
+        lower.canonical_cmp(value) != Ordering::Greater
+            && value.canonical_cmp(upper) != Ordering::Greater
+    };
+    let five = Bson::Int32(5);
+    assert!(in_range(&Bson::Int32(i32::MIN), &Bson::MinKey, &five));
+    assert!(in_range(&Bson::Null, &Bson::MinKey, &five));
+    assert!(!in_range(&Bson::Double(5.5), &Bson::MinKey, &five));
+    assert!(in_range(&Bson::String("a".into()), &five, &Bson::MaxKey));
+    assert!(!in_range(&Bson::Int64(4), &five, &Bson::MaxKey));
+
+    // Within a type.
+    assert_eq!(
+        Bson::Int64(2).canonical_cmp(&Bson::Double(1.5)),
+        Ordering::Greater
+    );
+    assert_eq!(
+        Bson::String("a".into()).canonical_cmp(&Bson::Symbol("a".into())),
+        Ordering::Equal
+    );
+    assert_eq!(
+        Bson::Document(doc! { "a": 1 }).canonical_cmp(&Bson::Document(doc! { "a": 1, "b": 0 })),
+        Ordering::Less
+    );
+    assert_eq!(
+        Bson::Document(doc! { "a": "x" }).canonical_cmp(&Bson::Document(doc! { "a": 2 })),
+        Ordering::Greater
+    );
+    assert_eq!(
+        Bson::Array(vec![Bson::Int32(1), Bson::Int32(3)])
+            .canonical_cmp(&Bson::Array(vec![Bson::Double(1.0), Bson::Int32(2)])),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn element_type_u8_round_trip() {
+    let _guard = LOCK.run_concurrently();
+
+    let mut known = 0;
+    for byte in 0..=u8::MAX {
+        if let Some(element_type) = ElementType::from_u8(byte) {
+            known += 1;
+            assert_eq!(element_type.as_u8(), byte);
+            assert_eq!(ElementType::from(byte), Some(element_type));
+        }
+    }
+    // 0x01 through 0x13, plus MinKey and MaxKey
+    assert_eq!(known, 21);
+    assert_eq!(ElementType::from_u8(0x00), None);
+    assert_eq!(ElementType::MinKey.as_u8(), 0xFF);
+    assert_eq!(ElementType::MaxKey.as_u8(), 0x7F);
+    assert_eq!(
+        Bson::Decimal128(crate::Decimal128::from_bytes([0; 16]))
+            .element_type()
+            .as_u8(),
+        0x13
+    );
+}
+
+#[test]
+fn take() {
+    let _guard = LOCK.run_concurrently();
+
+    let mut value = Bson::String("x".into());
+    assert_eq!(value.take(), Bson::String("x".into()));
+    assert_eq!(value, Bson::Null);
+    assert_eq!(value.take(), Bson::Null);
+
+    let mut doc = doc! { "a": 1, "b": { "c": 2 }, "d": 3 };
+    if let Some(b) = doc.get_mut("b") {
+        let inner = b.take();
+        *b = Bson::Array(vec![inner]);
+    }
+    assert_eq!(doc, doc! { "a": 1, "b": [{ "c": 2 }], "d": 3 });
+
+    assert_eq!(doc.take("a"), Some(Bson::Int32(1)));
+    assert_eq!(doc.take("a"), None);
+    assert_eq!(doc.keys().collect::<Vec<_>>(), vec!["b", "d"]);
+}
+
+#[test]
+fn relaxed_extjson_non_finite_policy() {
+    let _guard = LOCK.run_concurrently();
+
+    let policy = |p| RelaxedExtJsonOptions::builder().non_finite(p).build();
+
+    let value = Bson::from(doc! {
+        "nan": f64::NAN,
+        "nested": [1.5, { "inf": f64::NEG_INFINITY }],
+        "code": JavaScriptCodeWithScope {
+            code: "x".into(),
+            scope: doc! { "inf": f64::INFINITY },
+        },
+    });
+
+    assert_eq!(
+        value
+            .clone()
+            .try_into_relaxed_extjson(policy(NonFinitePolicy::default()))
+            .unwrap(),
+        value.clone().into_relaxed_extjson()
+    );
+    assert_eq!(
+        value
+            .clone()
+            .try_into_relaxed_extjson(policy(NonFinitePolicy::Null))
+            .unwrap(),
+        json!({
+            "nan": null,
+            "nested": [1.5, { "inf": null }],
+            "code": { "$code": "x", "$scope": { "inf": null } },
+        })
+    );
+
+    let err = value
+        .try_into_relaxed_extjson(policy(NonFinitePolicy::Error))
+        .unwrap_err();
+    assert!(err.value.is_nan());
+    assert_eq!(
+        Bson::Double(f64::INFINITY).try_into_relaxed_extjson(policy(NonFinitePolicy::Error)),
+        Err(NonFiniteDoubleError {
+            value: f64::INFINITY
+        })
+    );
+    assert_eq!(
+        Bson::Double(-0.5)
+            .try_into_relaxed_extjson(policy(NonFinitePolicy::Error))
+            .unwrap(),
+        json!(-0.5)
+    );
+}
+
+#[test]
+fn classify_extended_and_container() {
+    let _guard = LOCK.run_concurrently();
+
+    let values = [
+        Bson::Double(1.5),
+        Bson::String("s".into()),
+        Bson::Array(vec![]),
+        Bson::Document(doc! {}),
+        Bson::Boolean(true),
+        Bson::Null,
+        Bson::RegularExpression(Regex {
+            pattern: "a".into(),
+            options: "".into(),
+        }),
+        Bson::JavaScriptCode("x".into()),
+        Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope {
+            code: "x".into(),
+            scope: doc! {},
+        }),
+        Bson::Int32(1),
+        Bson::Int64(1),
+        Bson::Timestamp(Timestamp {
+            time: 1,
+            increment: 1,
+        }),
+        Bson::Binary(Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: vec![],
+        }),
+        Bson::ObjectId(ObjectId::new()),
+        Bson::DateTime(DateTime::now()),
+        Bson::Symbol("s".into()),
+        Bson::Decimal128("1".parse().unwrap()),
+        Bson::Undefined,
+        Bson::MaxKey,
+        Bson::MinKey,
+    ];
+    for value in values {
+        let extjson = value.clone().into_relaxed_extjson();
+        let wrapped = extjson.as_object().map_or(false, |obj| {
+            obj.keys().all(|k| k.starts_with('$')) && !obj.is_empty()
+        });
+        assert_eq!(value.is_extended_type(), wrapped, "{:?}", value);
+        assert_eq!(
+            value.is_container(),
+            matches!(
+                value.element_type(),
+                ElementType::Array | ElementType::EmbeddedDocument
+            ),
+            "{:?}",
+            value
+        );
+    }
+}
+
+#[test]
+fn relaxed_extjson_object_id_as_hex() {
+    let _guard = LOCK.run_concurrently();
+
+    let oid = ObjectId::new();
+    let value = Bson::from(doc! { "_id": oid, "refs": [oid], "nan": f64::NAN });
+    let options = RelaxedExtJsonOptions::builder()
+        .object_id_as_hex(true)
+        .build();
+    assert_eq!(
+        value.clone().try_into_relaxed_extjson(options).unwrap(),
+        json!({
+            "_id": oid.to_hex(),
+            "refs": [oid.to_hex()],
+            "nan": { "$numberDouble": "NaN" },
+        })
+    );
+
+    // the output reads back as plain strings
+    let parsed = Bson::try_from(value.try_into_relaxed_extjson(options).unwrap()).unwrap();
+    assert_eq!(
+        parsed.as_document().unwrap().get("_id"),
+        Some(&Bson::String(oid.to_hex()))
+    );
+}
+
+#[test]
+fn json_depth_limit() {
+    let _guard = LOCK.run_concurrently();
+
+    fn nested(depth: usize) -> Value {
+        let mut value = json!(1);
+        for i in 0..depth {
+            // built directly, since `json!` would recursively serialize `value`
+            value = if i % 2 == 0 {
+                Value::Array(vec![value])
+            } else {
+                Value::Object(std::iter::once(("a".to_string(), value)).collect())
+            };
+        }
+        value
+    }
+
+    assert_eq!(
+        Bson::try_from_value_limited(json!(1), 0).unwrap(),
+        Bson::Int32(1)
+    );
+    assert!(Bson::try_from_value_limited(json!([]), 0).is_err());
+    assert!(Bson::try_from_value_limited(nested(5), 5).is_ok());
+    assert!(matches!(
+        Bson::try_from_value_limited(nested(6), 5),
+        Err(crate::extjson::de::Error::DepthLimitExceeded { max_depth: 5 })
+    ));
+
+    // wrapper objects count towards the depth
+    let wrapped = json!({ "a": { "$numberInt": "1" } });
+    assert!(Bson::try_from_value_limited(wrapped.clone(), 1).is_err());
+    assert_eq!(
+        Bson::try_from_value_limited(wrapped, 2).unwrap(),
+        Bson::Document(doc! { "a": 1 })
+    );
+
+    // the default limit matches serde_json's parser
+    let max = crate::extjson::de::DEFAULT_MAX_DEPTH;
+    assert!(Bson::try_from(nested(max)).is_ok());
+    assert!(Bson::try_from(nested(max + 1)).is_err());
+    let object = match nested(max + 2) {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    };
+    assert!(Document::try_from(object.clone()).is_err());
+    assert!(Bson::try_from(object).is_err());
+
+    // rejecting a very deep value must not overflow the stack, including when dropping it
+    assert!(Bson::try_from(nested(100_000)).is_err());
+}
+
+#[test]
+fn decimal128_normalized() {
+    let _guard = LOCK.run_concurrently();
+
+    let canonical = |s: &str| s.parse::<Decimal128>().unwrap().to_canonical_string();
+
+    assert_eq!(canonical("1.000"), "1");
+    assert_eq!(canonical("0.1E+1"), "1");
+    assert_eq!(canonical("100"), "100");
+    assert_eq!(canonical("1200"), "1200");
+    assert_eq!(canonical("1.20E+3"), "1200");
+    assert_eq!(canonical("-12.3400"), "-12.34");
+    assert_eq!(canonical("0.00001230"), "0.0000123");
+    assert_eq!(canonical("0E-6176"), "0");
+    assert_eq!(canonical("-0.00"), "-0");
+    assert_eq!(canonical("-Infinity"), "-Infinity");
+    assert_eq!(canonical("-NaN"), "NaN");
+    assert_eq!(canonical("sNaN"), "NaN");
+
+    // the exponent can't be raised past its maximum, so the zeros are kept
+    assert_eq!(canonical("1000E+6109"), "1.0E+6112");
+
+    // inputs from the decimal128 BSON corpus; trailing zeros are dropped here, whereas the
+    // corpus keeps them in its canonical strings
+    assert_eq!(canonical("0.00123400000"), "0.001234");
+    assert_eq!(canonical("2.000"), "2");
+    assert_eq!(canonical("-0.0"), "-0");
+    assert_eq!(canonical("-1.00E-8"), "-1E-8");
+
+    // inputs from the corpus whose output is the same as the corpus's canonical string
+    assert_eq!(canonical("1E-6176"), "1E-6176");
+    assert_eq!(
+        canonical("1234567890123456789012345678901234"),
+        "1234567890123456789012345678901234"
+    );
+    assert_eq!(
+        canonical("1.2345689012345789012345E+34"),
+        "1.2345689012345789012345E+34"
+    );
+    assert_eq!(
+        canonical("1.000000000000000000000000000000000E+6144"),
+        "1.000000000000000000000000000000000E+6144"
+    );
+    assert_eq!(
+        canonical("0.000001234567890123456789012345678901234"),
+        "0.000001234567890123456789012345678901234"
+    );
+
+    // integers that fit are deliberately written in plain notation, unlike the corpus's
+    // canonical strings "0E+3", "1E+3", "1.050E+4" and "1.05E+3"
+    assert_eq!(canonical("0E+3"), "0");
+    assert_eq!(canonical("1E+3"), "1000");
+    assert_eq!(canonical("1.050E+4"), "10500");
+    assert_eq!(canonical("1.05E+3"), "1050");
+
+    let cohort = ["1.5", "1.50", "15E-1", "0.0015E+3"];
+    let normalized: Vec<_> = cohort
+        .iter()
+        .map(|s| s.parse::<Decimal128>().unwrap().normalized())
+        .collect();
+    assert!(normalized.iter().all(|d| *d == normalized[0]));
+    assert_eq!(normalized[0].normalized(), normalized[0]);
+    assert_eq!(normalized[0].to_string(), "1.5");
+}
+
+#[test]
+fn integer_conversions() {
+    let _guard = LOCK.run_concurrently();
+
+    assert_eq!(Bson::Int32(-7).to_i64(), Some(-7));
+    assert_eq!(Bson::Int64(i64::MIN).to_i64(), Some(i64::MIN));
+    assert_eq!(Bson::Int32(i32::MIN).to_i32_checked(), Some(i32::MIN));
+    assert_eq!(
+        Bson::Int64(i32::MAX.into()).to_i32_checked(),
+        Some(i32::MAX)
+    );
+    assert_eq!(Bson::Int64(i64::from(i32::MAX) + 1).to_i32_checked(), None);
+    assert_eq!(Bson::Int64(i64::from(i32::MIN) - 1).to_i32_checked(), None);
+
+    for other in [Bson::Double(1.0), Bson::String("1".into()), Bson::Null] {
+        assert_eq!(other.to_i64(), None);
+        assert_eq!(other.to_i32_checked(), None);
+    }
+
+    // the variant-exact accessors are unchanged
+    assert_eq!(Bson::Int32(1).as_i64(), None);
+    assert_eq!(Bson::Int64(1).as_i32(), None);
+}
+
+#[test]
+fn code_with_scope_constructors() {
+    let _guard = LOCK.run_concurrently();
+
+    let code = JavaScriptCodeWithScope::new(String::from("x"), doc! { "x": 1 })
+        .with_scope_var("x", "replaced")
+        .with_scope_var(String::from("y"), vec![1, 2]);
+    assert_eq!(
+        code,
+        JavaScriptCodeWithScope {
+            code: "x".into(),
+            scope: doc! { "x": "replaced", "y": [1, 2] },
+        }
+    );
+
+    let raw = crate::RawJavaScriptCodeWithScope::new("x", crate::rawdoc! {})
+        .with_scope_var("x", 1)
+        .with_scope_var("y", true);
+    assert_eq!(raw.code, "x");
+    assert_eq!(raw.scope, crate::rawdoc! { "x": 1, "y": true });
+    let bson = Bson::try_from(crate::RawBson::JavaScriptCodeWithScope(raw)).unwrap();
+    assert_eq!(
+        bson,
+        Bson::JavaScriptCodeWithScope(JavaScriptCodeWithScope::new(
+            "x",
+            doc! { "x": 1, "y": true }
+        ))
+    );
+}
+
+#[test]
+fn extjson_from_str() {
+    let _guard = LOCK.run_concurrently();
+
+    let bson = Bson::Document(doc! {
+        "i": 1,
+        "l": i64::MAX,
+        "d": 1.5,
+        "date": DateTime::from_millis(1_000),
+        "ts": Timestamp { time: 1, increment: 2 },
+        "min": Bson::MinKey,
+    });
+    let canonical = bson.to_canonical_extjson().to_string();
+    let relaxed = bson.to_relaxed_extjson().to_string();
+    assert_eq!(Bson::from_canonical_extjson_str(&canonical).unwrap(), bson);
+    assert_eq!(Bson::from_relaxed_extjson_str(&relaxed).unwrap(), bson);
+    assert_eq!(Bson::from_relaxed_extjson_str(&canonical).unwrap(), bson);
+
+    // relaxed-only forms
+    assert!(Bson::from_canonical_extjson_str(&relaxed).is_err());
+    let err = Bson::from_canonical_extjson_str(r#"{"a": {"b": 5}}"#).unwrap_err();
+    assert!(err.to_string().contains("bare number 5"), "{}", err);
+    let err = Bson::from_canonical_extjson_str(r#"{"d": {"$date": "1970-01-01T00:00:01Z"}}"#)
+        .unwrap_err();
+    assert!(err.to_string().contains("$date"), "{}", err);
+    assert!(Bson::from_canonical_extjson_str("[1.5]").is_err());
+
+    // malformed JSON and extended JSON
+    let err = Bson::from_relaxed_extjson_str("{\n\"a\": }").unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{}", err);
+    assert!(Bson::from_relaxed_extjson_str(r#"{"$numberLong": 5}"#).is_err());
+    assert!(Bson::from_canonical_extjson_str(r#"{"$oid": "zz"}"#).is_err());
+}
+
+#[test]
+fn borrowing_extjson() {
+    let _guard = LOCK.run_concurrently();
+
+    let bson = Bson::Document(doc! {
+        "i": 1,
+        "l": 2_i64,
+        "d": f64::NEG_INFINITY,
+        "s": "string",
+        "date": DateTime::from_millis(-1),
+        "array": [{ "nested": 0.0 }],
+        "regex": Regex::new("^a", "im").unwrap(),
+        "code": JavaScriptCodeWithScope::new("x", doc! { "x": 1 }),
+        "bin": Binary { subtype: BinarySubtype::Generic, bytes: vec![1, 2] },
+        "oid": ObjectId::new(),
+        "decimal": "1.5".parse::<Decimal128>().unwrap(),
+        "undefined": Bson::Undefined,
+    });
+    let relaxed = bson.to_relaxed_extjson();
+    let canonical = bson.to_canonical_extjson();
+    assert_eq!(relaxed, bson.clone().into_relaxed_extjson());
+    assert_eq!(canonical, bson.clone().into_canonical_extjson());
+    assert_eq!(
+        canonical["code"],
+        json!({ "$code": "x", "$scope": { "x": { "$numberInt": "1" } } })
+    );
+    assert_eq!(
+        relaxed["code"],
+        json!({ "$code": "x", "$scope": { "x": 1 } })
+    );
+}
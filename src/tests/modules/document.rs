@@ -1,12 +1,14 @@
 use crate::{
     doc,
-    document::ValueAccessError,
+    document::{KeyError, ValueAccessError},
     oid::ObjectId,
     spec::BinarySubtype,
     tests::LOCK,
+    validate_key,
     Binary,
     Bson,
     Document,
+    KeyPolicy,
     Timestamp,
 };
 use time::OffsetDateTime;
@@ -245,3 +247,340 @@ fn extend() {
         },
     );
 }
+
+#[test]
+fn extend_and_collect_with_conversions() {
+    let _guard = LOCK.run_concurrently();
+    let mut doc = Document::from_pairs(vec![("a", 1), ("b", 2)]);
+    assert_eq!(doc, doc! { "a": 1, "b": 2 });
+
+    doc.extend_pairs(vec![("b".to_string(), "two"), ("c".to_string(), "three")]);
+    doc.extend_pairs(std::iter::once(("d", Bson::Null)));
+    assert_eq!(
+        doc,
+        doc! { "a": 1, "b": "two", "c": "three", "d": Bson::Null }
+    );
+
+    let squares = Document::from_pairs((1..=3).map(|i| (format!("n{}", i), i * i)));
+    assert_eq!(squares, doc! { "n1": 1, "n2": 4, "n3": 9 });
+
+    // collecting `(String, Bson)` pairs still works with conversions inferred inside the closure
+    let collected: Document = vec![("x", 1)]
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect();
+    assert_eq!(collected, doc! { "x": 1 });
+}
+
+#[test]
+fn array_field() {
+    let _guard = LOCK.run_concurrently();
+    let mut doc = doc! { "x": 1 };
+    assert_eq!(doc.array_field("ids", vec![1i64, 2, 3]), None);
+    assert_eq!(
+        doc.array_field("x", ["a", "b"].iter().copied()),
+        Some(Bson::Int32(1))
+    );
+    assert_eq!(doc, doc! { "x": ["a", "b"], "ids": [1i64, 2i64, 3i64] });
+}
+
+#[test]
+fn to_raw_document_buf() {
+    let _guard = LOCK.run_concurrently();
+    let doc = doc! {
+        "double": 2.5,
+        "string": "hello",
+        "document": { "a": 1, "b": [1_i64, "two", { "three": 3 }] },
+        "binary": Binary { subtype: BinarySubtype::BinaryOld, bytes: vec![1, 2, 3] },
+        "oid": ObjectId::new(),
+        "bool": true,
+        "datetime": crate::DateTime::now(),
+        "null": Bson::Null,
+        "regex": Bson::RegularExpression(crate::Regex {
+            pattern: "^a".into(),
+            options: "xi".into(),
+        }),
+        "code": Bson::JavaScriptCode("x".into()),
+        "code_w_scope": Bson::JavaScriptCodeWithScope(crate::JavaScriptCodeWithScope {
+            code: "y".into(),
+            scope: doc! { "y": 1 },
+        }),
+        "timestamp": Timestamp { time: 1, increment: 2 },
+        "decimal": Bson::Decimal128(crate::Decimal128::from_bytes([1; 16])),
+        "undefined": Bson::Undefined,
+        "symbol": Bson::Symbol("s".into()),
+        "min": Bson::MinKey,
+        "max": Bson::MaxKey,
+    };
+
+    let raw = doc.to_raw_document_buf().unwrap();
+    assert_eq!(raw.as_bytes(), crate::to_vec(&doc).unwrap().as_slice());
+
+    let invalid_key = doc! { "a\0b": 1 };
+    assert!(invalid_key.to_raw_document_buf().is_err());
+    let invalid_regex = doc! { "nested": { "r": Bson::RegularExpression(crate::Regex {
+        pattern: "a\0".into(),
+        options: String::new(),
+    }) } };
+    let error = invalid_regex.to_raw_document_buf().unwrap_err();
+    assert_eq!(error.key(), Some("r"));
+}
+
+#[test]
+fn get_case_insensitive() {
+    let _guard = LOCK.run_concurrently();
+    let doc = doc! { "userId": 1, "USERID": 2, "name": "x" };
+    assert_eq!(
+        doc.get_case_insensitive("userid"),
+        Some(("userId", &Bson::Int32(1)))
+    );
+    assert_eq!(
+        doc.get_case_insensitive("NAME"),
+        Some(("name", &Bson::String("x".into())))
+    );
+    assert_eq!(doc.get_case_insensitive("missing"), None);
+    assert!(doc.contains_key_case_insensitive("UserID"));
+    assert!(!doc.contains_key_case_insensitive("user"));
+}
+
+#[test]
+fn iter_mut() {
+    let _guard = LOCK.run_concurrently();
+    let mut doc = doc! { "z": 1, "a": 2, "m": 3 };
+    let mut keys = Vec::new();
+    for (key, value) in doc.iter_mut() {
+        keys.push(key.clone());
+        if let Bson::Int32(i) = value {
+            *i *= 10;
+        }
+    }
+    assert_eq!(keys, vec!["z", "a", "m"]);
+    assert_eq!(doc, doc! { "z": 10, "a": 20, "m": 30 });
+}
+
+#[test]
+fn insert_checked() {
+    let _guard = LOCK.run_concurrently();
+
+    for key in ["", "a", "$a", "a.b", "a$"] {
+        assert_eq!(validate_key(key, KeyPolicy::Bson), Ok(()));
+    }
+    assert!(matches!(
+        validate_key("a\0b", KeyPolicy::Bson),
+        Err(KeyError::InteriorNull { key }) if key == "a\0b"
+    ));
+    assert!(matches!(
+        validate_key("$a", KeyPolicy::Strict),
+        Err(KeyError::LeadingDollar { .. })
+    ));
+    assert!(matches!(
+        validate_key("a.b", KeyPolicy::Strict),
+        Err(KeyError::ContainsDot { .. })
+    ));
+    assert!(matches!(
+        validate_key("$a.\0", KeyPolicy::Strict),
+        Err(KeyError::InteriorNull { .. })
+    ));
+    assert_eq!(validate_key("a$", KeyPolicy::Strict), Ok(()));
+
+    let mut doc = Document::new();
+    assert_eq!(doc.insert_checked("a", 1, KeyPolicy::Strict), Ok(None));
+    assert_eq!(
+        doc.insert_checked("a", 2, KeyPolicy::Strict),
+        Ok(Some(Bson::Int32(1)))
+    );
+    let err = doc.insert_checked("b\0", 3, KeyPolicy::Bson).unwrap_err();
+    assert!(err.to_string().contains("null byte"));
+    assert_eq!(doc, doc! { "a": 2 });
+}
+
+#[test]
+fn partition() {
+    let _guard = LOCK.run_concurrently();
+
+    let doc = doc! { "a": 1, "b": "x", "c": 2, "d": null, "e": 3 };
+    let (ints, others) = doc.clone().partition(|_, v| matches!(v, Bson::Int32(_)));
+    assert_eq!(ints.keys().collect::<Vec<_>>(), vec!["a", "c", "e"]);
+    assert_eq!(others.keys().collect::<Vec<_>>(), vec!["b", "d"]);
+    assert_eq!(others, doc! { "b": "x", "d": null });
+
+    let mut seen = Vec::new();
+    let (all, none) = doc.clone().partition(|k, _| {
+        seen.push(k.to_string());
+        true
+    });
+    assert_eq!(seen, vec!["a", "b", "c", "d", "e"]);
+    assert_eq!(all, doc);
+    assert!(none.is_empty());
+
+    let (empty_a, empty_b) = Document::new().partition(|_, _| true);
+    assert!(empty_a.is_empty() && empty_b.is_empty());
+}
+
+#[test]
+fn rename_key() {
+    let _guard = LOCK.run_concurrently();
+
+    let mut doc = doc! { "a": 1, "b": { "x": [1, 2] }, "c": "three" };
+    assert!(doc.rename_key("a", "first"));
+    assert!(doc.rename_key("c", String::from("last")));
+    assert!(doc.rename_key("b", "middle"));
+    assert_eq!(
+        doc.keys().collect::<Vec<_>>(),
+        vec!["first", "middle", "last"]
+    );
+    assert_eq!(
+        doc,
+        doc! { "first": 1, "middle": { "x": [1, 2] }, "last": "three" }
+    );
+
+    // the document is unchanged when the rename can't be done
+    let before = doc.clone();
+    assert!(!doc.rename_key("missing", "other"));
+    assert!(!doc.rename_key("first", "last"));
+    assert!(!doc.rename_key("missing", "last"));
+    assert!(doc.rename_key("middle", "middle"));
+    assert!(!doc.rename_key("other", "other"));
+    assert_eq!(
+        doc.keys().collect::<Vec<_>>(),
+        before.keys().collect::<Vec<_>>()
+    );
+    assert_eq!(doc, before);
+}
+
+#[test]
+fn replace_values() {
+    let _guard = LOCK.run_concurrently();
+
+    let target = Bson::String("X".to_string());
+    let mut doc = doc! {
+        "a": "X",
+        "b": { "c": "X", "d": ["X", { "e": "X" }, ["X"]] },
+        "f": "x",
+        "g": 1,
+    };
+    assert_eq!(doc.replace_values(&target, &Bson::Int64(0)), 5);
+    assert_eq!(
+        doc,
+        doc! {
+            "a": 0_i64,
+            "b": { "c": 0_i64, "d": [0_i64, { "e": 0_i64 }, [0_i64]] },
+            "f": "x",
+            "g": 1,
+        }
+    );
+    assert_eq!(doc.replace_values(&target, &Bson::Null), 0);
+
+    // comparison is strict, so other numeric types don't match
+    assert_eq!(doc.replace_values(&Bson::Int32(0), &Bson::Null), 0);
+    assert_eq!(doc.replace_values(&Bson::Int32(1), &Bson::Null), 1);
+    assert!(doc.is_null("g"));
+
+    // whole containers can be matched, and replacements aren't searched
+    let mut doc = doc! { "a": [1, 2], "b": { "c": [1, 2] } };
+    let target = Bson::Array(vec![Bson::Int32(1), Bson::Int32(2)]);
+    let replacement = Bson::Array(vec![target.clone()]);
+    assert_eq!(doc.replace_values(&target, &replacement), 2);
+    assert_eq!(doc, doc! { "a": [[1, 2]], "b": { "c": [[1, 2]] } });
+}
+
+#[test]
+fn stable_hash() {
+    let _guard = LOCK.run_concurrently();
+
+    /// Records everything written, so the exact canonical encoding can be compared.
+    #[derive(Default)]
+    struct Recorder(Vec<u8>);
+
+    impl std::hash::Hasher for Recorder {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    fn encode(doc: &Document) -> Vec<u8> {
+        let mut recorder = Recorder::default();
+        doc.stable_hash(&mut recorder);
+        recorder.0
+    }
+
+    let decimal = |s: &str| Bson::Decimal128(s.parse().unwrap());
+
+    // the encoding is pinned so that hashes stay stable across releases
+    assert_eq!(
+        encode(&doc! { "b": true, "a": 1.0 }),
+        [
+            &2u64.to_le_bytes()[..],
+            &1u64.to_le_bytes(),
+            b"a",
+            &[0x12],
+            &1i64.to_le_bytes(),
+            &1u64.to_le_bytes(),
+            b"b",
+            &[0x08, 1],
+        ]
+        .concat()
+    );
+
+    assert_eq!(
+        encode(&doc! { "x": 1, "y": { "p": 0.0, "q": [1, "s"] } }),
+        encode(&doc! { "y": { "q": [1i64, "s"], "p": -0.0 }, "x": decimal("1.00") })
+    );
+    assert_eq!(
+        encode(&doc! { "d": decimal("1.50") }),
+        encode(&doc! { "d": decimal("1.5") })
+    );
+    assert_eq!(
+        encode(&doc! { "d": f64::NAN }),
+        encode(&doc! { "d": decimal("NaN") })
+    );
+    assert_eq!(
+        encode(&doc! { "d": f64::NEG_INFINITY }),
+        encode(&doc! { "d": decimal("-Infinity") })
+    );
+    assert_eq!(encode(&doc! { "d": 1e300 }), encode(&doc! { "d": 1e300 }));
+
+    assert_ne!(encode(&doc! { "a": [1, 2] }), encode(&doc! { "a": [2, 1] }));
+    assert_ne!(
+        encode(&doc! { "a": 1.5 }),
+        encode(&doc! { "a": decimal("1.5") })
+    );
+    assert_ne!(encode(&doc! { "a": "1" }), encode(&doc! { "a": 1 }));
+    assert_ne!(
+        encode(&doc! { "a": f64::INFINITY }),
+        encode(&doc! { "a": f64::NEG_INFINITY })
+    );
+    assert_ne!(encode(&doc! { "ab": "c" }), encode(&doc! { "a": "bc" }));
+}
+
+#[test]
+fn try_into_typed() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    struct Point {
+        x: i32,
+        y: Option<String>,
+    }
+
+    let point: Point = doc! { "x": 1, "y": "a" }.try_into_typed().unwrap();
+    assert_eq!(
+        point,
+        Point {
+            x: 1,
+            y: Some("a".into())
+        }
+    );
+
+    let err = doc! { "x": "one" }.try_into_typed::<Point>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        crate::from_document::<Point>(doc! { "x": "one" })
+            .unwrap_err()
+            .to_string()
+    );
+}
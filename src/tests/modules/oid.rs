@@ -58,3 +58,56 @@ fn fromstr_oid() {
     let actual_s = hex::encode(oid_res.unwrap().bytes());
     assert_eq!(s, &actual_s, "parsed and expected oids differ");
 }
+
+#[test]
+fn ordering_by_timestamp() {
+    let _guard = LOCK.run_concurrently();
+    let earlier = ObjectId::parse_str("5f3a2b10ffffffffffffffff").unwrap();
+    let later = ObjectId::parse_str("5f3a2b1100000000000000ff").unwrap();
+    assert!(earlier < later);
+    assert_eq!(
+        earlier.timestamp().timestamp_millis() + 1000,
+        later.timestamp().timestamp_millis()
+    );
+
+    let mut ids = vec![later, earlier];
+    ids.sort();
+    assert_eq!(ids, vec![earlier, later]);
+}
+
+#[test]
+fn ordering_within_timestamp() {
+    let _guard = LOCK.run_concurrently();
+    let a = ObjectId::parse_str("5f3a2b100000000001000000").unwrap();
+    let b = ObjectId::parse_str("5f3a2b100000000002000000").unwrap();
+    let c = ObjectId::parse_str("5f3a2b100000000002000001").unwrap();
+    assert!(a < b);
+    assert!(b < c);
+
+    let first = ObjectId::new();
+    let second = ObjectId::new();
+    assert!(first < second);
+}
+
+#[test]
+fn oid_from_bytes_conversions() {
+    use crate::oid::Error;
+    use std::convert::{TryFrom, TryInto};
+
+    let _guard = LOCK.run_concurrently();
+
+    let bytes: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+    let oid = ObjectId::from(bytes);
+    assert_eq!(oid, ObjectId::from_bytes(bytes));
+
+    let from_slice: ObjectId = bytes[..].try_into().unwrap();
+    assert_eq!(from_slice, oid);
+
+    for len in [0, 11, 13] {
+        let slice = vec![0u8; len];
+        assert!(matches!(
+            ObjectId::try_from(slice.as_slice()),
+            Err(Error::InvalidByteLength { length }) if length == len
+        ));
+    }
+}
@@ -2,7 +2,18 @@ use std::{collections::BTreeMap, u16, u32, u64, u8};
 
 use assert_matches::assert_matches;
 
-use crate::{from_bson, oid::ObjectId, ser, tests::LOCK, to_bson, to_vec, Bson, Document, Regex};
+use crate::{
+    doc,
+    from_bson,
+    oid::ObjectId,
+    ser,
+    tests::LOCK,
+    to_bson,
+    to_vec,
+    Bson,
+    Document,
+    Regex,
+};
 
 #[test]
 #[allow(clippy::float_cmp)]
@@ -170,3 +181,94 @@ fn cstring_null_bytes_error() {
         ));
     }
 }
+
+#[test]
+fn to_raw_document_buf() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(serde::Serialize)]
+    struct Inner {
+        tags: Vec<&'static str>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Outer {
+        id: ObjectId,
+        inner: Inner,
+        count: Option<i64>,
+    }
+
+    let value = Outer {
+        id: ObjectId::new(),
+        inner: Inner {
+            tags: vec!["a", "b"],
+        },
+        count: None,
+    };
+    let raw = crate::to_raw_document_buf(&value).unwrap();
+    assert_eq!(raw.as_bytes(), to_vec(&value).unwrap().as_slice());
+    assert_eq!(raw.get_object_id("id").unwrap(), value.id);
+    assert_eq!(
+        raw.to_document().unwrap(),
+        crate::to_document(&value).unwrap()
+    );
+
+    // only documents can be serialized to a `RawDocumentBuf`
+    assert!(crate::to_raw_document_buf(&5).is_err());
+}
+
+#[test]
+fn non_string_map_keys() {
+    use std::collections::HashMap;
+
+    let _guard = LOCK.run_concurrently();
+
+    let mut map = HashMap::new();
+    map.insert(1i32, "one".to_string());
+    map.insert(-20i32, "minus twenty".to_string());
+    let expected = doc! { "1": "one", "-20": "minus twenty" };
+
+    let from_raw: Document = crate::from_slice(&to_vec(&map).unwrap()).unwrap();
+    assert_eq!(from_raw, expected);
+    assert_eq!(crate::to_document(&map).unwrap(), expected);
+    assert_eq!(to_bson(&map).unwrap(), Bson::Document(expected));
+
+    let mut scalars = BTreeMap::new();
+    scalars.insert(u64::MAX, 1);
+    let expected = doc! { "18446744073709551615": 1 };
+    assert_eq!(crate::to_document(&scalars).unwrap(), expected);
+    assert_eq!(
+        crate::from_slice::<Document>(&to_vec(&scalars).unwrap()).unwrap(),
+        expected
+    );
+
+    let mut chars = BTreeMap::new();
+    chars.insert('a', 1);
+    chars.insert('b', 2);
+    assert_eq!(crate::to_document(&chars).unwrap(), doc! { "a": 1, "b": 2 });
+
+    let mut bools = BTreeMap::new();
+    bools.insert(true, 1);
+    assert_eq!(crate::to_document(&bools).unwrap(), doc! { "true": 1 });
+
+    // keys that have no natural string form are rejected
+    let mut tuples = BTreeMap::new();
+    tuples.insert((1, 2), 3);
+    assert_matches!(to_vec(&tuples), Err(ser::Error::InvalidDocumentKey(_)));
+    assert_matches!(
+        crate::to_document(&tuples),
+        Err(ser::Error::InvalidDocumentKey(_))
+    );
+
+    let mut options = BTreeMap::new();
+    options.insert(None::<i32>, 1);
+    assert_matches!(to_vec(&options), Err(ser::Error::InvalidDocumentKey(_)));
+    assert_matches!(to_bson(&options), Err(ser::Error::InvalidDocumentKey(_)));
+
+    let mut nul = BTreeMap::new();
+    nul.insert('\0', 1);
+    assert_matches!(to_vec(&nul), Err(ser::Error::InvalidCString(_)));
+    let mut nul = BTreeMap::new();
+    nul.insert("a\0b", 1);
+    assert_matches!(crate::to_document(&nul), Err(ser::Error::InvalidCString(_)));
+}
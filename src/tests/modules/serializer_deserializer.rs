@@ -6,9 +6,16 @@ use std::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    de::from_document,
+    de::{
+        from_document,
+        DeserializerOptions,
+        DuplicateKeyPolicy,
+        RawDeserializerOptions,
+        Utf8Validation,
+    },
     doc,
     oid::ObjectId,
+    raw::{RawArrayBuf, RawBson, RawDocument, RawDocumentBuf},
     ser::Error,
     spec::BinarySubtype,
     tests::LOCK,
@@ -77,6 +84,184 @@ fn test_encode_decode_utf8_string_invalid() {
     assert_eq!(decoded, expected);
 }
 
+#[test]
+fn test_from_slice_with_options_utf8() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, Deserialize)]
+    struct Foo<'a> {
+        key: &'a str,
+        owned: String,
+    }
+
+    let valid = crate::to_vec(&doc! { "key": "ok", "owned": "fine" }).unwrap();
+    let invalid = {
+        let src = unsafe { String::from_utf8_unchecked(b"a\x80".to_vec()) };
+        crate::to_vec(&doc! { "key": "ok", "owned": src }).unwrap()
+    };
+
+    let strict = RawDeserializerOptions::builder().build();
+    assert_eq!(strict.utf8, Utf8Validation::Strict);
+    let foo: Foo = crate::from_slice_with_options(&valid, strict.clone()).unwrap();
+    assert_eq!((foo.key, foo.owned.as_str()), ("ok", "fine"));
+    assert!(crate::from_slice_with_options::<Foo>(&invalid, strict).is_err());
+
+    let lossy = RawDeserializerOptions::builder()
+        .utf8(Utf8Validation::Lossy)
+        .build();
+    let foo: Document = crate::from_slice_with_options(&invalid, lossy).unwrap();
+    assert_eq!(foo, doc! { "key": "ok", "owned": "a\u{FFFD}" });
+
+    // SAFETY: `valid` was produced from Rust strings.
+    let unchecked = unsafe { RawDeserializerOptions::builder().utf8_unchecked() }.build();
+    let foo: Foo = crate::from_slice_with_options(&valid, unchecked).unwrap();
+    assert_eq!((foo.key, foo.owned.as_str()), ("ok", "fine"));
+}
+
+#[test]
+fn test_duplicate_key_policy() {
+    let _guard = LOCK.run_concurrently();
+
+    let mut inner = crate::RawDocumentBuf::new();
+    inner.append("x", 1);
+    inner.append("x", 2);
+    let mut raw = crate::RawDocumentBuf::new();
+    raw.append("a", 1);
+    raw.append("b", "first");
+    raw.append("a", 2);
+    raw.append("nested", inner);
+    let bytes = raw.as_bytes();
+
+    let options = |policy| {
+        RawDeserializerOptions::builder()
+            .duplicate_keys(policy)
+            .build()
+    };
+
+    let doc: Document = crate::from_slice(bytes).unwrap();
+    assert_eq!(doc, doc! { "a": 2, "b": "first", "nested": { "x": 2 } });
+    let doc: Document =
+        crate::from_slice_with_options(bytes, options(DuplicateKeyPolicy::KeepLast)).unwrap();
+    assert_eq!(doc, doc! { "a": 2, "b": "first", "nested": { "x": 2 } });
+
+    let doc: Document =
+        crate::from_slice_with_options(bytes, options(DuplicateKeyPolicy::KeepFirst)).unwrap();
+    assert_eq!(doc, doc! { "a": 1, "b": "first", "nested": { "x": 1 } });
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Foo {
+        a: i32,
+        b: String,
+    }
+    let foo: Foo =
+        crate::from_slice_with_options(bytes, options(DuplicateKeyPolicy::KeepFirst)).unwrap();
+    assert_eq!(
+        foo,
+        Foo {
+            a: 1,
+            b: "first".to_string()
+        }
+    );
+
+    let error =
+        crate::from_slice_with_options::<Document>(bytes, options(DuplicateKeyPolicy::Error))
+            .unwrap_err();
+    assert!(error.to_string().contains("duplicate key `a`"), "{}", error);
+}
+
+#[test]
+fn test_reject_deprecated_types() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Foo {
+        a: i32,
+    }
+
+    let strict = RawDeserializerOptions::builder()
+        .reject_deprecated_types(true)
+        .build();
+
+    let modern = crate::to_vec(&doc! { "a": 1, "b": { "c": [1.5, "d"] } }).unwrap();
+    let foo: Foo = crate::from_slice_with_options(&modern, strict.clone()).unwrap();
+    assert_eq!(foo, Foo { a: 1 });
+
+    let deprecated = [
+        ("Undefined", Bson::Undefined),
+        (
+            "DbPointer",
+            Bson::try_from(json!({
+                "$dbPointer": { "$ref": "db.coll", "$id": { "$oid": "5e0a4b2f4b5b1c0f1c0b0c0a" } }
+            }))
+            .unwrap(),
+        ),
+        ("Symbol", Bson::Symbol("sym".to_string())),
+    ];
+    for (name, value) in deprecated {
+        let value_copy = value.clone();
+        let strict_raw = strict.clone();
+
+        // Deprecated values are rejected even when nested or when the field would be skipped.
+        let bytes = crate::to_vec(&doc! { "a": 1, "b": [{ "c": value }] }).unwrap();
+        crate::from_slice::<Foo>(&bytes).unwrap();
+        crate::from_slice_with_options::<Foo>(&bytes, Default::default()).unwrap();
+
+        let error = crate::from_slice_with_options::<Foo>(&bytes, strict.clone()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("deprecated BSON type {}", name)),
+            "{}",
+            error
+        );
+        crate::from_slice_with_options::<Document>(&bytes, strict.clone()).unwrap_err();
+
+        let doc = crate::from_slice::<Document>(&bytes).unwrap();
+        from_document::<Foo>(doc.clone()).unwrap();
+        let strict = DeserializerOptions::builder()
+            .reject_deprecated_types(true)
+            .build();
+        let error =
+            crate::from_document_with_options::<Foo>(doc.clone(), strict.clone()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("deprecated BSON type {}", name)),
+            "{}",
+            error
+        );
+        crate::from_bson_with_options::<Bson>(Bson::Document(doc.clone()), strict.clone())
+            .unwrap_err();
+
+        // values deserialized into raw types are checked too
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct RawFields {
+            a: i32,
+            b: RawArrayBuf,
+        }
+        crate::from_slice::<RawFields>(&bytes).unwrap();
+        crate::from_slice_with_options::<RawFields>(&bytes, strict_raw.clone()).unwrap_err();
+        crate::from_slice_with_options::<RawDocumentBuf>(&bytes, strict_raw.clone()).unwrap_err();
+        crate::from_slice_with_options::<&RawDocument>(&bytes, strict_raw.clone()).unwrap_err();
+        crate::from_document_with_options::<RawFields>(doc, strict).unwrap_err();
+
+        let scoped = doc! {
+            "a": 1,
+            "b": JavaScriptCodeWithScope { code: "c".to_string(), scope: doc! { "c": value_copy } },
+        };
+        let bytes = crate::to_vec(&scoped).unwrap();
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct RawCode {
+            a: i32,
+            b: RawBson,
+        }
+        crate::from_slice::<RawCode>(&bytes).unwrap();
+        crate::from_slice_with_options::<RawCode>(&bytes, strict_raw.clone()).unwrap_err();
+    }
+}
+
 #[test]
 fn test_serialize_deserialize_array() {
     let _guard = LOCK.run_concurrently();
@@ -571,3 +756,235 @@ fn test_deserialize_invalid_old_binary_length() {
     Document::from_reader(&mut std::io::Cursor::new(buffer))
         .expect_err("expected deserialization to fail");
 }
+
+#[test]
+fn test_peek_document_length() {
+    let _guard = LOCK.run_concurrently();
+    let bytes = crate::to_vec(&doc! { "x": 1 }).unwrap();
+    assert_eq!(crate::peek_document_length(&bytes).unwrap(), bytes.len());
+    assert_eq!(
+        crate::peek_document_length(&bytes[..4]).unwrap(),
+        bytes.len()
+    );
+
+    assert!(matches!(
+        crate::peek_document_length(&bytes[..3]),
+        Err(crate::de::Error::EndOfStream)
+    ));
+    assert!(crate::peek_document_length(&[4, 0, 0, 0, 0]).is_err());
+    assert!(crate::peek_document_length(&(-1i32).to_le_bytes()).is_err());
+    assert_eq!(crate::peek_document_length(&[5, 0, 0, 0, 0]).unwrap(), 5);
+}
+
+#[test]
+fn test_document_frame_len() {
+    use crate::FrameStatus;
+
+    let _guard = LOCK.run_concurrently();
+    let first = crate::to_vec(&doc! { "x": 1 }).unwrap();
+    let second = crate::to_vec(&doc! { "y": "two", "z": [1, 2] }).unwrap();
+    let mut stream = first.clone();
+    stream.extend(&second);
+
+    assert_eq!(
+        crate::document_frame_len(&[]),
+        FrameStatus::Incomplete { needed: 4 }
+    );
+    assert_eq!(
+        crate::document_frame_len(&first[..3]),
+        FrameStatus::Incomplete { needed: 1 }
+    );
+    assert_eq!(
+        crate::document_frame_len(&first[..4]),
+        FrameStatus::Incomplete {
+            needed: first.len() - 4
+        }
+    );
+    assert_eq!(
+        crate::document_frame_len(&stream),
+        FrameStatus::Complete { len: first.len() }
+    );
+    assert_eq!(
+        crate::document_frame_len(&[5, 0, 0, 0, 0]),
+        FrameStatus::Complete { len: 5 }
+    );
+
+    assert_eq!(
+        crate::document_frame_len(&[4, 0, 0, 0]),
+        FrameStatus::Invalid
+    );
+    assert_eq!(
+        crate::document_frame_len(&(-1i32).to_le_bytes()),
+        FrameStatus::Invalid
+    );
+    assert_eq!(
+        crate::document_frame_len(&[5, 0, 0, 0, 1]),
+        FrameStatus::Invalid
+    );
+
+    // Feed the stream in small chunks, splitting off documents as they complete.
+    let mut buffer = Vec::new();
+    let mut docs = Vec::new();
+    for chunk in stream.chunks(3) {
+        buffer.extend_from_slice(chunk);
+        while let FrameStatus::Complete { len } = crate::document_frame_len(&buffer) {
+            docs.push(crate::from_slice::<Document>(&buffer[..len]).unwrap());
+            buffer.drain(..len);
+        }
+        assert_ne!(crate::document_frame_len(&buffer), FrameStatus::Invalid);
+    }
+    assert!(buffer.is_empty());
+    assert_eq!(
+        docs,
+        vec![doc! { "x": 1 }, doc! { "y": "two", "z": [1, 2] }]
+    );
+}
+
+#[test]
+fn test_from_slice_interned() {
+    use crate::de::InternedBson;
+    use std::{collections::HashSet, sync::Arc};
+
+    let _guard = LOCK.run_concurrently();
+
+    let doc = doc! {
+        "category": "books",
+        "n": 1,
+        "nested": { "category": "books", "tags": ["books", "paper", { "category": "misc" }] },
+        "empty": {},
+        "code": JavaScriptCodeWithScope::new("x", doc! { "x": "books" }),
+    };
+    let bytes = crate::to_vec(&doc).unwrap();
+
+    let mut seen: HashSet<Arc<str>> = HashSet::new();
+    let mut calls = 0;
+    let interned = crate::from_slice_interned(&bytes, |s| {
+        calls += 1;
+        match seen.get(s) {
+            Some(shared) => shared.clone(),
+            None => {
+                let shared: Arc<str> = s.into();
+                seen.insert(shared.clone());
+                shared
+            }
+        }
+    })
+    .unwrap();
+    // Keys and string values, but not array indexes or values of other types.
+    assert_eq!(calls, 13);
+    assert_eq!(seen.len(), 9);
+
+    let category = match &interned[0] {
+        (key, InternedBson::String(value)) => {
+            assert_eq!(key.as_ref(), "category");
+            value.clone()
+        }
+        other => panic!("unexpected {:?}", other),
+    };
+    match &interned[2].1 {
+        InternedBson::Document(entries) => {
+            assert!(Arc::ptr_eq(&entries[0].0, &interned[0].0));
+            match (&entries[0].1, &entries[1].1) {
+                (InternedBson::String(a), InternedBson::Array(tags)) => {
+                    assert!(Arc::ptr_eq(a, &category));
+                    assert!(
+                        matches!(&tags[0], InternedBson::String(b) if Arc::ptr_eq(b, &category))
+                    );
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(interned[1].1, InternedBson::Other(Bson::Int32(1)));
+    assert_eq!(
+        Bson::from(InternedBson::Document(interned)),
+        Bson::Document(doc)
+    );
+
+    let mut bytes = crate::to_vec(&doc! { "a": { "s": "abc" } }).unwrap();
+    // the declared length of the string overruns its document
+    bytes[14] = 0x20;
+    let error = crate::from_slice_interned(&bytes, |s| Arc::from(s)).unwrap_err();
+    assert_eq!(error.key(), Some("s"));
+    assert!(crate::from_slice_interned(&[5, 0, 0, 0, 1], |s| Arc::from(s)).is_err());
+}
+
+#[test]
+fn test_oversized_string_length() {
+    let _guard = LOCK.run_concurrently();
+
+    // { "s": <string> } with a declared string length of i32::MAX
+    let mut buffer = crate::to_vec(&doc! { "s": "abc" }).unwrap();
+    buffer[7..11].copy_from_slice(&i32::MAX.to_le_bytes());
+
+    let assert_bounds_error = |err: crate::de::Error| {
+        assert!(
+            err.to_string()
+                .contains("string length exceeds document bounds"),
+            "{}",
+            err
+        );
+    };
+    assert_bounds_error(Document::from_reader(&mut Cursor::new(&buffer)).unwrap_err());
+    assert_bounds_error(crate::from_slice::<Document>(&buffer).unwrap_err());
+    assert_bounds_error(crate::from_slice_utf8_lossy::<Document>(&buffer).unwrap_err());
+
+    // symbols and code are read through a separate path
+    let mut buffer = crate::to_vec(&doc! { "s": Bson::Symbol("abc".into()) }).unwrap();
+    buffer[7..11].copy_from_slice(&i32::MAX.to_le_bytes());
+    assert_bounds_error(crate::from_slice::<Document>(&buffer).unwrap_err());
+    assert_bounds_error(crate::from_slice_utf8_lossy::<Document>(&buffer).unwrap_err());
+
+    // a length that overruns by a single byte is caught too
+    let mut buffer = crate::to_vec(&doc! { "s": "abc" }).unwrap();
+    buffer[7] = 7;
+    assert_bounds_error(crate::from_slice::<Document>(&buffer).unwrap_err());
+
+    // as is one that would swallow the document's null terminator
+    buffer[7] = 5;
+    assert_bounds_error(crate::from_slice::<Document>(&buffer).unwrap_err());
+
+    // lengths are checked against the enclosing document rather than the whole input
+    let mut buffer =
+        crate::to_vec(&doc! { "d": { "s": "abc" }, "t": "more than enough padding" }).unwrap();
+    buffer[14] = 10;
+    assert_bounds_error(crate::from_slice::<Document>(&buffer).unwrap_err());
+}
+
+#[test]
+fn test_code_with_scope_length_mismatch() {
+    let _guard = LOCK.run_concurrently();
+
+    let doc = doc! { "c": JavaScriptCodeWithScope::new("x", doc! { "a": 1 }), "z": 5 };
+    let bytes = crate::to_vec(&doc).unwrap();
+    // the total length follows the element type byte and the key "c"
+    let len = i32::from_le_bytes([bytes[7], bytes[8], bytes[9], bytes[10]]);
+
+    for declared in [len - 4, len - 1, len + 1, len + 4] {
+        let mut buffer = bytes.clone();
+        buffer[7..11].copy_from_slice(&declared.to_le_bytes());
+        let expected = format!(
+            "code with scope length {} does not match the length of its contents ({} bytes)",
+            declared, len
+        );
+
+        let err = crate::from_slice::<Document>(&buffer).unwrap_err();
+        assert!(err.to_string().contains(&expected), "{}", err);
+        let err = Document::from_reader(&mut Cursor::new(&buffer)).unwrap_err();
+        assert!(err.to_string().contains(&expected), "{}", err);
+
+        let raw = crate::RawDocument::from_bytes(&buffer).unwrap();
+        let err = raw.get("c").unwrap_err();
+        assert_eq!(err.key(), Some("c"));
+        assert!(err.to_string().contains(&expected), "{}", err);
+    }
+
+    // a string length that runs past the declared length
+    let mut buffer = bytes;
+    buffer[11..15].copy_from_slice(&100i32.to_le_bytes());
+    let err = crate::from_slice::<Document>(&buffer).unwrap_err();
+    assert!(err.to_string().contains("too short"), "{}", err);
+    let raw = crate::RawDocument::from_bytes(&buffer).unwrap();
+    assert!(raw.get("c").is_err());
+}
@@ -33,7 +33,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     convert::{TryFrom, TryInto},
 };
 
@@ -419,6 +419,32 @@ fn test_serde_bytes() {
     assert_eq!(x, f);
 }
 
+#[test]
+fn test_serde_bytes_borrowed() {
+    let _guard = LOCK.run_concurrently();
+    #[derive(Deserialize, Debug)]
+    pub struct Foo<'a> {
+        #[serde(with = "serde_bytes")]
+        data: &'a [u8],
+        #[serde(borrow)]
+        wrapped: &'a serde_bytes::Bytes,
+    }
+
+    let bytes = crate::to_vec(&doc! {
+        "data": Binary { subtype: BinarySubtype::Generic, bytes: b"12345abcde".to_vec() },
+        "wrapped": Binary { subtype: BinarySubtype::Generic, bytes: b"fghij".to_vec() },
+    })
+    .unwrap();
+    let foo: Foo = crate::from_slice(&bytes).unwrap();
+    assert_eq!(foo.data, b"12345abcde");
+    assert_eq!(foo.wrapped.as_ref(), b"fghij");
+
+    // Both fields should point directly into the input buffer rather than into a copy.
+    let input = bytes.as_ptr_range();
+    assert!(input.contains(&foo.data.as_ptr()));
+    assert!(input.contains(&foo.wrapped.as_ptr()));
+}
+
 #[test]
 fn test_serde_newtype_struct() {
     let _guard = LOCK.run_concurrently();
@@ -788,6 +814,282 @@ fn test_unsigned_helpers() {
     assert!(doc_result.is_err());
 }
 
+#[test]
+fn test_duration_helpers() {
+    use std::time::Duration;
+
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct A {
+        #[serde(with = "serde_helpers::duration_as_i64_millis")]
+        millis: Duration,
+        #[serde(with = "serde_helpers::duration_as_f64_seconds")]
+        secs: Duration,
+    }
+
+    let a = A {
+        millis: Duration::from_millis(1_500),
+        secs: Duration::from_micros(2_250),
+    };
+    let doc = to_document(&a).unwrap();
+    assert_eq!(doc, doc! { "millis": 1_500i64, "secs": 0.00225 });
+    assert_eq!(from_document::<A>(doc.clone()).unwrap(), a);
+    let bytes = crate::to_vec(&doc).unwrap();
+    assert_eq!(crate::from_slice::<A>(&bytes).unwrap(), a);
+
+    // sub-millisecond precision is truncated, and Int32 values are accepted
+    let doc = to_document(&A {
+        millis: Duration::from_micros(2_999),
+        secs: Duration::ZERO,
+    })
+    .unwrap();
+    assert_eq!(doc.get_i64("millis"), Ok(2));
+    let back: A = from_document(doc! { "millis": 7, "secs": 0.0 }).unwrap();
+    assert_eq!(back.millis, Duration::from_millis(7));
+
+    assert!(from_document::<A>(doc! { "millis": -1i64, "secs": 0.0 }).is_err());
+    assert!(to_document(&A {
+        millis: Duration::MAX,
+        secs: Duration::ZERO,
+    })
+    .is_err());
+
+    for secs in [-0.5, f64::NAN, f64::INFINITY, 1e20] {
+        assert!(
+            from_document::<A>(doc! { "millis": 0i64, "secs": secs }).is_err(),
+            "{}",
+            secs
+        );
+    }
+}
+
+#[test]
+fn test_hex_string_helpers() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct A {
+        #[serde(with = "serde_helpers::u64_as_hex_string")]
+        mask: u64,
+        #[serde(with = "serde_helpers::i64_as_hex_string")]
+        value: i64,
+    }
+
+    let a = A {
+        mask: 0x1f,
+        value: -255,
+    };
+    let doc = to_document(&a).unwrap();
+    assert_eq!(doc, doc! { "mask": "0x1f", "value": "-0xff" });
+    assert_eq!(from_document::<A>(doc.clone()).unwrap(), a);
+    let bytes = crate::to_vec(&doc).unwrap();
+    assert_eq!(crate::from_slice::<A>(&bytes).unwrap(), a);
+
+    let extremes = A {
+        mask: u64::MAX,
+        value: i64::MIN,
+    };
+    let doc = to_document(&extremes).unwrap();
+    assert_eq!(
+        doc,
+        doc! { "mask": "0xffffffffffffffff", "value": "-0x8000000000000000" }
+    );
+    assert_eq!(from_document::<A>(doc).unwrap(), extremes);
+
+    // the prefix is optional and digits are case-insensitive
+    let parsed: A = from_document(doc! { "mask": "FF", "value": "0X7fFFffFFffFFffFF" }).unwrap();
+    assert_eq!(
+        parsed,
+        A {
+            mask: 255,
+            value: i64::MAX
+        }
+    );
+
+    for (mask, value) in [
+        ("0xg", "0x0"),
+        ("", "0x0"),
+        ("0x", "0x0"),
+        ("+0x1", "0x0"),
+        ("0x10000000000000000", "0x0"),
+        ("-0x1", "0x0"),
+        ("0x0", "0x8000000000000000"),
+        ("0x0", "-0x8000000000000001"),
+        ("0x0", "--0x1"),
+    ] {
+        assert!(
+            from_document::<A>(doc! { "mask": mask, "value": value }).is_err(),
+            "{} {}",
+            mask,
+            value
+        );
+    }
+    assert!(from_document::<A>(doc! { "mask": 31i64, "value": "0x0" }).is_err());
+}
+
+#[test]
+fn test_binary_subtype_exact() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct A {
+        #[serde(with = "serde_helpers::BinarySubtypeExact::<4>")]
+        id: Binary,
+        #[serde(with = "serde_helpers::BinarySubtypeExact::<0x80>")]
+        custom: Binary,
+    }
+
+    let a = A {
+        id: Binary {
+            subtype: BinarySubtype::Uuid,
+            bytes: vec![1; 16],
+        },
+        custom: Binary {
+            subtype: BinarySubtype::UserDefined(0x80),
+            bytes: vec![1, 2, 3],
+        },
+    };
+    let doc = to_document(&a).unwrap();
+    assert_eq!(from_document::<A>(doc.clone()).unwrap(), a);
+    let bytes = crate::to_vec(&doc).unwrap();
+    assert_eq!(crate::from_slice::<A>(&bytes).unwrap(), a);
+
+    let mut wrong = doc;
+    wrong.insert(
+        "id",
+        Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: vec![1; 16],
+        },
+    );
+    let err = from_document::<A>(wrong.clone()).unwrap_err();
+    assert!(err.to_string().contains("subtype Uuid"), "{}", err);
+    let bytes = crate::to_vec(&wrong).unwrap();
+    assert!(crate::from_slice::<A>(&bytes).is_err());
+    assert!(from_document::<A>(doc! { "id": "not binary", "custom": 1 }).is_err());
+}
+
+#[test]
+fn test_string_or_number_helpers() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct A {
+        #[serde(with = "serde_helpers::string_or_int")]
+        small: i32,
+        #[serde(with = "serde_helpers::string_or_int")]
+        large: i64,
+        #[serde(with = "serde_helpers::string_or_int")]
+        unsigned: u8,
+        #[serde(with = "serde_helpers::string_or_f64")]
+        float: f64,
+    }
+
+    let expected = A {
+        small: -42,
+        large: 1 << 40,
+        unsigned: 255,
+        float: 2.5,
+    };
+    let stringly = doc! {
+        "small": "-42",
+        "large": (1_i64 << 40).to_string(),
+        "unsigned": "255",
+        "float": "2.5",
+    };
+    let native = doc! {
+        "small": -42,
+        "large": 1_i64 << 40,
+        "unsigned": 255,
+        "float": 2.5,
+    };
+    for doc in [&stringly, &native] {
+        assert_eq!(from_document::<A>(doc.clone()).unwrap(), expected);
+        let bytes = crate::to_vec(doc).unwrap();
+        assert_eq!(crate::from_slice::<A>(&bytes).unwrap(), expected);
+    }
+
+    // Serialization always emits the native numeric types.
+    let doc = to_document(&expected).unwrap();
+    assert_eq!(doc.get_i32("small").unwrap(), -42);
+    assert_eq!(doc.get_i64("large").unwrap(), 1 << 40);
+    assert_eq!(doc.get_f64("float").unwrap(), 2.5);
+
+    let from_int = from_document::<A>(doc! {
+        "small": "1", "large": 2_i32, "unsigned": 3_i64, "float": 4_i64,
+    })
+    .unwrap();
+    assert_eq!(from_int.large, 2);
+    assert_eq!(from_int.float, 4.0);
+
+    let invalid = [
+        ("small", Bson::String("4.2".into())),
+        ("small", Bson::String("abc".into())),
+        ("small", Bson::Int64(i64::MAX)),
+        ("unsigned", Bson::String("256".into())),
+        ("unsigned", Bson::Int32(-1)),
+        ("large", Bson::Double(1.0)),
+        ("float", Bson::String("one".into())),
+        ("float", Bson::Boolean(true)),
+    ];
+    for (key, value) in invalid {
+        let mut doc = native.clone();
+        doc.insert(key, value.clone());
+        assert!(
+            from_document::<A>(doc.clone()).is_err(),
+            "{}: {:?}",
+            key,
+            value
+        );
+        let bytes = crate::to_vec(&doc).unwrap();
+        assert!(
+            crate::from_slice::<A>(&bytes).is_err(),
+            "{}: {:?}",
+            key,
+            value
+        );
+    }
+}
+
+#[test]
+fn test_f32_checked() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct A {
+        #[serde(with = "serde_helpers::f32_checked")]
+        num: f32,
+    }
+
+    let a = A { num: 1.5 };
+    let doc = to_document(&a).unwrap();
+    assert_eq!(doc, doc! { "num": 1.5f64 });
+    let back: A = from_document(doc).unwrap();
+    assert_eq!(back, a);
+
+    let back: A = from_document(doc! { "num": 0.1f32 as f64 }).unwrap();
+    assert_eq!(back, A { num: 0.1 });
+
+    let back: A = from_document(doc! { "num": f64::INFINITY }).unwrap();
+    assert_eq!(back, A { num: f32::INFINITY });
+
+    let back: A = from_document(doc! { "num": f64::NAN }).unwrap();
+    assert!(back.num.is_nan());
+
+    // 0.1 is not exactly representable as an f32
+    assert!(from_document::<A>(doc! { "num": 0.1f64 }).is_err());
+    assert!(from_document::<A>(doc! { "num": f64::MAX }).is_err());
+
+    // the unchecked default silently rounds
+    #[derive(Deserialize)]
+    struct B {
+        num: f32,
+    }
+    let b: B = from_document(doc! { "num": 0.1f64 }).unwrap();
+    assert_eq!(b.num, 0.1f32);
+}
+
 #[test]
 fn test_datetime_helpers() {
     use time::{format_description::well_known::Rfc3339, OffsetDateTime};
@@ -920,6 +1222,25 @@ fn test_i64_as_bson_datetime() {
     assert_eq!(doc.get_datetime("now").unwrap(), &now);
     let a: A = from_document(doc).unwrap();
     assert_eq!(a.now, now.timestamp_millis());
+
+    // integer millis are accepted on input and written back out as a datetime
+    for doc in [
+        doc! { "now": 1_591_700_287_095_i64 },
+        doc! { "now": DateTime::from_millis(1_591_700_287_095) },
+    ] {
+        let bytes = crate::to_vec(&doc).unwrap();
+        let a: A = crate::from_slice(&bytes).unwrap();
+        assert_eq!(a.now, 1_591_700_287_095);
+        let a: A = from_document(doc).unwrap();
+        assert_eq!(a.now, 1_591_700_287_095);
+        assert_eq!(
+            to_document(&a).unwrap(),
+            doc! { "now": DateTime::from_millis(1_591_700_287_095) }
+        );
+    }
+    let a: A = from_document(doc! { "now": 5 }).unwrap();
+    assert_eq!(a.now, 5);
+    assert!(from_document::<A>(doc! { "now": "5" }).is_err());
 }
 
 #[test]
@@ -974,8 +1295,20 @@ fn test_uuid_1_helpers() {
         }
         _ => panic!("expected Bson::Binary"),
     }
-    let a: A = from_document(doc).unwrap();
+    let a: A = from_document(doc.clone()).unwrap();
+    assert_eq!(a.uuid, uuid);
+
+    let bytes = crate::to_vec(&a).unwrap();
+    assert_eq!(crate::from_slice::<Document>(&bytes).unwrap(), doc);
+    let a: A = crate::from_slice(&bytes).unwrap();
     assert_eq!(a.uuid, uuid);
+
+    // only binaries with the UUID subtype are accepted
+    let generic = doc! {
+        "uuid": Binary { subtype: BinarySubtype::Generic, bytes: uuid.as_bytes().to_vec() },
+    };
+    assert!(from_document::<A>(generic.clone()).is_err());
+    assert!(crate::from_slice::<A>(&crate::to_vec(&generic).unwrap()).is_err());
 }
 
 #[test]
@@ -1017,6 +1350,34 @@ fn test_timestamp_helpers() {
     };
     let b = B { timestamp };
     assert!(serde_json::to_value(b).is_err());
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct C {
+        #[serde(with = "serde_helpers::u32_pair_as_timestamp")]
+        pub ts: (u32, u32),
+    }
+
+    let c = C { ts: (12345, 7) };
+    let doc = to_document(&c).unwrap();
+    assert_eq!(
+        doc.get_timestamp("ts").unwrap(),
+        Timestamp {
+            time: 12345,
+            increment: 7
+        }
+    );
+    assert_eq!(from_document::<C>(doc.clone()).unwrap(), c);
+    let bytes = crate::to_vec(&doc).unwrap();
+    assert_eq!(crate::from_slice::<C>(&bytes).unwrap(), c);
+    assert!(from_document::<C>(doc! { "ts": [12345, 7] }).is_err());
+
+    let pair: (u32, u32) = Timestamp {
+        time: 1,
+        increment: 2,
+    }
+    .into();
+    assert_eq!(pair, (1, 2));
+    assert_eq!(Timestamp::from(pair).to_u64(), (1 << 32) | 2);
 }
 
 #[test]
@@ -1063,3 +1424,650 @@ fn fuzz_regression_00() {
     let buf: &[u8] = &[227, 0, 35, 4, 2, 0, 255, 255, 255, 127, 255, 255, 255, 47];
     let _ = crate::from_slice::<Document>(buf);
 }
+
+#[test]
+fn option_missing_and_null_equivalent() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Foo {
+        a: Option<i32>,
+    }
+
+    let cases = [
+        (doc! {}, Foo { a: None }),
+        (doc! { "a": Bson::Null }, Foo { a: None }),
+        (doc! { "a": 5 }, Foo { a: Some(5) }),
+    ];
+    for (doc, expected) in cases {
+        let bytes = crate::to_vec(&doc).unwrap();
+        assert_eq!(crate::from_slice::<Foo>(&bytes).unwrap(), expected);
+        assert_eq!(
+            crate::from_bson::<Foo>(Bson::Document(doc.clone())).unwrap(),
+            expected
+        );
+        assert_eq!(from_document::<Foo>(doc).unwrap(), expected);
+    }
+}
+
+#[test]
+fn serialize_sort_keys() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Serialize)]
+    enum Bar {
+        V { y: i32, x: i32 },
+    }
+
+    #[derive(Serialize)]
+    struct Foo {
+        z: i32,
+        a: Document,
+        m: Vec<Document>,
+        n: Vec<i32>,
+        e: Bar,
+    }
+
+    let foo = Foo {
+        z: 1,
+        a: doc! { "y": 1, "b": { "d": 1, "c": 2 } },
+        m: vec![doc! { "q": 1, "p": 2 }, doc! { "o": 1 }],
+        n: vec![3, 1, 2],
+        e: Bar::V { y: 1, x: 2 },
+    };
+    let options = crate::SerializerOptions::builder().sort_keys(true).build();
+    let sorted = crate::to_document_with_options(&foo, options.clone()).unwrap();
+
+    let expected = doc! {
+        "a": { "b": { "c": 2, "d": 1 }, "y": 1 },
+        "e": { "V": { "x": 2, "y": 1 } },
+        "m": [{ "p": 2, "q": 1 }, { "o": 1 }],
+        "n": [3, 1, 2],
+        "z": 1,
+    };
+    let expected_bytes = crate::to_vec(&expected).unwrap();
+    assert_eq!(crate::to_vec(&sorted).unwrap(), expected_bytes);
+
+    // the raw serializer sorts as well
+    assert_eq!(
+        crate::to_vec_with_options(&foo, options).unwrap(),
+        expected_bytes
+    );
+
+    // the default preserves insertion order
+    let unsorted = to_document(&foo).unwrap();
+    assert_eq!(
+        unsorted.keys().collect::<Vec<_>>(),
+        vec!["z", "a", "m", "n", "e"]
+    );
+    let unsorted = crate::RawDocumentBuf::from_bytes(crate::to_vec(&foo).unwrap()).unwrap();
+    assert_eq!(
+        unsorted.iter().map(|e| e.unwrap().0).collect::<Vec<_>>(),
+        vec!["z", "a", "m", "n", "e"]
+    );
+}
+
+#[test]
+fn deserialize_seed() {
+    let _guard = LOCK.run_concurrently();
+
+    use serde::de::DeserializeSeed;
+
+    // Resolves string ids against a table supplied at runtime.
+    struct Resolve<'t>(&'t BTreeMap<String, i32>);
+
+    impl<'de, 't> DeserializeSeed<'de> for Resolve<'t> {
+        type Value = Vec<i32>;
+
+        fn deserialize<D: serde::Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Vec<i32>, D::Error> {
+            #[derive(Deserialize)]
+            struct Refs {
+                ids: Vec<String>,
+            }
+            let refs = Refs::deserialize(deserializer)?;
+            refs.ids
+                .iter()
+                .map(|id| {
+                    self.0
+                        .get(id)
+                        .copied()
+                        .ok_or_else(|| serde::de::Error::custom(format!("unknown id {}", id)))
+                })
+                .collect()
+        }
+    }
+
+    let mut table = BTreeMap::new();
+    table.insert("a".to_string(), 1);
+    table.insert("b".to_string(), 2);
+
+    let doc = doc! { "ids": ["b", "a"] };
+    let bytes = crate::to_vec(&doc).unwrap();
+    assert_eq!(
+        crate::from_slice_seed(&bytes, Resolve(&table)).unwrap(),
+        vec![2, 1]
+    );
+    assert_eq!(
+        crate::from_bson_seed(Bson::Document(doc), Resolve(&table)).unwrap(),
+        vec![2, 1]
+    );
+
+    let missing = crate::to_vec(&doc! { "ids": ["c"] }).unwrap();
+    assert!(crate::from_slice_seed(&missing, Resolve(&table)).is_err());
+}
+
+#[test]
+fn deserialization_error_key() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Item {
+        name: String,
+        price: i32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Order {
+        id: i32,
+        item: Item,
+    }
+
+    let doc = doc! { "id": 1, "item": { "name": "widget", "price": "ten" } };
+
+    let error = from_document::<Order>(doc.clone()).unwrap_err();
+    assert_eq!(error.key(), Some("price"));
+    assert!(error.to_string().contains("field `price`"), "{}", error);
+    // the key is appended to the original message
+    assert!(
+        error
+            .to_string()
+            .starts_with("invalid type: string \"ten\""),
+        "{}",
+        error
+    );
+
+    let bytes = crate::to_vec(&doc).unwrap();
+    let error = crate::from_slice::<Order>(&bytes).unwrap_err();
+    assert_eq!(error.key(), Some("price"));
+    assert!(error.to_string().contains("field `price`"), "{}", error);
+
+    // errors raised for the struct itself are attributed to the field containing it
+    let doc = doc! { "id": 1, "item": { "name": "widget" } };
+    let error = from_document::<Order>(doc.clone()).unwrap_err();
+    assert_eq!(error.key(), Some("item"));
+    let error = crate::from_slice::<Order>(&crate::to_vec(&doc).unwrap()).unwrap_err();
+    assert_eq!(error.key(), Some("item"));
+
+    // top-level errors have no key
+    let error = from_document::<Order>(doc! { "item": { "name": "a", "price": 1 } }).unwrap_err();
+    assert_eq!(error.key(), None);
+}
+
+#[test]
+fn test_system_time_helpers() {
+    use std::time::{Duration, SystemTime};
+
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct A {
+        #[serde(with = "serde_helpers::system_time_as_bson_datetime")]
+        date: SystemTime,
+    }
+
+    let a = A {
+        date: SystemTime::UNIX_EPOCH + Duration::from_millis(1_591_700_287_095),
+    };
+    let doc = to_document(&a).unwrap();
+    assert_eq!(
+        doc.get_datetime("date").unwrap().timestamp_millis(),
+        1_591_700_287_095
+    );
+    assert_eq!(from_document::<A>(doc.clone()).unwrap(), a);
+    let bytes = crate::to_vec(&doc).unwrap();
+    assert_eq!(crate::from_slice::<A>(&bytes).unwrap(), a);
+
+    // before the epoch, with sub-millisecond precision truncated
+    let a = A {
+        date: SystemTime::UNIX_EPOCH - Duration::from_micros(1_500),
+    };
+    let doc = to_document(&a).unwrap();
+    assert_eq!(doc.get_datetime("date").unwrap().timestamp_millis(), -1);
+}
+
+#[test]
+fn dollar_keys_into_map() {
+    let _guard = LOCK.run_concurrently();
+
+    // keys that look like extended JSON must be passed through literally when deserializing into
+    // plain map types, both from raw bytes and from a `Document`
+    let doc = doc! {
+        "$oid": "not an oid",
+        "$date": 5,
+        "$numberLong": { "$numberLong": "12" },
+        "$binary": { "$regularExpression": 1, "x": "y" },
+        "nested": { "$symbol": "abc" },
+    };
+    let bytes = crate::to_vec(&doc).unwrap();
+
+    let from_raw: HashMap<String, serde_json::Value> = crate::from_slice(&bytes).unwrap();
+    let from_doc: HashMap<String, serde_json::Value> = from_document(doc.clone()).unwrap();
+    for map in [&from_raw, &from_doc] {
+        assert_eq!(map.len(), doc.len());
+        assert_eq!(map["$oid"], json!("not an oid"));
+        assert_eq!(map["$date"], json!(5));
+        assert_eq!(map["$numberLong"], json!({ "$numberLong": "12" }));
+        assert_eq!(map["$binary"], json!({ "$regularExpression": 1, "x": "y" }));
+        assert_eq!(map["nested"], json!({ "$symbol": "abc" }));
+    }
+
+    let round_trip = crate::to_vec(&from_raw).unwrap();
+    let round_trip: BTreeMap<String, serde_json::Value> = crate::from_slice(&round_trip).unwrap();
+    assert_eq!(round_trip.into_iter().collect::<HashMap<_, _>>(), from_raw);
+
+    let strings = doc! { "$in": "a", "$regex": "b" };
+    let from_raw: BTreeMap<String, String> =
+        crate::from_slice(&crate::to_vec(&strings).unwrap()).unwrap();
+    let from_doc: BTreeMap<String, String> = from_document(strings).unwrap();
+    for map in [from_raw, from_doc] {
+        assert_eq!(map["$in"], "a");
+        assert_eq!(map["$regex"], "b");
+    }
+}
+
+#[test]
+fn legacy_regex_extjson() {
+    let _guard = LOCK.run_concurrently();
+
+    let expected = Bson::RegularExpression(crate::Regex {
+        pattern: "ab".into(),
+        options: "ix".into(),
+    });
+
+    let legacy = Bson::try_from(json!({ "$regex": "ab", "$options": "xi" })).unwrap();
+    assert_eq!(legacy, expected);
+    let reordered = Bson::try_from(json!({ "$options": "xi", "$regex": "ab" })).unwrap();
+    assert_eq!(reordered, expected);
+
+    let canonical = Bson::try_from(json!({
+        "$regularExpression": { "pattern": "ab", "options": "ix" }
+    }))
+    .unwrap();
+    assert_eq!(canonical, expected);
+
+    let nested = Bson::try_from(json!({ "a": { "$regex": "ab", "$options": "ix" } })).unwrap();
+    assert_eq!(nested, Bson::Document(doc! { "a": expected.clone() }));
+
+    // query operator forms are left as documents
+    let operator = Bson::try_from(json!({
+        "$regex": { "$regularExpression": { "pattern": "ab", "options": "" } },
+        "$options": "ix",
+    }))
+    .unwrap();
+    assert_eq!(
+        operator,
+        Bson::Document(doc! {
+            "$regex": Bson::RegularExpression(crate::Regex { pattern: "ab".into(), options: "".into() }),
+            "$options": "ix",
+        })
+    );
+    let extra = Bson::try_from(json!({ "$regex": "ab", "$options": "ix", "$not": 1 })).unwrap();
+    assert_eq!(
+        extra,
+        Bson::Document(doc! { "$regex": "ab", "$options": "ix", "$not": 1 })
+    );
+    let no_options = Bson::try_from(json!({ "$regex": "ab" })).unwrap();
+    assert_eq!(no_options, Bson::Document(doc! { "$regex": "ab" }));
+
+    // `$regex` query documents are not reinterpreted outside of extended JSON parsing
+    let query = doc! { "name": { "$regex": "^a", "$options": "i" } };
+    let bytes = crate::to_vec(&query).unwrap();
+    assert_eq!(crate::from_slice::<Document>(&bytes).unwrap(), query);
+    assert_eq!(
+        crate::from_slice::<Bson>(&bytes).unwrap(),
+        Bson::Document(query.clone())
+    );
+    assert_eq!(from_document::<Document>(query.clone()).unwrap(), query);
+    assert_eq!(
+        crate::from_bson::<Bson>(Bson::Document(query.clone())).unwrap(),
+        Bson::Document(query.clone())
+    );
+
+    #[derive(Deserialize)]
+    struct Filter {
+        name: Document,
+        any: Bson,
+    }
+    let filter: Filter = from_document(doc! {
+        "name": { "$regex": "^a", "$options": "i" },
+        "any": { "$regex": "^a", "$options": "i" },
+    })
+    .unwrap();
+    assert_eq!(filter.name, doc! { "$regex": "^a", "$options": "i" });
+    assert_eq!(
+        filter.any,
+        Bson::Document(doc! { "$regex": "^a", "$options": "i" })
+    );
+}
+
+#[test]
+fn test_json_number_as_decimal128() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct A {
+        #[serde(with = "serde_helpers::json_number_as_decimal128")]
+        amount: crate::Decimal128,
+    }
+
+    let from_json = |json: serde_json::Value| -> Result<String, serde_json::Error> {
+        serde_json::from_value::<A>(json).map(|a| a.amount.to_string())
+    };
+    assert_eq!(from_json(json!({ "amount": 123.456 })).unwrap(), "123.456");
+    assert_eq!(from_json(json!({ "amount": -0.1 })).unwrap(), "-0.1");
+    assert_eq!(from_json(json!({ "amount": 1e300 })).unwrap(), "1E+300");
+    assert_eq!(from_json(json!({ "amount": 5e-324 })).unwrap(), "5E-324");
+    assert_eq!(from_json(json!({ "amount": -42 })).unwrap(), "-42");
+    assert_eq!(
+        from_json(json!({ "amount": u64::MAX })).unwrap(),
+        u64::MAX.to_string()
+    );
+    assert_eq!(
+        from_json(json!({ "amount": "1234567890.123456789012345678901234" })).unwrap(),
+        "1234567890.123456789012345678901234"
+    );
+    assert!(from_json(json!({ "amount": "abc" })).is_err());
+    assert!(from_json(json!({ "amount": true })).is_err());
+
+    // BSON decimals are accepted and the value is always serialized as a decimal
+    let decimal: crate::Decimal128 = "1.50".parse().unwrap();
+    let doc = doc! { "amount": decimal };
+    let a: A = from_document(doc.clone()).unwrap();
+    assert_eq!(a.amount, decimal);
+    let a: A = crate::from_slice(&crate::to_vec(&doc).unwrap()).unwrap();
+    assert_eq!(a.amount, decimal);
+    assert_eq!(to_document(&a).unwrap(), doc);
+
+    let a: A = from_document(doc! { "amount": 2.5 }).unwrap();
+    assert_eq!(
+        to_document(&a).unwrap(),
+        doc! { "amount": "2.5".parse::<crate::Decimal128>().unwrap() }
+    );
+}
+
+#[test]
+fn enum_errors_list_variants() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    enum Shape {
+        Circle,
+        Square(i32),
+        Rect { w: i32 },
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct S {
+        shape: Shape,
+    }
+
+    let errors = |doc: Document| {
+        let from_doc = from_document::<S>(doc.clone()).unwrap_err().to_string();
+        let from_raw = crate::from_slice::<S>(&crate::to_vec(&doc).unwrap())
+            .unwrap_err()
+            .to_string();
+        [from_doc, from_raw]
+    };
+
+    for doc in [
+        doc! { "shape": "Triangle" },
+        doc! { "shape": { "Triangle": 3 } },
+    ] {
+        for message in errors(doc) {
+            assert!(
+                message.contains(
+                    "unknown variant `Triangle`, expected one of `Circle`, `Square`, `Rect`"
+                ),
+                "{}",
+                message
+            );
+        }
+    }
+
+    for message in errors(doc! { "shape": 5 }) {
+        assert!(
+            message.contains("enum `Shape` with variants `Circle`, `Square`, `Rect`"),
+            "{}",
+            message
+        );
+    }
+
+    let message = from_document::<S>(doc! { "shape": {} })
+        .unwrap_err()
+        .to_string();
+    assert!(
+        message.contains("a variant of enum `Shape` with variants `Circle`, `Square`, `Rect`"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn i128_from_decimal128() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Counter {
+        value: i128,
+    }
+
+    fn decimal(s: &str) -> Bson {
+        Bson::Decimal128(s.parse().unwrap())
+    }
+
+    fn both(doc: Document) -> (crate::de::Result<Counter>, crate::de::Result<Counter>) {
+        let bytes = crate::to_vec(&doc).unwrap();
+        (from_document(doc), crate::from_slice(&bytes))
+    }
+
+    let cases = [
+        ("1234", 1234),
+        ("-1234", -1234),
+        ("1.2E+3", 1200),
+        ("12.000", 12),
+        ("0E-6176", 0),
+        ("-0", 0),
+        (
+            "9999999999999999999999999999999999E+4",
+            99_999_999_999_999_999_999_999_999_999_999_990_000,
+        ),
+        (
+            "-17014118346046923173168730371588E+6",
+            -17_014_118_346_046_923_173_168_730_371_588_000_000,
+        ),
+    ];
+    for (input, expected) in cases {
+        let (value, raw) = both(doc! { "value": decimal(input) });
+        assert_eq!(value.unwrap(), Counter { value: expected }, "{}", input);
+        assert_eq!(raw.unwrap(), Counter { value: expected }, "{}", input);
+    }
+
+    for input in ["1.5", "1E+39", "-1E+39", "NaN", "Infinity", "1E-6176"] {
+        let (value, raw) = both(doc! { "value": decimal(input) });
+        assert!(value.is_err(), "{}", input);
+        assert!(raw.is_err(), "{}", input);
+    }
+
+    // integer BSON types still deserialize as before
+    let (value, raw) = both(doc! { "value": i64::MIN });
+    assert_eq!(value.unwrap().value, i64::MIN as i128);
+    assert_eq!(raw.unwrap().value, i64::MIN as i128);
+}
+
+#[test]
+fn tuple_length_checked() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Point(f64, f64);
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Shape {
+        Line(i32, i32),
+    }
+
+    let coords: [f64; 3] = from_bson(bson!([1.0, 2.0, 3.0])).unwrap();
+    assert_eq!(coords, [1.0, 2.0, 3.0]);
+    let pair: (i32, String) = from_bson(bson!([1, "a"])).unwrap();
+    assert_eq!(pair, (1, "a".to_string()));
+    let empty: [i32; 0] = from_bson(bson!([])).unwrap();
+    assert!(empty.is_empty());
+
+    let message = |result: crate::de::Result<_>| match result {
+        Err(crate::de::Error::DeserializationError { message, .. }) => message,
+        Err(e) => panic!("unexpected error: {:?}", e),
+        Ok(()) => panic!("expected an error"),
+    };
+    assert_eq!(
+        message(from_bson::<[f64; 3]>(bson!([1.0, 2.0])).map(drop)),
+        "expected array of length 3, found 2"
+    );
+    assert_eq!(
+        message(from_bson::<(i32, i32)>(bson!([1, 2, 3])).map(drop)),
+        "expected array of length 2, found 3"
+    );
+    assert_eq!(
+        message(from_bson::<Point>(bson!([1.0])).map(drop)),
+        "expected array of length 2, found 1"
+    );
+    assert_eq!(
+        message(from_document::<Shape>(doc! { "Line": [1] }).map(drop)),
+        "expected array of length 2, found 1"
+    );
+
+    // non-array values are reported as before
+    assert!(from_bson::<[i32; 2]>(Bson::Int32(1)).is_err());
+}
+
+#[test]
+fn borrowed_keys() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Deserialize)]
+    struct Counts<'a> {
+        #[serde(borrow)]
+        counts: HashMap<&'a str, i32>,
+    }
+
+    let bytes = crate::to_vec(&doc! { "counts": { "a": 1, "bb": 2 } }).unwrap();
+    let source = bytes.as_ptr_range();
+
+    let parsed: Counts = crate::from_slice(&bytes).unwrap();
+    assert_eq!(parsed.counts.len(), 2);
+    for (key, expected) in [("a", 1), ("bb", 2)] {
+        let (borrowed, value) = parsed.counts.get_key_value(key).unwrap();
+        assert_eq!(*value, expected);
+        assert!(source.contains(&borrowed.as_ptr()), "{} was copied", key);
+    }
+}
+
+#[test]
+fn internally_tagged_enum_with_bson_types() {
+    let _guard = LOCK.run_concurrently();
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Payload {
+        id: ObjectId,
+        raw: crate::RawDocumentBuf,
+    }
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    #[serde(tag = "kind")]
+    #[allow(clippy::large_enum_variant)]
+    enum Event {
+        Created {
+            id: ObjectId,
+            at: DateTime,
+            uuid: crate::Uuid,
+        },
+        Updated {
+            ts: Timestamp,
+            bin: Binary,
+            amount: crate::Decimal128,
+            changes: Document,
+            previous: Option<Bson>,
+            #[serde(with = "hex_string_as_object_id")]
+            by: String,
+        },
+        Wrapped(Payload),
+        Deleted,
+    }
+
+    let id = ObjectId::new();
+    let at = DateTime::from_millis(1_234);
+    let events = [
+        Event::Created {
+            id,
+            at,
+            uuid: crate::Uuid::new(),
+        },
+        Event::Updated {
+            ts: Timestamp {
+                time: 1,
+                increment: 2,
+            },
+            bin: Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: vec![1, 2, 3],
+            },
+            amount: "1.50".parse().unwrap(),
+            changes: doc! { "id": id, "at": at, "list": [at] },
+            previous: Some(Bson::DateTime(at)),
+            by: id.to_hex(),
+        },
+        Event::Wrapped(Payload {
+            id,
+            raw: crate::rawdoc! {
+                "at": at,
+                "id": id,
+                "long": 5_i64,
+                "ts": Timestamp { time: 3, increment: 4 },
+                "bin": Binary { subtype: BinarySubtype::Generic, bytes: vec![4, 5] },
+                "amount": "2.5".parse::<crate::Decimal128>().unwrap(),
+                "regex": crate::Regex { pattern: "^a".into(), options: "i".into() },
+                "nested": { "list": [at, { "x": 1 }] },
+            },
+        }),
+        Event::Deleted,
+    ];
+
+    for event in events {
+        let doc = to_document(&event).unwrap();
+        assert_eq!(from_document::<Event>(doc.clone()).unwrap(), event);
+        assert_eq!(
+            from_bson::<Event>(Bson::Document(doc.clone())).unwrap(),
+            event
+        );
+        let bytes = crate::to_vec(&doc).unwrap();
+        assert_eq!(crate::from_slice::<Event>(&bytes).unwrap(), event);
+
+        // the tag doesn't have to be the first field
+        let kind = doc.get("kind").unwrap().clone();
+        let mut reordered: Document = doc.into_iter().filter(|(k, _)| k != "kind").collect();
+        reordered.insert("kind", kind);
+        assert_eq!(from_document::<Event>(reordered.clone()).unwrap(), event);
+        let bytes = crate::to_vec(&reordered).unwrap();
+        assert_eq!(crate::from_slice::<Event>(&bytes).unwrap(), event);
+    }
+
+    let unknown = doc! { "kind": "Archived", "id": id };
+    assert!(from_document::<Event>(unknown.clone()).is_err());
+    assert!(crate::from_slice::<Event>(&crate::to_vec(&unknown).unwrap()).is_err());
+}
@@ -440,6 +440,16 @@ fn run_test(test: TestFile) {
 
         let json_to_native_cej: Bson = cej.clone().try_into().expect("cej into bson should work");
 
+        // parsing cEJ directly from the string is equivalent
+        let parsed_cej = Bson::from_canonical_extjson_str(&valid.canonical_extjson)
+            .unwrap_or_else(|e| panic!("{}: {}", description, e));
+        assert_eq!(
+            parsed_cej.into_canonical_extjson(),
+            json_to_native_cej.clone().into_canonical_extjson(),
+            "{}",
+            description,
+        );
+
         let native_to_canonical_extended_json_bson_to_native_cej =
             json_to_native_cej.clone().into_canonical_extjson();
 
@@ -505,11 +515,14 @@ fn run_test(test: TestFile) {
 
         // native_to_relaxed_extended_json( json_to_native(rEJ) ) = rEJ
 
-        if let Some(ref rej) = valid.relaxed_extjson {
-            let rej: serde_json::Value = serde_json::from_str(rej).unwrap();
+        if let Some(ref rej_str) = valid.relaxed_extjson {
+            let rej: serde_json::Value = serde_json::from_str(rej_str).unwrap();
 
             let json_to_native_rej: Bson = rej.clone().try_into().unwrap();
 
+            let parsed_rej = Bson::from_relaxed_extjson_str(rej_str).expect(&description);
+            assert_eq!(parsed_rej.into_relaxed_extjson(), rej, "{}", description,);
+
             let native_to_relaxed_extended_json_bson_to_native_rej =
                 json_to_native_rej.clone().into_relaxed_extjson();
 
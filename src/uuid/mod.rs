@@ -418,6 +418,26 @@ impl Binary {
         }
     }
 
+    /// Creates a BSON binary from the bytes of a UUID in standard (RFC 4122) byte order, stored
+    /// using the given representation. This reorders the bytes as the representation requires and
+    /// uses subtype [`BinarySubtype::Uuid`] for [`UuidRepresentation::Standard`] and
+    /// [`BinarySubtype::UuidOld`] for the legacy representations.
+    ///
+    /// ```
+    /// use bson::{spec::BinarySubtype, Binary, UuidRepresentation};
+    ///
+    /// let bytes = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+    /// let binary = Binary::from_uuid_bytes(bytes, UuidRepresentation::JavaLegacy);
+    /// assert_eq!(binary.subtype, BinarySubtype::UuidOld);
+    /// assert_eq!(
+    ///     binary.bytes,
+    ///     [7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13, 12, 11, 10, 9, 8]
+    /// );
+    /// ```
+    pub fn from_uuid_bytes(bytes: [u8; 16], rep: UuidRepresentation) -> Self {
+        Binary::from_uuid_with_representation(Uuid::from_bytes(bytes), rep)
+    }
+
     /// Deserializes a BSON [`Binary`] type into a [`Uuid`] according to the provided
     /// representation. If the representation does not match the [`Binary`], an error will be
     /// returned.
@@ -471,6 +491,27 @@ impl Binary {
     pub fn to_uuid(&self) -> Result<Uuid> {
         self.to_uuid_with_representation(UuidRepresentation::Standard)
     }
+
+    /// Whether this is a 16-byte binary with the legacy [`BinarySubtype::UuidOld`] subtype. The
+    /// byte order of such a value depends on the driver that wrote it, so it must be read with
+    /// [`Binary::to_uuid_with_representation`] using the matching legacy [`UuidRepresentation`].
+    ///
+    /// ```
+    /// use bson::{Binary, uuid::{Uuid, UuidRepresentation}};
+    ///
+    /// let uuid = Uuid::parse_str("00112233445566778899AABBCCDDEEFF")?;
+    /// let legacy = Binary::from_uuid_with_representation(uuid, UuidRepresentation::JavaLegacy);
+    /// assert!(legacy.is_legacy_uuid());
+    /// assert!(!Binary::from_uuid(uuid).is_legacy_uuid());
+    ///
+    /// // migrate to the standard representation
+    /// let standard = Binary::from_uuid(legacy.to_uuid_with_representation(UuidRepresentation::JavaLegacy)?);
+    /// assert_eq!(standard.to_uuid()?, uuid);
+    /// # Ok::<(), Box::<dyn std::error::Error>>(())
+    /// ```
+    pub fn is_legacy_uuid(&self) -> bool {
+        self.subtype == BinarySubtype::UuidOld && self.bytes.len() == 16
+    }
 }
 
 macro_rules! trait_impls {
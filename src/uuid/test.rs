@@ -290,3 +290,60 @@ fn deserialize_uuid_from_string() {
         from_slice(raw_doc.as_bytes()).expect("failed to deserialize raw document");
     assert_eq!(wrapper.uuid, uuid);
 }
+
+#[test]
+fn is_legacy_uuid() {
+    let uuid = crate::Uuid::parse_str("00112233445566778899AABBCCDDEEFF").unwrap();
+    for rep in [
+        UuidRepresentation::CSharpLegacy,
+        UuidRepresentation::JavaLegacy,
+        UuidRepresentation::PythonLegacy,
+    ] {
+        let bin = Binary::from_uuid_with_representation(uuid, rep);
+        assert!(bin.is_legacy_uuid(), "{:?}", rep);
+        let migrated = Binary::from_uuid(bin.to_uuid_with_representation(rep).unwrap());
+        assert!(!migrated.is_legacy_uuid());
+        assert_eq!(migrated.to_uuid().unwrap(), uuid);
+    }
+
+    assert!(!Binary::from_uuid(uuid).is_legacy_uuid());
+    let short = Binary {
+        subtype: BinarySubtype::UuidOld,
+        bytes: vec![0; 15],
+    };
+    assert!(!short.is_legacy_uuid());
+}
+
+#[test]
+fn from_uuid_bytes() {
+    let uuid = crate::Uuid::parse_str("00112233445566778899AABBCCDDEEFF").unwrap();
+    let expected = [
+        (
+            UuidRepresentation::Standard,
+            "00112233445566778899aabbccddeeff",
+        ),
+        (
+            UuidRepresentation::CSharpLegacy,
+            "33221100554477668899aabbccddeeff",
+        ),
+        (
+            UuidRepresentation::JavaLegacy,
+            "7766554433221100ffeeddccbbaa9988",
+        ),
+        (
+            UuidRepresentation::PythonLegacy,
+            "00112233445566778899aabbccddeeff",
+        ),
+    ];
+    for (rep, hex) in expected {
+        let bin = Binary::from_uuid_bytes(uuid.bytes(), rep);
+        assert_eq!(hex::encode(&bin.bytes), hex, "{:?}", rep);
+        let subtype = if rep == UuidRepresentation::Standard {
+            BinarySubtype::Uuid
+        } else {
+            BinarySubtype::UuidOld
+        };
+        assert_eq!(bin.subtype, subtype);
+        assert_eq!(bin.to_uuid_with_representation(rep).unwrap(), uuid);
+    }
+}